@@ -86,7 +86,7 @@ enum DeleteUserError {
 /// This is typically used for health checks or testing connectivity.
 ///
 /// # Responses
-/// - 200: Returns a hello world message
+/// - 200: Returns a HelloResponse greeting message
 #[api_handler("health")]
 async fn hello() -> Json<HelloResponse> {
     Json(HelloResponse {
@@ -259,38 +259,30 @@ async fn main() {
         println!("GreetResponse schema: {}", GreetResponse::schema());
         return;
     }
-    if std::env::args().any(|arg| arg == "--test-schema") {
-        let mut router = api_router!("Hello World API", "1.0.0")
-            .description("A comprehensive example API demonstrating machined-openapi-gen's automatic OpenAPI generation capabilities. This API showcases various endpoint types, request/response schemas, error handling, and documentation features.")
-            .terms_of_service("https://example.com/terms")
-            .contact(Some("API Support Team"), Some("https://example.com/support"), Some("support@example.com"))
-            .license("MIT", Some("https://opensource.org/licenses/MIT"))
-            .tag("health", Some("Health check and status endpoints"))
-            .tag_with_docs("user", Some("User management operations"), Some("Find out more about user management"), "https://example.com/docs/users")
-            .tag("greeting", Some("Greeting and message endpoints"))
-            .tag("admin", Some("Administrative operations requiring elevated permissions"))
-            .route("/", get(hello))
-            .route("/greet", post(greet))
-            .route("/users/{id}", get(get_user).delete(delete_user))
-            .route("/users", post(create_user_with_errors));
+    let mut router = api_router!("Hello World API", "1.0.0")
+        .description("A comprehensive example API demonstrating machined-openapi-gen's automatic OpenAPI generation capabilities. This API showcases various endpoint types, request/response schemas, error handling, and documentation features.")
+        .terms_of_service("https://example.com/terms")
+        .contact(Some("API Support Team"), Some("https://example.com/support"), Some("support@example.com"))
+        .license("MIT", Some("https://opensource.org/licenses/MIT"))
+        .tag("health", Some("Health check and status endpoints"))
+        .tag_with_docs("user", Some("User management operations"), Some("Find out more about user management"), "https://example.com/docs/users")
+        .tag("greeting", Some("Greeting and message endpoints"))
+        .tag("admin", Some("Administrative operations requiring elevated permissions"))
+        .route("/", get(hello))
+        .route("/greet", post(greet))
+        .route("/users/{id}", get(get_user).delete(delete_user))
+        .route("/users", post(create_user_with_errors));
 
+    if std::env::args().any(|arg| arg == "--test-schema") {
         println!("{}", router.openapi_json());
         return;
     }
 
-    let router = axum::Router::new()
-        .route("/", axum::routing::get(hello))
-        .route("/greet", axum::routing::post(greet))
-        .route("/users/{id}", axum::routing::get(get_user))
-        .route("/users/{id}", axum::routing::delete(delete_user))
-        .route("/users", axum::routing::post(create_user_with_errors));
-
-    let app = router.route(
-        "/openapi.json",
-        axum::routing::get(|| async {
-            r#"{"openapi":"3.0.0","info":{"title":"Hello World API","version":"1.0.0"},"paths":{}}"#
-        }),
-    );
+    let app = router
+        .with_openapi_routes()
+        .with_swagger_ui("/docs")
+        .with_redoc("/redoc")
+        .into_router();
 
     run_server(app).await;
 }
@@ -302,6 +294,8 @@ async fn run_server(app: axum::Router) {
 
     println!("Server running on http://127.0.0.1:3000");
     println!("OpenAPI spec available at: http://127.0.0.1:3000/openapi.json");
+    println!("Swagger UI available at: http://127.0.0.1:3000/docs");
+    println!("ReDoc available at: http://127.0.0.1:3000/redoc");
     println!();
     println!("Available endpoints:");
     println!("  - GET /");
@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 use syn::{
     parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, FnArg, GenericArgument, ItemFn,
     Lit, Meta, PathArguments, ReturnType, Type, Variant,
@@ -304,11 +305,8 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
         }
     }
 
-    let description = if !description_lines.is_empty() {
-        Some(description_lines.join(" "))
-    } else {
-        None
-    };
+    let description_lines: Vec<&str> = description_lines.iter().map(String::as_str).collect();
+    let description = join_description_lines(&description_lines);
 
     ParsedDocs {
         summary,
@@ -357,19 +355,89 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
 /// // Returns: Some("UpdateUserRequest")
 /// ```
 ///
+/// ```ignore
+/// // For this handler with an optional body:
+/// async fn touch_user(Path(id): Path<u32>, body: Option<Json<TouchRequest>>) -> StatusCode
+///
+/// // Returns: Some(("TouchRequest", true))
+/// ```
+///
 /// # Returns
 ///
-/// - `Some(String)` containing the type name if a `Json<T>` parameter is found
+/// - `Some((type_name, optional))` if a `Json<T>` parameter is found, with `optional`
+///   set when it is wrapped in `Option`
 /// - `None` if no JSON request body parameter exists
+///
+/// Axum only allows one body extractor, so a second `Json<T>` parameter is reported as an error
 fn extract_request_body_type(
     inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>,
-) -> Option<String> {
+) -> syn::Result<Option<(String, bool)>> {
+    let mut body_type = None;
     for input in inputs {
         if let FnArg::Typed(pat_type) = input {
-            if let Type::Path(type_path) = &*pat_type.ty {
+            // `Option<Json<T>>` accepts a request without a body
+            let (ty, optional) = match option_inner_type(&pat_type.ty) {
+                Some(inner) => (inner, true),
+                None => (&*pat_type.ty, false),
+            };
+            if let Type::Path(type_path) = ty {
                 // Look for Json<T> pattern
                 if let Some(segment) = type_path.path.segments.last() {
                     if segment.ident == "Json" {
+                        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                            if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
+                                if body_type.is_some() {
+                                    return Err(syn::Error::new_spanned(
+                                        pat_type,
+                                        "a handler can only take one `Json<T>` request body; \
+                                         combine the fields into a single type",
+                                    ));
+                                }
+                                body_type = Some((quote!(#inner_type).to_string(), optional));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(body_type)
+}
+
+/// Extract the type of a `Form<T>` extractor, the URL-encoded counterpart of `Json<T>`
+fn extract_form_body_type(
+    inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>,
+) -> Option<String> {
+    inputs.iter().find_map(|input| {
+        let FnArg::Typed(pat_type) = input else { return None };
+        let Type::Path(type_path) = &*pat_type.ty else { return None };
+        let segment = type_path.path.segments.last().filter(|segment| segment.ident == "Form")?;
+        let PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+        match args.args.first()? {
+            GenericArgument::Type(inner_type) => Some(quote!(#inner_type).to_string()),
+            _ => None,
+        }
+    })
+}
+
+/// Extract the type of a `Query<T>` extractor from function parameters
+///
+/// Its fields are expanded into individual query parameters when the spec is built.
+///
+/// ```ignore
+/// // For this handler:
+/// async fn list_users(Query(filter): Query<UserFilter>) -> Json<Vec<User>>
+///
+/// // Returns: Some("UserFilter")
+/// ```
+fn extract_query_params_type(
+    inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>,
+) -> Option<String> {
+    for input in inputs {
+        if let FnArg::Typed(pat_type) = input {
+            if let Type::Path(type_path) = &*pat_type.ty {
+                if let Some(segment) = type_path.path.segments.last() {
+                    if segment.ident == "Query" {
                         if let PathArguments::AngleBracketed(args) = &segment.arguments {
                             if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
                                 return Some(quote!(#inner_type).to_string());
@@ -383,6 +451,41 @@ fn extract_request_body_type(
     None
 }
 
+/// Get the schema of each value captured by a `Path<T>` extractor, in order
+///
+/// A tuple yields one schema per element; anything else yields a single schema,
+/// which is a `$ref` for a struct whose fields are matched by name.
+///
+/// ```ignore
+/// // For this handler:
+/// async fn get_post(Path((user_id, slug)): Path<(u32, String)>) -> Json<Post>
+///
+/// // Returns: [{"type":"integer","format":"int64"}, {"type":"string"}]
+/// ```
+fn extract_path_param_schemas(
+    inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>,
+) -> Vec<String> {
+    for input in inputs {
+        if let FnArg::Typed(pat_type) = input {
+            if let Type::Path(type_path) = &*pat_type.ty {
+                if let Some(segment) = type_path.path.segments.last() {
+                    if segment.ident == "Path" {
+                        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                            if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
+                                return match inner_type {
+                                    Type::Tuple(tuple) => tuple.elems.iter().map(get_type_schema).collect(),
+                                    _ => vec![get_type_schema(inner_type)],
+                                };
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Vec::new()
+}
+
 /// Check if function parameters include an Authorized parameter
 /// This indicates the endpoint requires authentication
 fn has_authorized_parameter(
@@ -402,37 +505,89 @@ fn has_authorized_parameter(
     false
 }
 
-/// Enhance a JSON schema with examples and defaults from field attributes
+/// Enhance a JSON schema with examples, defaults and constraints from field attributes
 ///
 /// Supports attributes like:
 /// - `#[example = "sample_value"]`
 /// - `#[default = "default_value"]`
 /// - `#[doc = "Field description [example: value, default: value]"]`
+/// - `#[minimum = 0]`, `#[maximum = 120]`, `#[exclusive_minimum]`, `#[exclusive_maximum]`
+/// - `#[min_length = 1]`, `#[max_length = 64]`, `#[pattern = "^[a-z]+$"]`
+/// - `#[min_items = 1]`, `#[max_items = 10]`, `#[unique_items]`
+/// - `#[read_only]`, `#[write_only]`
 fn enhance_schema_with_attributes(
     attrs: &[Attribute],
     base_schema: String,
 ) -> (String, Option<String>) {
     let mut example: Option<String> = None;
     let mut default: Option<String> = None;
+    // Extra keywords as (name, JSON value), in attribute order
+    let mut keywords: Vec<(&str, String)> = Vec::new();
 
     // Check for dedicated attributes first
     for attr in attrs {
         if attr.path().is_ident("example") {
             if let Meta::NameValue(meta) = &attr.meta {
                 if let Expr::Lit(lit) = &meta.value {
-                    if let Lit::Str(s) = &lit.lit {
-                        example = Some(s.value());
-                    }
+                    // `#[example = 42]` and `#[example = true]` work as well as strings
+                    example = match &lit.lit {
+                        Lit::Str(s) => Some(s.value()),
+                        Lit::Int(int) => Some(int.base10_digits().to_string()),
+                        Lit::Float(float) => Some(float.base10_digits().to_string()),
+                        Lit::Bool(flag) => Some(flag.value.to_string()),
+                        _ => example,
+                    };
                 }
             }
         } else if attr.path().is_ident("default") {
             if let Meta::NameValue(meta) = &attr.meta {
                 if let Expr::Lit(lit) = &meta.value {
-                    if let Lit::Str(s) = &lit.lit {
-                        default = Some(s.value());
-                    }
+                    default = match &lit.lit {
+                        Lit::Str(s) => Some(s.value()),
+                        Lit::Int(int) => Some(int.base10_digits().to_string()),
+                        Lit::Float(float) => Some(float.base10_digits().to_string()),
+                        Lit::Bool(flag) => Some(flag.value.to_string()),
+                        _ => default,
+                    };
+                }
+            }
+        } else if attr.path().is_ident("minimum") || attr.path().is_ident("maximum") {
+            if let Meta::NameValue(meta) = &attr.meta {
+                if let Some(bound) = numeric_attribute_value(&meta.value) {
+                    let keyword = if attr.path().is_ident("minimum") { "minimum" } else { "maximum" };
+                    keywords.push((keyword, bound));
+                }
+            }
+        } else if attr.path().is_ident("min_length") || attr.path().is_ident("max_length") {
+            if let Meta::NameValue(meta) = &attr.meta {
+                if let Expr::Lit(syn::ExprLit { lit: Lit::Int(length), .. }) = &meta.value {
+                    let keyword = if attr.path().is_ident("min_length") { "minLength" } else { "maxLength" };
+                    keywords.push((keyword, length.base10_digits().to_string()));
+                }
+            }
+        } else if attr.path().is_ident("pattern") {
+            if let Meta::NameValue(meta) = &attr.meta {
+                if let Expr::Lit(syn::ExprLit { lit: Lit::Str(pattern), .. }) = &meta.value {
+                    keywords.push(("pattern", json_string(&pattern.value())));
+                }
+            }
+        } else if attr.path().is_ident("min_items") || attr.path().is_ident("max_items") {
+            if let Meta::NameValue(meta) = &attr.meta {
+                if let Expr::Lit(syn::ExprLit { lit: Lit::Int(count), .. }) = &meta.value {
+                    let keyword = if attr.path().is_ident("min_items") { "minItems" } else { "maxItems" };
+                    keywords.push((keyword, count.base10_digits().to_string()));
                 }
             }
+        } else if attr.path().is_ident("unique_items") {
+            keywords.push(("uniqueItems", "true".to_string()));
+        } else if attr.path().is_ident("read_only") {
+            keywords.push(("readOnly", "true".to_string()));
+        } else if attr.path().is_ident("write_only") {
+            keywords.push(("writeOnly", "true".to_string()));
+        } else if attr.path().is_ident("exclusive_minimum") {
+            keywords.push(("exclusiveMinimum", "true".to_string()));
+        } else if attr.path().is_ident("exclusive_maximum") {
+            keywords.push(("exclusiveMaximum", "true".to_string()));
         } else if attr.path().is_ident("doc") {
             // Parse doc comments for inline metadata
             if let Meta::NameValue(meta) = &attr.meta {
@@ -472,24 +627,150 @@ fn enhance_schema_with_attributes(
     let mut enhanced_schema = base_schema;
 
     if let Some(example_value) = &example {
-        // Add example to the schema
-        enhanced_schema = enhanced_schema.replace(
-            "}",
-            &format!(",\"example\":\"{}\"}}", example_value.replace("\"", "\\\"")),
-        );
+        let example_json = typed_schema_value(&enhanced_schema, example_value);
+        add_schema_keyword(&mut enhanced_schema, "example", &example_json);
     }
 
     if let Some(default_value) = &default {
-        // Add default to the schema
-        enhanced_schema = enhanced_schema.replace(
-            "}",
-            &format!(",\"default\":\"{}\"}}", default_value.replace("\"", "\\\"")),
-        );
+        let default_json = typed_schema_value(&enhanced_schema, default_value);
+        add_schema_keyword(&mut enhanced_schema, "default", &default_json);
+    }
+
+    for (keyword, value) in &keywords {
+        add_schema_keyword(&mut enhanced_schema, keyword, value);
     }
 
     (enhanced_schema, default.clone())
 }
 
+/// Description lines as Markdown text
+///
+/// Line breaks are kept, so lists and code blocks render as written; a run of
+/// blank lines becomes one paragraph break and blank lines at either end are dropped.
+fn join_description_lines(lines: &[&str]) -> Option<String> {
+    let mut text = String::new();
+    let mut paragraph_break = false;
+    for line in lines {
+        if line.trim().is_empty() {
+            paragraph_break = !text.is_empty();
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str(if paragraph_break { "\n\n" } else { "\n" });
+        }
+        text.push_str(line);
+        paragraph_break = false;
+    }
+    (!text.is_empty()).then_some(text)
+}
+
+/// The first paragraph of a doc comment as one line, without a trailing
+/// `[example: ..., default: ...]` block
+fn doc_description(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(syn::MetaNameValue { value: Expr::Lit(syn::ExprLit { lit: Lit::Str(doc), .. }), .. }) => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| line.trim().to_string())
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect();
+    let text = lines.join(" ");
+
+    let text = match text.rfind('[') {
+        Some(start) if text.ends_with(']') && ["example:", "default:"].iter().any(|key| text[start..].contains(key)) => {
+            text[..start].trim_end()
+        }
+        _ => text.as_str(),
+    };
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Append `"keyword":value` to the outermost object of a schema string
+fn add_schema_keyword(schema: &mut String, keyword: &str, value: &str) {
+    if let Some(end) = schema.rfind('}') {
+        schema.insert_str(end, &format!(",\"{keyword}\":{value}"));
+    }
+}
+
+/// An attribute value as JSON of the type the schema declares
+///
+/// `42` becomes a number for an `integer` or `number` schema and `true` a boolean
+/// for a `boolean` one; arrays and objects are read as JSON. Anything that doesn't
+/// fit the declared type, or a schema without one, is kept as a string.
+fn typed_schema_value(schema: &str, value: &str) -> String {
+    let schema_type = serde_json::from_str::<serde_json::Value>(schema)
+        .ok()
+        .and_then(|schema| schema.get("type").and_then(|t| t.as_str()).map(str::to_string));
+    let parsed = serde_json::from_str::<serde_json::Value>(value.trim()).ok();
+    let typed = match (schema_type.as_deref(), parsed) {
+        (Some("integer"), Some(serde_json::Value::Number(number))) if number.is_i64() || number.is_u64() => Some(number.to_string()),
+        (Some("number"), Some(serde_json::Value::Number(number))) => Some(number.to_string()),
+        (Some("boolean"), Some(serde_json::Value::Bool(flag))) => Some(flag.to_string()),
+        (Some("array"), Some(array @ serde_json::Value::Array(_))) => Some(array.to_string()),
+        (Some("object"), Some(object @ serde_json::Value::Object(_))) => Some(object.to_string()),
+        _ => None,
+    };
+    typed.unwrap_or_else(|| json_string(value))
+}
+
+/// A string as a JSON string literal, escaped
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// A numeric attribute value like `0` or `1.5`, as JSON number text
+///
+/// Attribute values can't be negative literals, so `"-5"` is accepted as a string too.
+fn numeric_attribute_value(expr: &Expr) -> Option<String> {
+    let Expr::Lit(lit) = expr else {
+        return None;
+    };
+    match &lit.lit {
+        Lit::Int(int) => Some(int.base10_digits().to_string()),
+        Lit::Float(float) => Some(float.base10_digits().to_string()),
+        Lit::Str(text) => {
+            let text = text.value().trim().to_string();
+            serde_json::from_str::<serde_json::Number>(&text).ok().map(|_| text)
+        }
+        _ => None,
+    }
+}
+
+/// Read `#[extension("x-name", value)]` attributes into (name, JSON value) pairs
+fn parse_schema_extensions(attrs: &[Attribute]) -> syn::Result<Vec<(String, String)>> {
+    let mut extensions = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("extension")) {
+        let args = attr.parse_args_with(
+            syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated,
+        )?;
+        let (Some(Expr::Lit(name)), Some(value), 2) = (args.first(), args.last(), args.len()) else {
+            return Err(syn::Error::new_spanned(attr, "expected #[extension(\"x-name\", value)]"));
+        };
+        let Lit::Str(name) = &name.lit else {
+            return Err(syn::Error::new_spanned(name, "extension name must be a string"));
+        };
+        if !name.value().starts_with("x-") {
+            return Err(syn::Error::new_spanned(name, "extension names must start with `x-`"));
+        }
+        let value_json = match value {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Str(text) => Some(json_string(&text.value())),
+                Lit::Bool(flag) => Some(flag.value.to_string()),
+                _ => numeric_attribute_value(value),
+            },
+            _ => None,
+        };
+        let Some(value_json) = value_json else {
+            return Err(syn::Error::new_spanned(value, "extension value must be a string, number or boolean"));
+        };
+        extensions.push((name.value(), value_json));
+    }
+    Ok(extensions)
+}
+
 /// Extract the response and error types from a function's return type.
 ///
 /// This function analyzes the return type of a handler function to determine:
@@ -560,23 +841,9 @@ fn extract_response_and_error_types(output: &ReturnType) -> (Option<String>, Opt
                         let mut response_type = None;
                         let mut error_type = None;
 
-                        // First argument is success type
-                        if let Some(GenericArgument::Type(Type::Path(ok_path))) = args.args.first()
-                        {
-                            // Check if it's Json<T>
-                            if let Some(json_segment) = ok_path.path.segments.last() {
-                                if json_segment.ident == "Json" {
-                                    if let PathArguments::AngleBracketed(json_args) =
-                                        &json_segment.arguments
-                                    {
-                                        if let Some(GenericArgument::Type(inner_type)) =
-                                            json_args.args.first()
-                                        {
-                                            response_type = Some(quote!(#inner_type).to_string());
-                                        }
-                                    }
-                                }
-                            }
+                        // First argument is success type: Json<T> or (StatusCode, Json<T>)
+                        if let Some(GenericArgument::Type(ok_type)) = args.args.first() {
+                            response_type = json_payload_type(ok_type);
                         }
 
                         // Second argument is error type
@@ -587,18 +854,474 @@ fn extract_response_and_error_types(output: &ReturnType) -> (Option<String>, Opt
                         return (response_type, error_type);
                     }
                 }
-                // Handle direct Json<T> pattern (no Result wrapper)
-                else if segment.ident == "Json" {
-                    if let PathArguments::AngleBracketed(args) = &segment.arguments {
-                        if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
-                            return (Some(quote!(#inner_type).to_string()), None);
+            }
+        }
+
+        // Handle direct Json<T> or (StatusCode, Json<T>) (no Result wrapper)
+        return (json_payload_type(return_type), None);
+    }
+    (None, None)
+}
+
+/// The `T` in `Json<T>`, also looking inside tuples like `(StatusCode, Json<T>)`
+///
+/// A `Json<Vec<T>>` payload is reported as `Vec<T>` so the generator can emit an array schema.
+fn json_payload_type(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            if segment.ident != "Json" {
+                return None;
+            }
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
+                    if let Some(item_type) = vec_item_type(inner_type) {
+                        return Some(format!("Vec<{}>", quote!(#item_type)));
+                    }
+                    return Some(quote!(#inner_type).to_string());
+                }
+            }
+            None
+        }
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(json_payload_type),
+        _ => None,
+    }
+}
+
+/// The `T` in `Vec<T>`
+fn vec_item_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(item_type)) => Some(item_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the success value is a tuple such as `(StatusCode, Json<T>)`
+fn has_tuple_success_type(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, return_type) = output else {
+        return false;
+    };
+    if let Type::Path(type_path) = &**return_type {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Result" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    return matches!(args.args.first(), Some(GenericArgument::Type(Type::Tuple(_))));
+                }
+            }
+        }
+    }
+    matches!(&**return_type, Type::Tuple(_))
+}
+
+/// HTTP status for a `StatusCode::NAME` constant in the 2xx range
+fn success_status_code(name: &str) -> Option<(u16, &'static str)> {
+    match name {
+        "OK" => Some((200, "OK")),
+        "CREATED" => Some((201, "Created")),
+        "ACCEPTED" => Some((202, "Accepted")),
+        "NON_AUTHORITATIVE_INFORMATION" => Some((203, "Non-Authoritative Information")),
+        "NO_CONTENT" => Some((204, "No Content")),
+        "RESET_CONTENT" => Some((205, "Reset Content")),
+        "PARTIAL_CONTENT" => Some((206, "Partial Content")),
+        _ => None,
+    }
+}
+
+/// Collect the 2xx `StatusCode::NAME` constants used in a token stream
+fn collect_success_status_codes(tokens: proc_macro2::TokenStream, codes: &mut Vec<(u16, &'static str)>) {
+    let tokens: Vec<proc_macro2::TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            proc_macro2::TokenTree::Group(group) => collect_success_status_codes(group.stream(), codes),
+            proc_macro2::TokenTree::Ident(ident) if ident == "StatusCode" => {
+                // StatusCode :: NAME
+                if let (
+                    Some(proc_macro2::TokenTree::Punct(first)),
+                    Some(proc_macro2::TokenTree::Punct(second)),
+                    Some(proc_macro2::TokenTree::Ident(name)),
+                ) = (tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3))
+                {
+                    if first.as_char() == ':' && second.as_char() == ':' {
+                        if let Some(code) = success_status_code(&name.to_string()) {
+                            if !codes.contains(&code) {
+                                codes.push(code);
+                            }
                         }
                     }
                 }
             }
+            _ => {}
         }
     }
-    (None, None)
+}
+
+/// Success status for handlers returning `(StatusCode, Json<T>)`
+///
+/// Uses the 2xx `StatusCode` constant from the body when exactly one appears,
+/// otherwise assumes a create-style handler and defaults to 201.
+fn tuple_success_status(input: &ItemFn) -> Option<(u16, &'static str)> {
+    if !has_tuple_success_type(&input.sig.output) {
+        return None;
+    }
+
+    let block = &input.block;
+    let mut codes = Vec::new();
+    collect_success_status_codes(quote!(#block), &mut codes);
+    match codes.as_slice() {
+        [code] => Some(*code),
+        _ => Some((201, "Created")),
+    }
+}
+
+/// The success value of a handler's return type: `T` for `Result<T, E>`, otherwise the type itself
+fn success_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, return_type) = output else {
+        return None;
+    };
+    if let Type::Path(type_path) = &**return_type {
+        if let Some(segment) = type_path.path.segments.last().filter(|segment| segment.ident == "Result") {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(ok_type)) = args.args.first() {
+                    return Some(ok_type);
+                }
+            }
+        }
+    }
+    Some(return_type)
+}
+
+/// Whether the success type is the named path type, e.g. `StatusCode` in `Result<StatusCode, E>`
+fn has_success_type_named(output: &ReturnType, name: &str) -> bool {
+    matches!(success_type(output), Some(Type::Path(type_path)) if type_path.path.segments.last().is_some_and(|segment| segment.ident == name))
+}
+
+/// Success status for handlers that return a bare `StatusCode`
+///
+/// Such handlers send no body, so unless the body uses exactly one 2xx
+/// `StatusCode` constant they are documented as 204 No Content.
+fn status_code_success_status(input: &ItemFn) -> Option<(u16, &'static str)> {
+    if !has_success_type_named(&input.sig.output, "StatusCode") {
+        return None;
+    }
+
+    let block = &input.block;
+    let mut codes = Vec::new();
+    collect_success_status_codes(quote!(#block), &mut codes);
+    match codes.as_slice() {
+        [code] => Some(*code),
+        _ => Some((204, "No Content")),
+    }
+}
+
+/// Redirect status for handlers returning `axum::response::Redirect`, bare or in a `Result`
+///
+/// Uses the constructor the body calls when only one kind appears, otherwise
+/// documents a 307 Temporary Redirect.
+fn redirect_status(input: &ItemFn) -> Option<(u16, &'static str)> {
+    if !has_success_type_named(&input.sig.output, "Redirect") {
+        return None;
+    }
+
+    let block = &input.block;
+    let mut codes = Vec::new();
+    collect_redirect_status_codes(quote!(#block), &mut codes);
+    match codes.as_slice() {
+        [code] => Some(*code),
+        _ => Some((307, "Temporary Redirect")),
+    }
+}
+
+/// Collect the statuses of the `Redirect::to`, `Redirect::temporary` and
+/// `Redirect::permanent` calls in a token stream
+fn collect_redirect_status_codes(tokens: proc_macro2::TokenStream, codes: &mut Vec<(u16, &'static str)>) {
+    let tokens: Vec<proc_macro2::TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            proc_macro2::TokenTree::Group(group) => collect_redirect_status_codes(group.stream(), codes),
+            proc_macro2::TokenTree::Ident(ident) if ident == "Redirect" => {
+                // Redirect :: constructor
+                if let (
+                    Some(proc_macro2::TokenTree::Punct(first)),
+                    Some(proc_macro2::TokenTree::Punct(second)),
+                    Some(proc_macro2::TokenTree::Ident(name)),
+                ) = (tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3))
+                {
+                    let code = match name.to_string().as_str() {
+                        "to" => Some((303, "See Other")),
+                        "temporary" => Some((307, "Temporary Redirect")),
+                        "permanent" => Some((308, "Permanent Redirect")),
+                        _ => None,
+                    };
+                    if let Some(code) = code.filter(|code| first.as_char() == ':' && second.as_char() == ':' && !codes.contains(code)) {
+                        codes.push(code);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether a response doc entry starts with a status code, a `4XX` style
+/// class or `default`, e.g. "201: Created"
+fn is_status_entry(entry: &str) -> bool {
+    entry.split_once(':').is_some_and(|(code, _)| {
+        let code = code.trim();
+        code == "default"
+            || matches!(code.as_bytes(), [b'1'..=b'5', b'X', b'X'])
+            || (code.len() == 3 && code.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Status code of the most recent response entry, skipping metadata entries
+fn last_status_code(responses: &[String]) -> Option<String> {
+    responses
+        .iter()
+        .rev()
+        .find(|entry| is_status_entry(entry))
+        .and_then(|entry| entry.split_once(':'))
+        .map(|(code, _)| code.trim().to_string())
+}
+
+/// One `#[response(...)]` attribute on a handler
+#[derive(Debug, Default, PartialEq)]
+struct ResponseAttribute {
+    status: String,
+    description: Option<String>,
+    schema: Option<String>,
+    content_type: Option<String>,
+}
+
+/// Parse the handler's `#[response(status = 200, description = "...", schema = "User")]` attributes
+fn parse_response_attributes(attrs: &[Attribute]) -> syn::Result<Vec<ResponseAttribute>> {
+    let mut responses = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("response")) {
+        let mut response = ResponseAttribute::default();
+        attr.parse_nested_meta(|meta| {
+            let key = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+            let value: Lit = meta.value()?.parse()?;
+            let text = match &value {
+                Lit::Int(code) => code.base10_digits().to_string(),
+                Lit::Str(text) => text.value(),
+                _ => return Err(meta.error("expected a string or integer")),
+            };
+            match key.as_str() {
+                "status" => response.status = text,
+                "description" => response.description = Some(text),
+                "schema" => response.schema = Some(text),
+                "content_type" => response.content_type = Some(text),
+                _ => return Err(meta.error("expected `status`, `description`, `schema` or `content_type`")),
+            }
+            Ok(())
+        })?;
+        if !is_status_entry(&format!("{}:", response.status)) {
+            return Err(syn::Error::new_spanned(attr, "`status` must be a status code like 200, a class like \"4XX\" or \"default\""));
+        }
+        responses.push(response);
+    }
+    Ok(responses)
+}
+
+/// Response entries for `#[response]` attributes, in the format doc comments produce
+fn response_attribute_entries(responses: &[ResponseAttribute]) -> Vec<String> {
+    let mut entries = Vec::new();
+    for response in responses {
+        entries.push(format!("{}: {}", response.status, response.description.as_deref().unwrap_or_default()).trim_end().to_string());
+        if let Some(schema) = response.schema.as_ref().filter(|_| response.status != "204") {
+            entries.push(format!("Schema: {} {schema}", response.status));
+        }
+        if let Some(content_type) = &response.content_type {
+            entries.push(format!("ContentType: {} {content_type}", response.status));
+        }
+    }
+    entries
+}
+
+/// A handler's `#[request_body(...)]` attribute
+#[derive(Debug, Default, PartialEq)]
+struct RequestBodyAttribute {
+    schema: Option<String>,
+    content_type: Option<String>,
+    required: Option<bool>,
+}
+
+/// Parse `#[request_body(schema = "CreateUser", content_type = "application/json", required = false)]`
+fn parse_request_body_attribute(attrs: &[Attribute]) -> syn::Result<Option<RequestBodyAttribute>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("request_body")) else {
+        return Ok(None);
+    };
+    let mut request_body = RequestBodyAttribute::default();
+    attr.parse_nested_meta(|meta| {
+        let key = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+        match (key.as_str(), meta.value()?.parse::<Lit>()?) {
+            ("schema", Lit::Str(schema)) => request_body.schema = Some(schema.value()),
+            ("content_type", Lit::Str(content_type)) => request_body.content_type = Some(content_type.value()),
+            ("required", Lit::Bool(required)) => request_body.required = Some(required.value),
+            ("schema" | "content_type", _) => return Err(meta.error("expected a string")),
+            ("required", _) => return Err(meta.error("expected `true` or `false`")),
+            _ => return Err(meta.error("expected `schema`, `content_type` or `required`")),
+        }
+        Ok(())
+    })?;
+    Ok(Some(request_body))
+}
+
+/// Request body entries for a `#[request_body]` attribute
+fn request_body_attribute_entries(request_body: &RequestBodyAttribute) -> Vec<String> {
+    let mut entries = Vec::new();
+    if let Some(schema) = &request_body.schema {
+        entries.push(format!("Schema: {schema}"));
+    }
+    if let Some(content_type) = &request_body.content_type {
+        entries.push(format!("ContentType: {content_type}"));
+    }
+    if let Some(required) = request_body.required {
+        entries.push(format!("Required: {required}"));
+    }
+    entries
+}
+
+/// One `#[callback(...)]` attribute on a handler
+#[derive(Debug, PartialEq)]
+struct CallbackAttribute {
+    name: String,
+    expression: String,
+    method: String,
+    description: Option<String>,
+    schema: Option<String>,
+}
+
+/// Parse the handler's `#[callback(name = "onEvent", expression = "{$request.body#/callbackUrl}",
+/// method = "post", description = "...", schema = "Event")]` attributes
+fn parse_callback_attributes(attrs: &[Attribute]) -> syn::Result<Vec<CallbackAttribute>> {
+    let mut callbacks = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("callback")) {
+        let (mut name, mut expression, mut method, mut description, mut schema) = (None, None, None, None, None);
+        attr.parse_nested_meta(|meta| {
+            let key = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+            let slot = match key.as_str() {
+                "name" => &mut name,
+                "expression" => &mut expression,
+                "method" => &mut method,
+                "description" => &mut description,
+                "schema" => &mut schema,
+                _ => return Err(meta.error("expected `name`, `expression`, `method`, `description` or `schema`")),
+            };
+            match meta.value()?.parse::<Lit>()? {
+                Lit::Str(value) => *slot = Some(value.value()),
+                _ => return Err(meta.error("expected a string")),
+            }
+            Ok(())
+        })?;
+        let Some(name) = name.filter(|name| !name.is_empty() && !name.contains(char::is_whitespace)) else {
+            return Err(syn::Error::new_spanned(attr, "callback needs a `name` without spaces"));
+        };
+        let Some(expression) = expression.filter(|expr| !expr.is_empty() && !expr.contains(char::is_whitespace)) else {
+            return Err(syn::Error::new_spanned(attr, "callback needs an `expression` without spaces"));
+        };
+        let method = method.unwrap_or_else(|| "post".to_string()).to_lowercase();
+        if !matches!(method.as_str(), "get" | "post" | "put" | "delete" | "patch" | "head" | "options" | "trace") {
+            return Err(syn::Error::new_spanned(attr, "callback `method` must be an HTTP method like \"post\""));
+        }
+        callbacks.push(CallbackAttribute { name, expression, method, description, schema });
+    }
+    Ok(callbacks)
+}
+
+/// Callback entries for `#[callback]` attributes
+fn callback_attribute_entries(callbacks: &[CallbackAttribute]) -> Vec<String> {
+    let mut entries = Vec::new();
+    for callback in callbacks {
+        let line = format!("{} {} {}", callback.name, callback.method.to_uppercase(), callback.expression);
+        entries.push(match &callback.description {
+            Some(description) => format!("{line}: {description}"),
+            None => line,
+        });
+        if let Some(schema) = &callback.schema {
+            entries.push(format!("Schema: {} {schema}", callback.name));
+        }
+    }
+    entries
+}
+
+/// Parse `#[external_docs(url = "https://...", description = "...")]` into the
+/// "URL description" form `HandlerDocumentation::external_docs` stores
+fn parse_external_docs_attribute(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("external_docs")) else {
+        return Ok(None);
+    };
+    let mut url = None;
+    let mut description = None;
+    attr.parse_nested_meta(|meta| {
+        let key = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+        match (key.as_str(), meta.value()?.parse::<Lit>()?) {
+            ("url", Lit::Str(value)) => url = Some(value.value()),
+            ("description", Lit::Str(value)) => description = Some(value.value()),
+            ("url" | "description", _) => return Err(meta.error("expected a string")),
+            _ => return Err(meta.error("expected `url` or `description`")),
+        }
+        Ok(())
+    })?;
+    let Some(url) = url.filter(|url| !url.trim().is_empty() && !url.contains(char::is_whitespace)) else {
+        return Err(syn::Error::new_spanned(attr, "external_docs needs a `url` without spaces"));
+    };
+    Ok(Some(match description {
+        Some(description) => format!("{url} {description}"),
+        None => url,
+    }))
+}
+
+/// Link from a "# See Also" doc line, e.g. "- Pagination guide: https://example.com/pagination"
+///
+/// The first http(s) URL on the line is the link; the rest of the line, minus
+/// separators, is its description.
+fn see_also_entry(line: &str) -> Option<String> {
+    let line = line.strip_prefix("- ").unwrap_or(line);
+    let url = line
+        .split_whitespace()
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))?;
+    let description = line
+        .replacen(url, "", 1)
+        .trim()
+        .trim_matches(|c: char| c == ':' || c == '-' || c.is_whitespace())
+        .to_string();
+    let url = url.trim_end_matches(['.', ',', ')']);
+    Some(if description.is_empty() { url.to_string() } else { format!("{url} {description}") })
+}
+
+/// Status from a `success_status = 202` argument to `#[api_handler]`
+///
+/// Handlers whose return type doesn't reveal their success code, such as a bare
+/// `StatusCode` computed at runtime, use this to document it.
+fn parse_success_status_argument(attr: &proc_macro2::TokenStream) -> syn::Result<Option<(u16, &'static str)>> {
+    let attr_str = attr.to_string();
+    let Some(argument) = attr_str.split(',').map(str::trim).find(|s| s.starts_with("success_status")) else {
+        return Ok(None);
+    };
+    let code = argument
+        .strip_prefix("success_status")
+        .and_then(|rest| rest.trim().strip_prefix('='))
+        .and_then(|code| code.trim().parse::<u16>().ok())
+        .filter(|code| (200..300).contains(code));
+    let Some(code) = code else {
+        return Err(syn::Error::new_spanned(attr, "`success_status` must be a 2xx status code like 202"));
+    };
+    let reason = ["OK", "CREATED", "ACCEPTED", "NON_AUTHORITATIVE_INFORMATION", "NO_CONTENT", "RESET_CONTENT", "PARTIAL_CONTENT"]
+        .into_iter()
+        .filter_map(success_status_code)
+        .find(|(known, _)| *known == code)
+        .map_or("Successful response", |(_, reason)| reason);
+    Ok(Some((code, reason)))
 }
 
 /// Simple api_handler attribute that works with current simplified implementation
@@ -607,41 +1330,110 @@ fn extract_response_and_error_types(output: &ReturnType) -> (Option<String>, Opt
 /// - `#[api_handler]` - No tags
 /// - `#[api_handler("tag1")]` - Single tag
 /// - `#[api_handler("tag1", "tag2")]` - Multiple tags
+/// - `#[api_handler(deprecated)]` - Mark the operation deprecated; a `#[deprecated]`
+///   attribute on the handler does the same
+/// - `#[api_handler(public)]` - Opt out of the router's `default_security`
+/// - `#[api_handler(success_status = 202)]` - Document the success code of a handler
+///   whose return type doesn't show it, when the doc comment lists no 2xx response
+/// - `#[api_handler(tag_from_module)]` - Tag the operation with the name of the module
+///   the handler is defined in, unless explicit tags are given. A proc macro can't see
+///   its module path, so the router reads it from `module_path!()` when building the
+///   spec: only the innermost module counts (`api::v2::users` gives `users`), and a
+///   handler at the crate root stays untagged
+///
+/// A `# Security` doc section listing scheme names (`- sessionAuth`), or `- none`,
+/// overrides the router's default security for the operation.
+///
+/// Responses can be declared with `#[response(status = 200, description = "...",
+/// schema = "UserResponse", content_type = "application/json")]`, repeated once per
+/// status and placed below `#[api_handler]`. When any are present they replace the
+/// doc comment's `# Responses` section.
+///
+/// `#[request_body(schema = "CreateUserRequest", content_type = "application/json",
+/// required = false)]` documents the body when the signature can't, e.g. for `Bytes`,
+/// and takes priority over a `Json<T>` extractor.
+///
+/// `#[external_docs(url = "https://...", description = "...")]`, or a URL under a
+/// `# See Also` doc section, links the operation to further documentation.
+///
+/// `#[callback(name = "onEvent", expression = "{$request.body#/callbackUrl}",
+/// method = "post", description = "...", schema = "EventPayload")]` documents a
+/// request the API sends back to the client, e.g. a webhook; `method` defaults to `post`.
 #[proc_macro_attribute]
 pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
+    let mut input = parse_macro_input!(item as ItemFn);
+
+    // `#[response(...)]`, `#[request_body(...)]`, `#[external_docs(...)]` and `#[callback(...)]` are only
+    // read here, so they are removed from the emitted function
+    let response_attributes = match parse_response_attributes(&input.attrs) {
+        Ok(responses) => responses,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let request_body_attribute = match parse_request_body_attribute(&input.attrs) {
+        Ok(request_body) => request_body,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let external_docs_attribute = match parse_external_docs_attribute(&input.attrs) {
+        Ok(external_docs) => external_docs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let callback_attributes = match parse_callback_attributes(&input.attrs) {
+        Ok(callbacks) => callbacks,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    input.attrs.retain(|attr| {
+        !["response", "request_body", "external_docs", "callback"].iter().any(|name| attr.path().is_ident(name))
+    });
+    let success_status = match parse_success_status_argument(&attr.clone().into()) {
+        Ok(success_status) => success_status,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let fn_name = &input.sig.ident;
 
-    // Parse tags from attribute arguments
+    // Parse tags from attribute arguments; a bare `skip` hides the handler from the spec,
+    // a bare `deprecated` marks it deprecated, as Rust's own `#[deprecated]` does, a
+    // bare `public` opts out of the router's default security, and a bare
+    // `tag_from_module` tags an otherwise untagged handler with its module's name
+    let attr_str = attr.to_string();
+    let hidden = attr_str.split(',').any(|s| s.trim() == "skip");
+    let public = attr_str.split(',').any(|s| s.trim() == "public");
+    let tag_from_module = attr_str.split(',').any(|s| s.trim() == "tag_from_module");
+    let deprecated = attr_str.split(',').any(|s| s.trim() == "deprecated")
+        || input.attrs.iter().any(|attr| attr.path().is_ident("deprecated"));
     let tags: Vec<String> = if attr.is_empty() {
         Vec::new()
     } else {
         // Parse comma-separated string literals
-        let attr_str = attr.to_string();
         attr_str
             .split(',')
+            .filter(|s| !matches!(s.trim(), "skip" | "deprecated" | "public" | "tag_from_module") && !s.trim().starts_with("success_status"))
             .map(|s| s.trim().trim_matches('"').trim_matches('\'').to_string())
             .filter(|s| !s.is_empty())
             .collect()
     };
 
-    // Extract documentation from doc comments
-    let mut doc_lines = Vec::new();
+    // Extract documentation from doc comments, keeping blank lines and indentation
+    // (less the space after `///`) for the Markdown description
+    let mut raw_doc_lines = Vec::new();
     for attr in &input.attrs {
         if attr.path().is_ident("doc") {
             if let Meta::NameValue(meta) = &attr.meta {
                 if let Expr::Lit(lit) = &meta.value {
                     if let Lit::Str(s) = &lit.lit {
-                        let line = s.value();
-                        let trimmed = line.trim();
-                        if !trimmed.is_empty() {
-                            doc_lines.push(trimmed.to_string());
+                        for line in s.value().split('\n') {
+                            raw_doc_lines.push(line.strip_prefix(' ').unwrap_or(line).trim_end().to_string());
                         }
                     }
                 }
             }
         }
     }
+    let doc_lines: Vec<String> = raw_doc_lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
 
     let fn_name_str = fn_name.to_string();
     let summary = doc_lines
@@ -650,43 +1442,58 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
         .clone();
 
     // Extract description (everything after summary but before any # sections)
-    let mut description_lines = Vec::new();
-    for (i, line) in doc_lines.iter().enumerate() {
-        if i == 0 {
-            continue; // Skip summary
-        }
-        if line.starts_with("#") {
-            break; // Stop at first section header
-        }
-        if !line.trim().is_empty() {
-            description_lines.push(line.clone());
-        }
-    }
-    let description = if description_lines.is_empty() {
-        "No description".to_string()
-    } else {
-        description_lines.join(" ")
-    };
+    let description_lines: Vec<&str> = raw_doc_lines
+        .iter()
+        .map(String::as_str)
+        .skip_while(|line| line.trim().is_empty())
+        .skip(1) // Skip summary
+        .take_while(|line| !line.trim_start().starts_with('#')) // Stop at first section header
+        .collect();
+    let description = join_description_lines(&description_lines).unwrap_or_else(|| "No description".to_string());
 
     // Simple parameter and response parsing from doc string
     let mut parameters = Vec::new();
     let mut responses = Vec::new();
     let mut request_body = Vec::new();
+    let mut extensions = Vec::new();
+    let mut see_also = None;
+    let mut security_schemes: Option<Vec<String>> = None;
 
     let mut current_section = "";
+    // Status code of the response whose `headers:` or `links:` block is being read, with the entry prefix
+    let mut block_status: Option<(String, &str)> = None;
     for line in &doc_lines {
+        if line.starts_with('#') {
+            block_status = None;
+        }
         if line.starts_with("# Parameters") {
             current_section = "parameters";
         } else if line.starts_with("# Responses") {
             current_section = "responses";
         } else if line.starts_with("# Request Body") {
             current_section = "request_body";
+        } else if line.starts_with("# Extensions") {
+            current_section = "extensions";
+        } else if line.starts_with("# See Also") {
+            current_section = "see_also";
+        } else if line.starts_with("# Security") {
+            current_section = "security";
+            security_schemes.get_or_insert_with(Vec::new);
+        } else if let (Some((status, kind)), Some(entry)) = (
+            block_status.as_ref(),
+            line.strip_prefix("- ").filter(|entry| !is_status_entry(entry)),
+        ) {
+            // Header entries under "headers:", e.g. "- Location (string): URL of the new user",
+            // or link entries under "links:", e.g. "- GetUser (getUser, id = $response.body#/id): The new user"
+            responses.push(format!("{kind}: {status} {entry}"));
         } else if line.starts_with("- ") && current_section == "parameters" {
             let param_line = &line[2..];
 
             // Parse the parameter line to extract name, type, and description
-            // Expected format: "name (type): description"
-            if let Some(paren_start) = param_line.find('(') {
+            // Expected format: "name (type): description", or "name (ref)" for a shared parameter
+            if let Some(name) = param_line.split(':').next().and_then(|left| left.trim().strip_suffix("(ref)")) {
+                parameters.push(format!("ParameterRef: {}", name.trim()));
+            } else if let Some(paren_start) = param_line.find('(') {
                 if let Some(paren_end) = param_line.find(')') {
                     if let Some(colon_pos) = param_line[paren_end..].find(':') {
                         let name = param_line[..paren_start].trim();
@@ -698,8 +1505,12 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
                     }
                 }
             }
+        } else if line.starts_with("- ") && current_section == "extensions" {
+            // Vendor extensions, e.g. "- x-internal: true"
+            extensions.push(line[2..].trim().to_string());
         } else if line.starts_with("- ") && current_section == "responses" {
             let response_line = line[2..].to_string();
+            block_status = None;
 
             // Handle both simple format "- 200: Success" and complex format "- 404:"
             if response_line.contains(":") {
@@ -729,7 +1540,16 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
             && !line.starts_with("- ")
         {
             // Handle YAML-style continuation lines for complex responses
-            if line.trim().starts_with("description:") {
+            if line.trim() == "headers:" {
+                block_status = last_status_code(&responses).map(|status| (status, "Header"));
+            } else if line.trim() == "links:" {
+                block_status = last_status_code(&responses).map(|status| (status, "Link"));
+            } else if let Some(content_types) = line.trim().strip_prefix("Content-Type:") {
+                // Media types the last response can be served as, e.g. "Content-Type: application/json, text/csv"
+                if let Some(status) = last_status_code(&responses) {
+                    responses.push(format!("ContentType: {status} {}", content_types.trim()));
+                }
+            } else if line.trim().starts_with("description:") {
                 let desc = line
                     .trim()
                     .strip_prefix("description:")
@@ -745,20 +1565,67 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
         } else if current_section == "request_body" && !line.starts_with("#") {
             request_body.push(line.clone());
+        } else if let (Some(schemes), Some(scheme)) = (
+            security_schemes.as_mut().filter(|_| current_section == "security"),
+            line.strip_prefix("- "),
+        ) {
+            // "- none" documents the operation as public
+            let scheme = scheme.trim();
+            if scheme != "none" {
+                schemes.push(scheme.to_string());
+            }
+        } else if current_section == "see_also" && see_also.is_none() {
+            // OpenAPI allows one link per operation, so the first URL wins
+            see_also = see_also_entry(line);
         }
     }
 
+    // An `#[external_docs]` attribute wins over the doc comment
+    let external_docs = external_docs_attribute.or(see_also).unwrap_or_default();
+
+    // `public` and a "# Security" section are the handler's own word on auth; an
+    // empty string leaves it to the extractors and the router's default
+    let security = match (public, security_schemes) {
+        (true, _) => "[]".to_string(),
+        (false, Some(schemes)) => format!(
+            "[{}]",
+            schemes.iter().map(|s| format!("\"{}\"", s.replace('"', "\\\""))).collect::<Vec<_>>().join(",")
+        ),
+        (false, None) => String::new(),
+    };
+
     // Extract type information from function signature
-    let request_body_type = extract_request_body_type(&input.sig.inputs);
-    let (_response_type, error_type) = extract_response_and_error_types(&input.sig.output);
+    let request_body_type = match extract_request_body_type(&input.sig.inputs) {
+        Ok(request_body_type) => request_body_type,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let form_body_type = extract_form_body_type(&input.sig.inputs);
+    let (response_type, error_type) = extract_response_and_error_types(&input.sig.output);
+    let signature_status = success_status
+        .or_else(|| tuple_success_status(&input))
+        .or_else(|| status_code_success_status(&input));
     let requires_auth = has_authorized_parameter(&input.sig.inputs);
+    let query_params_type = extract_query_params_type(&input.sig.inputs);
+    let path_param_schemas = extract_path_param_schemas(&input.sig.inputs);
 
     // Include type information in the request body documentation
     let mut enhanced_request_body = request_body.clone();
-    if let Some(ref req_type) = request_body_type {
+    let explicit_schema = request_body_attribute.as_ref().is_some_and(|attr| attr.schema.is_some());
+    if let Some((req_type, _)) = request_body_type.as_ref().filter(|_| !explicit_schema) {
         // Add the type name to the beginning of the request body documentation
         enhanced_request_body.insert(0, format!("Type: {req_type}"));
     }
+    if request_body_type.as_ref().is_some_and(|(_, optional)| *optional) {
+        enhanced_request_body.push("Required: false".to_string());
+    }
+    if let Some(ref form_type) = form_body_type.filter(|_| !explicit_schema && request_body_type.is_none()) {
+        enhanced_request_body.insert(0, format!("Type: {form_type}"));
+        enhanced_request_body.insert(1, "ContentType: application/x-www-form-urlencoded".to_string());
+    }
+    if let Some(ref attr) = request_body_attribute {
+        // A declared #[request_body] takes priority over the signature
+        enhanced_request_body.splice(0..0, request_body_attribute_entries(attr));
+    }
 
     // Don't add authentication header parameter anymore - it will be handled by securitySchemes
     // Instead, add a special marker that the OpenAPI generator can detect
@@ -766,6 +1633,17 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     if requires_auth {
         enhanced_parameters.insert(0, "__REQUIRES_AUTH__".to_string());
     }
+    if let Some(ref query_type) = query_params_type {
+        enhanced_parameters.push(format!("QueryType: {query_type}"));
+    }
+    for schema in &path_param_schemas {
+        enhanced_parameters.push(format!("PathSchema: {schema}"));
+    }
+
+    // Declared `#[response]` attributes replace whatever the doc comment listed
+    if !response_attributes.is_empty() {
+        responses = response_attribute_entries(&response_attributes);
+    }
 
     // Enhance responses with error type information and add standard errors if needed
     let mut enhanced_responses = responses.clone();
@@ -777,12 +1655,58 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
 
+    // Tuple and bare StatusCode handlers pick their own status code, unless `success_status`
+    // names it; document it if no 2xx response is
+    if let Some((code, reason)) = signature_status {
+        let has_success = enhanced_responses.iter().any(|r| r.starts_with('2'));
+        if !has_success {
+            enhanced_responses.insert(0, format!("{code}: {reason}"));
+        }
+    }
+
+    // Redirect handlers document their 3xx status along with the Location header it carries
+    if let Some((code, reason)) = redirect_status(&input) {
+        let documented = enhanced_responses
+            .iter()
+            .filter(|r| is_status_entry(r))
+            .find_map(|r| r.split_once(':').map(|(code, _)| code.trim().to_string()).filter(|code| code.starts_with('3')));
+        let code = match documented {
+            Some(code) => code,
+            None => {
+                enhanced_responses.insert(0, format!("{code}: {reason}"));
+                code.to_string()
+            }
+        };
+        if !enhanced_responses.iter().any(|r| r.starts_with(&format!("Header: {code} Location "))) {
+            enhanced_responses.push(format!("Header: {code} Location (string): URL the client is redirected to"));
+        }
+    }
+
+    // Server-sent events stream from the first success response, so document its media type
+    if has_success_type_named(&input.sig.output, "Sse") {
+        let code = match enhanced_responses.iter().filter(|r| is_status_entry(r)).find(|r| r.starts_with('2')) {
+            Some(entry) => entry.split_once(':').map(|(code, _)| code.trim().to_string()).unwrap_or_default(),
+            None => {
+                enhanced_responses.insert(0, "200: Stream of server-sent events".to_string());
+                "200".to_string()
+            }
+        };
+        if !enhanced_responses.iter().any(|r| r.starts_with(&format!("ContentType: {code} "))) {
+            enhanced_responses.push(format!("ContentType: {code} text/event-stream"));
+        }
+    }
+
     // Always add 500 Internal Server Error if not already present
     let has_500 = enhanced_responses.iter().any(|r| r.starts_with("500"));
     if !has_500 {
         enhanced_responses.push("500: Internal server error occurred".to_string());
     }
 
+    if let Some(ref resp_type) = response_type {
+        // Add success type information to the responses
+        enhanced_responses.push(format!("ResponseType: {resp_type}"));
+    }
+
     if let Some(ref err_type) = error_type {
         // Add error type information to the responses
         enhanced_responses.push(format!("ErrorType: {err_type}"));
@@ -820,6 +1744,24 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
             .join(",")
     );
 
+    let extensions_json = format!(
+        "[{}]",
+        extensions
+            .iter()
+            .map(|e| format!("\"{}\"", e.replace("\"", "\\\"")))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    let callbacks_json = format!(
+        "[{}]",
+        callback_attribute_entries(&callback_attributes)
+            .iter()
+            .map(|c| format!("\"{}\"", c.replace("\"", "\\\"")))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
     let output = quote! {
         #input
 
@@ -827,12 +1769,20 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
         machined_openapi_gen::inventory::submit! {
             machined_openapi_gen::HandlerDocumentation {
                 function_name: #fn_name_str,
+                module_path: ::core::module_path!(),
+                hidden: #hidden,
+                deprecated: #deprecated,
                 summary: #summary,
                 description: #description,
                 parameters: #parameters_json,
                 responses: #responses_json,
                 request_body: #request_body_json,
                 tags: #tags_json,
+                tag_from_module: #tag_from_module,
+                extensions: #extensions_json,
+                external_docs: #external_docs,
+                security: #security,
+                callbacks: #callbacks_json,
             }
         }
     };
@@ -910,6 +1860,41 @@ fn parse_rename_all(attrs: &[Attribute]) -> RenameAll {
     RenameAll::None
 }
 
+/// Parse a field-level serde `rename = "..."` attribute
+fn parse_serde_rename(attrs: &[Attribute]) -> Option<String> {
+    for attr in attrs {
+        if let Meta::List(meta_list) = &attr.meta {
+            if meta_list.path.is_ident("serde") {
+                let tokens_str = meta_list.tokens.to_string();
+
+                if let Some(rename_start) = tokens_str.find("rename = \"") {
+                    let rename_value_start = rename_start + 10;
+                    if let Some(rename_end) = tokens_str[rename_value_start..].find('"') {
+                        return Some(tokens_str[rename_value_start..rename_value_start + rename_end].to_string());
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Check whether a serde attribute contains the given flag, e.g. `skip` or `skip_serializing_if`
+fn has_serde_flag(attrs: &[Attribute], flag: &str) -> bool {
+    attrs.iter().any(|attr| {
+        if let Meta::List(meta_list) = &attr.meta {
+            if meta_list.path.is_ident("serde") {
+                return meta_list
+                    .tokens
+                    .to_string()
+                    .split(',')
+                    .any(|part| part.split('=').next().map(str::trim) == Some(flag));
+            }
+        }
+        false
+    })
+}
+
 /// Parse serde attributes to determine enum tagging strategy
 fn parse_enum_tagging(attrs: &[Attribute]) -> EnumTagging {
     for attr in attrs {
@@ -969,6 +1954,37 @@ fn apply_rename_all(variant_name: &str, rename_all: &RenameAll) -> String {
     }
 }
 
+/// Apply rename_all transformation to a snake_case field name, matching serde's rules for fields
+fn apply_rename_all_to_field(field_name: &str, rename_all: &RenameAll) -> String {
+    match rename_all {
+        RenameAll::None | RenameAll::Lowercase | RenameAll::SnakeCase => field_name.to_string(),
+        RenameAll::Uppercase | RenameAll::ScreamingSnakeCase => field_name.to_uppercase(),
+        RenameAll::PascalCase | RenameAll::CamelCase => {
+            let mut result = String::new();
+            let mut capitalize = rename_all == &RenameAll::PascalCase;
+            for ch in field_name.chars() {
+                if ch == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    result.extend(ch.to_uppercase());
+                    capitalize = false;
+                } else {
+                    result.push(ch);
+                }
+            }
+            result
+        }
+        RenameAll::KebabCase => field_name.replace('_', "-"),
+        RenameAll::ScreamingKebabCase => field_name.replace('_', "-").to_uppercase(),
+    }
+}
+
+/// The tag value serde writes for a variant: its own `rename`, otherwise the
+/// enum's `rename_all` applied to the variant name
+fn variant_tag_value(variant: &Variant, rename_all: &RenameAll) -> String {
+    parse_serde_rename(&variant.attrs).unwrap_or_else(|| apply_rename_all(&variant.ident.to_string(), rename_all))
+}
+
 /// Convert variant name to snake_case for serde serialization
 fn to_snake_case(s: &str) -> String {
     let mut result = String::new();
@@ -991,15 +2007,20 @@ fn to_snake_case(s: &str) -> String {
 }
 
 /// Generate schema for enum with internal tagging: `{"type": "variant", ...fields}`
+///
+/// The `oneOf` carries a `discriminator` naming the tag property. Newtype variants
+/// around another schema are composed with `allOf` and listed in its `mapping`.
 fn generate_internal_tagged_enum_schema(
     variants: &syn::punctuated::Punctuated<Variant, syn::token::Comma>,
+    attrs: &[Attribute],
     tag_field: &str,
 ) -> String {
+    let rename_all = parse_rename_all(attrs);
     let mut one_of_schemas = Vec::new();
+    let mut mapping_entries = Vec::new();
 
     for variant in variants {
-        let variant_name = variant.ident.to_string();
-        let variant_value = to_snake_case(&variant_name);
+        let variant_value = variant_tag_value(variant, &rename_all);
 
         let variant_schema = match &variant.fields {
             Fields::Unit => {
@@ -1035,34 +2056,56 @@ fn generate_internal_tagged_enum_schema(
                     properties.join(",")
                 )
             }
-            Fields::Unnamed(_) => {
-                // Unnamed fields not typical for internal tagging, treat as object
-                format!(
+            Fields::Unnamed(fields) => {
+                let tag_schema = format!(
                     "{{\"type\":\"object\",\"required\":[\"{}\"],\"properties\":{{\"{}\":{{\"type\":\"string\",\"enum\":[\"{}\"]}}}}}}",
                     tag_field, tag_field, variant_value
-                )
+                );
+                // A newtype around a struct serializes as that struct's fields plus the tag
+                let inner_schema = match fields.unnamed.first() {
+                    Some(field) if fields.unnamed.len() == 1 => get_type_schema(&field.ty),
+                    _ => String::new(),
+                };
+                match inner_schema.strip_prefix("{\"$ref\":\"").and_then(|rest| rest.strip_suffix("\"}")) {
+                    Some(reference) => {
+                        mapping_entries.push(format!("\"{variant_value}\":\"{reference}\""));
+                        format!("{{\"allOf\":[{inner_schema},{tag_schema}]}}")
+                    }
+                    None => tag_schema,
+                }
             }
         };
 
         one_of_schemas.push(variant_schema);
     }
 
-    format!("{{\"oneOf\":[{}]}}", one_of_schemas.join(","))
+    let mapping = if mapping_entries.is_empty() {
+        String::new()
+    } else {
+        format!(",\"mapping\":{{{}}}", mapping_entries.join(","))
+    };
+    format!(
+        "{{\"oneOf\":[{}],\"discriminator\":{{\"propertyName\":\"{}\"{}}}}}",
+        one_of_schemas.join(","),
+        tag_field,
+        mapping
+    )
 }
 
 /// Generate schema for enum with adjacent tagging using OpenAPI discriminator pattern
 /// This creates a oneOf with references to named variant schemas for cleaner output
 fn generate_adjacent_tagged_enum_schema(
     variants: &syn::punctuated::Punctuated<Variant, syn::token::Comma>,
+    attrs: &[Attribute],
     tag_field: &str,
     _content_field: &str,
 ) -> String {
+    let rename_all = parse_rename_all(attrs);
     let mut one_of_refs = Vec::new();
     let mut mapping_entries = Vec::new();
 
     for variant in variants {
-        let variant_name = variant.ident.to_string();
-        let variant_value = to_snake_case(&variant_name);
+        let variant_value = variant_tag_value(variant, &rename_all);
         
         // Only create refs for variants with data (unnamed fields with inner types)
         if let Fields::Unnamed(fields) = &variant.fields {
@@ -1117,6 +2160,41 @@ fn generate_adjacent_tagged_enum_schema(
     }
 }
 
+/// Get the JSON schema for a numeric primitive, including its OpenAPI `format`
+fn numeric_type_schema(type_name: &str) -> Option<String> {
+    let (schema_type, format) = match type_name {
+        "i8" | "i16" | "i32" | "u8" | "u16" => ("integer", Some("int32")),
+        "i64" | "isize" | "u32" | "u64" | "usize" => ("integer", Some("int64")),
+        "i128" | "u128" => ("integer", None),
+        "f32" => ("number", Some("float")),
+        "f64" => ("number", Some("double")),
+        _ => return None,
+    };
+    Some(match format {
+        Some(format) => format!("{{\"type\":\"{schema_type}\",\"format\":\"{format}\"}}"),
+        None => format!("{{\"type\":\"{schema_type}\"}}"),
+    })
+}
+
+/// Get the JSON schema for a date, time, address or other well-known std/ecosystem type
+fn well_known_type_schema(type_name: &str) -> Option<String> {
+    let (schema_type, format) = match type_name {
+        "DateTime" | "NaiveDateTime" => ("string", Some("date-time")),
+        "NaiveDate" => ("string", Some("date")),
+        "NaiveTime" => ("string", Some("time")),
+        "Ipv4Addr" => ("string", Some("ipv4")),
+        "Ipv6Addr" => ("string", Some("ipv6")),
+        "IpAddr" | "PathBuf" => ("string", None),
+        "Duration" => ("string", Some("duration")),
+        "Decimal" => ("number", None),
+        _ => return None,
+    };
+    Some(match format {
+        Some(format) => format!("{{\"type\":\"{schema_type}\",\"format\":\"{format}\"}}"),
+        None => format!("{{\"type\":\"{schema_type}\"}}"),
+    })
+}
+
 /// Check if a type is Option<T>
 fn is_option_type(ty: &Type) -> bool {
     if let Type::Path(type_path) = ty {
@@ -1127,6 +2205,40 @@ fn is_option_type(ty: &Type) -> bool {
     false
 }
 
+/// `T` for an `Option<T>` type
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else { return None };
+    let segment = type_path.path.segments.last().filter(|segment| segment.ident == "Option")?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first()? {
+        GenericArgument::Type(inner_type) => Some(inner_type),
+        _ => None,
+    }
+}
+
+/// Schema for `Vec<T>` and sets: an array whose items follow `T`
+fn array_type_schema(segment: &syn::PathSegment) -> String {
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(item_type)) = args.args.first() {
+            return format!("{{\"type\":\"array\",\"items\":{}}}", get_type_schema(item_type));
+        }
+    }
+    "{\"type\":\"array\"}".to_string()
+}
+
+/// Schema for `HashMap<K, V>` / `BTreeMap<K, V>`: an object whose values follow `V`
+fn map_type_schema(segment: &syn::PathSegment) -> String {
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(value_type)) = args.args.iter().nth(1) {
+            return format!(
+                "{{\"type\":\"object\",\"additionalProperties\":{}}}",
+                get_type_schema(value_type)
+            );
+        }
+    }
+    "{\"type\":\"object\"}".to_string()
+}
+
 /// Get the JSON schema for a type
 fn get_type_schema(ty: &Type) -> String {
     if let Type::Path(type_path) = ty {
@@ -1134,14 +2246,20 @@ fn get_type_schema(ty: &Type) -> String {
             let type_name = segment.ident.to_string();
             match type_name.as_str() {
                 "String" | "str" => return "{\"type\":\"string\"}".to_string(),
-                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => return "{\"type\":\"integer\"}".to_string(),
-                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => return "{\"type\":\"integer\"}".to_string(),
-                "f32" | "f64" => return "{\"type\":\"number\"}".to_string(),
+                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" |
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" |
+                "f32" | "f64" => return numeric_type_schema(&type_name).unwrap_or_default(),
                 "bool" => return "{\"type\":\"boolean\"}".to_string(),
-                "Vec" => return "{\"type\":\"array\"}".to_string(),
-                "HashMap" | "BTreeMap" => return "{\"type\":\"object\"}".to_string(),
+                "Vec" | "HashSet" | "BTreeSet" => return array_type_schema(segment),
+                "HashMap" | "BTreeMap" => return map_type_schema(segment),
                 "Uuid" => return "{\"type\":\"string\",\"format\":\"uuid\"}".to_string(),
-                "Option" => return "{\"type\":\"string\"}".to_string(),
+                "DateTime" | "NaiveDateTime" | "NaiveDate" | "NaiveTime" | "Ipv4Addr" | "Ipv6Addr"
+                | "IpAddr" | "PathBuf" | "Duration" | "Decimal" => {
+                    return well_known_type_schema(&type_name).unwrap_or_default()
+                }
+                "Url" => return "{\"type\":\"string\",\"format\":\"uri\"}".to_string(),
+                // An Option is documented as the type it wraps
+                "Option" => return option_inner_type(ty).map(get_type_schema).unwrap_or_else(|| "{\"type\":\"string\"}".to_string()),
                 _ => return format!("{{\"$ref\":\"#/components/schemas/{}\"}}", type_name),
             }
         }
@@ -1198,29 +2316,27 @@ fn generate_external_tagged_enum_schema(
                             let schema_ref = match inner_type.as_str() {
                                 // Basic primitive types
                                 "String" | "str" => "{\"type\":\"string\"}".to_string(),
-                                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => {
-                                    "{\"type\":\"integer\"}".to_string()
-                                }
-                                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
-                                    "{\"type\":\"integer\"}".to_string()
+                                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" |
+                                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" |
+                                "f32" | "f64" => {
+                                    numeric_type_schema(&inner_type).unwrap_or_default()
                                 }
-                                "f32" | "f64" => "{\"type\":\"number\"}".to_string(),
                                 "bool" => "{\"type\":\"boolean\"}".to_string(),
 
                                 // Standard library collection types
-                                "Vec" => "{\"type\":\"array\"}".to_string(),
-                                "HashMap" | "BTreeMap" => "{\"type\":\"object\"}".to_string(),
-                                "HashSet" | "BTreeSet" => "{\"type\":\"array\"}".to_string(),
+                                "Vec" | "HashSet" | "BTreeSet" => array_type_schema(segment),
+                                "HashMap" | "BTreeMap" => map_type_schema(segment),
 
                                 // Common types that should be strings
                                 "Uuid" => "{\"type\":\"string\",\"format\":\"uuid\"}".to_string(),
-                                "DateTime" | "NaiveDateTime" | "NaiveDate" | "NaiveTime" => {
-                                    "{\"type\":\"string\",\"format\":\"date-time\"}".to_string()
+                                "DateTime" | "NaiveDateTime" | "NaiveDate" | "NaiveTime" | "Ipv4Addr"
+                                | "Ipv6Addr" | "IpAddr" | "PathBuf" | "Duration" | "Decimal" => {
+                                    well_known_type_schema(&inner_type).unwrap_or_default()
                                 }
                                 "Url" => "{\"type\":\"string\",\"format\":\"uri\"}".to_string(),
 
                                 // Wrappers
-                                "Option" => "{\"type\":\"string\"}".to_string(), // Simplified
+                                "Option" => get_type_schema(&fields.unnamed[0].ty),
                                 "Result" => "{\"type\":\"object\"}".to_string(), // Simplified
 
                                 _ => {
@@ -1265,7 +2381,111 @@ fn generate_external_tagged_enum_schema(
         one_of_schemas.push(variant_schema);
     }
 
-    format!("{{\"oneOf\":[{}]}}", one_of_schemas.join(","))
+    format!("{{\"oneOf\":[{}]}}", one_of_schemas.join(","))
+}
+
+/// Schema for the data a variant carries, or `None` for a unit variant
+fn variant_payload_schema(variant: &Variant) -> Option<String> {
+    match &variant.fields {
+        Fields::Unit => None,
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            Some(get_type_schema(&fields.unnamed.first().unwrap().ty))
+        }
+        Fields::Unnamed(fields) => Some(format!(
+            "{{\"type\":\"array\",\"items\":{{\"oneOf\":[{}]}}}}",
+            fields.unnamed.iter().map(|field| get_type_schema(&field.ty)).collect::<Vec<_>>().join(",")
+        )),
+        Fields::Named(_) => {
+            let (properties, required) = variant_field_properties(variant);
+            let required_str = if required.is_empty() {
+                String::new()
+            } else {
+                format!(",\"required\":[{}]", required.join(","))
+            };
+            Some(format!("{{\"type\":\"object\",\"properties\":{{{}}}{required_str}}}", properties.join(",")))
+        }
+    }
+}
+
+/// `"name":schema` property entries and quoted required names for a struct-like variant
+fn variant_field_properties(variant: &Variant) -> (Vec<String>, Vec<String>) {
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+    if let Fields::Named(fields) = &variant.fields {
+        for field in fields.named.iter().filter(|field| !has_serde_flag(&field.attrs, "skip")) {
+            let Some(ident) = &field.ident else { continue };
+            let field_name = json_string(&parse_serde_rename(&field.attrs).unwrap_or_else(|| ident.unraw().to_string()));
+            properties.push(format!("{field_name}:{}", get_type_schema(&field.ty)));
+            if !is_option_type(&field.ty) {
+                required.push(field_name);
+            }
+        }
+    }
+    (properties, required)
+}
+
+/// Generate a `oneOf` with the serialized shape of each variant
+///
+/// Unlike the derive's enum schemas this follows every serde tagging mode
+/// exactly, including variant renames and the fields of struct variants, so it
+/// describes what an `#[api_error]` body really looks like.
+fn generate_variant_shapes_schema(
+    variants: &syn::punctuated::Punctuated<Variant, syn::token::Comma>,
+    attrs: &[Attribute],
+) -> String {
+    let tagging = parse_enum_tagging(attrs);
+    let rename_all = parse_rename_all(attrs);
+
+    let shapes: Vec<String> = variants.iter().map(|variant| {
+        let variant_name = json_string(
+            &parse_serde_rename(&variant.attrs)
+                .unwrap_or_else(|| apply_rename_all(&variant.ident.to_string(), &rename_all)),
+        );
+        let payload = variant_payload_schema(variant);
+
+        match &tagging {
+            EnumTagging::External => match payload {
+                None => format!("{{\"type\":\"string\",\"enum\":[{variant_name}]}}"),
+                Some(payload) => format!(
+                    "{{\"type\":\"object\",\"required\":[{variant_name}],\"properties\":{{{variant_name}:{payload}}}}}"
+                ),
+            },
+            EnumTagging::Internal { tag } => {
+                let tag = json_string(tag);
+                let mut properties = vec![format!("{tag}:{{\"type\":\"string\",\"enum\":[{variant_name}]}}")];
+                let mut required = vec![tag];
+                let (fields, required_fields) = variant_field_properties(variant);
+                properties.extend(fields);
+                required.extend(required_fields);
+                let tag_object = format!(
+                    "{{\"type\":\"object\",\"required\":[{}],\"properties\":{{{}}}}}",
+                    required.join(","),
+                    properties.join(",")
+                );
+                match (&variant.fields, payload) {
+                    // A newtype variant's own fields sit next to the tag
+                    (Fields::Unnamed(_), Some(payload)) => format!("{{\"allOf\":[{tag_object},{payload}]}}"),
+                    _ => tag_object,
+                }
+            }
+            EnumTagging::Adjacent { tag, content } => {
+                let tag = json_string(tag);
+                let tag_property = format!("{tag}:{{\"type\":\"string\",\"enum\":[{variant_name}]}}");
+                match payload {
+                    None => format!("{{\"type\":\"object\",\"required\":[{tag}],\"properties\":{{{tag_property}}}}}"),
+                    Some(payload) => {
+                        let content = json_string(content);
+                        format!(
+                            "{{\"type\":\"object\",\"required\":[{tag},{content}],\"properties\":{{{tag_property},{content}:{payload}}}}}"
+                        )
+                    }
+                }
+            }
+            EnumTagging::Untagged => payload.unwrap_or_else(|| "{\"nullable\":true}".to_string()),
+        }
+    }).collect();
+
+    format!("{{\"oneOf\":[{}]}}", shapes.join(","))
 }
 
 /// Derive macro for automatic JSON schema generation.
@@ -1278,13 +2498,51 @@ fn generate_external_tagged_enum_schema(
 ///
 /// Supported Rust types and their JSON schema mappings:
 /// - `String`, `&str` → `"string"`
-/// - `i32`, `i64`, `u32`, `u64`, etc. → `"integer"`
-/// - `f32`, `f64` → `"number"`
+/// - `i32`, `i64`, `u32`, `u64`, etc. → `"integer"` with `int32`/`int64` format
+/// - `f32`, `f64` → `"number"` with `float`/`double` format
 /// - `bool` → `"boolean"`
+/// - `DateTime`/`NaiveDateTime`, `NaiveDate`, `NaiveTime` → `"string"` with `date-time`, `date`, `time` format
+/// - `Ipv4Addr`, `Ipv6Addr` → `"string"` with `ipv4`/`ipv6` format; `IpAddr`, `PathBuf` → `"string"`
+/// - `Duration` → `"string"` with `duration` format; `Decimal` → `"number"`
 /// - `Option<T>` → makes field optional
 /// - `Vec<T>` → `"array"` with item schema
+/// - `HashMap<String, T>`, `BTreeMap<String, T>` → `"object"` with `additionalProperties` of `T`
 /// - Nested structs → object references
-/// - Enums → `"string"` (basic support)
+/// - Newtype structs (`struct UserId(u32)`) → the inner type's schema
+/// - Tuple structs → `"array"` with `minItems`/`maxItems` set to the field count
+/// - Fieldless enums → `"string"` with `enum` values
+/// - Enums with data → `oneOf` of variant schemas
+/// - Internally tagged enums (`#[serde(tag = "kind")]`) → `oneOf` with a `discriminator` on the tag
+///
+/// # Field Constraints
+///
+/// Numeric fields accept range attributes that become validation keywords:
+/// - `#[minimum = 0]` / `#[maximum = 120]` → `minimum` / `maximum` (negative bounds as strings, `"-40"`)
+/// - `#[exclusive_minimum]` / `#[exclusive_maximum]` → bounds exclude the value itself
+///
+/// String fields accept length and format attributes:
+/// - `#[min_length = 1]` / `#[max_length = 64]` → `minLength` / `maxLength`
+/// - `#[pattern = r"^\d{3}-\d{4}$"]` → `pattern`, a regular expression the value must match
+///
+/// Collection fields accept item count attributes:
+/// - `#[min_items = 1]` / `#[max_items = 10]` → `minItems` / `maxItems`
+/// - `#[unique_items]` → `uniqueItems`, no duplicate elements
+///
+/// Fields that only travel one way can be marked so one type serves as both
+/// request and response body:
+/// - `#[read_only]` → `readOnly`, only in responses (server-assigned ids, timestamps)
+/// - `#[write_only]` → `writeOnly`, only in requests (passwords)
+///
+/// `Option<T>` fields are left out of `required`. Serde still writes `None` as
+/// `null`, which OpenAPI 3.0 only allows with `nullable: true`:
+/// - `#[nullable]` on a field → `nullable: true` on that property
+/// - `#[nullable]` on the struct → `nullable: true` on every `Option<T>` field
+///   without `skip_serializing_if`
+///
+/// # Vendor Extensions
+///
+/// `#[extension("x-internal", true)]` on the type adds an `x-` property to its
+/// schema. Values may be strings, numbers or booleans.
 ///
 /// # Examples
 ///
@@ -1400,7 +2658,27 @@ fn generate_external_tagged_enum_schema(
 /// - Your type must implement `Serialize` (for response types) or `Deserialize` (for request types)
 /// - The type must be used in a function signature annotated with `#[api_handler]`
 /// - For error types used in `Result<T, E>`, implement `axum::response::IntoResponse`
-#[proc_macro_derive(OpenApiSchema)]
+#[proc_macro_derive(
+    OpenApiSchema,
+    attributes(
+        example,
+        default,
+        minimum,
+        maximum,
+        exclusive_minimum,
+        exclusive_maximum,
+        min_length,
+        max_length,
+        pattern,
+        min_items,
+        max_items,
+        unique_items,
+        read_only,
+        write_only,
+        nullable,
+        extension
+    )
+)]
 pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -1413,10 +2691,33 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                 Fields::Named(fields) => {
                     let mut properties = Vec::new();
                     let mut required = Vec::new();
+                    let mut flattened = Vec::new();
+                    let rename_all = parse_rename_all(&input.attrs);
+                    // `#[nullable]` on the struct covers every Option field serde writes as `null`
+                    let nullable_options = input.attrs.iter().any(|attr| attr.path().is_ident("nullable"));
 
                     for field in fields.named.iter() {
+                        // Skipped fields never appear in the serialized payload
+                        if has_serde_flag(&field.attrs, "skip")
+                            || has_serde_flag(&field.attrs, "skip_serializing")
+                        {
+                            continue;
+                        }
+
+                        // Flattened fields merge the inner type's properties into this object
+                        if has_serde_flag(&field.attrs, "flatten") {
+                            let inner_schema = get_type_schema(&field.ty);
+                            if inner_schema.starts_with("{\"$ref\"") {
+                                flattened.push(inner_schema);
+                            }
+                            continue;
+                        }
+
                         if let Some(field_name) = &field.ident {
-                            let field_name_str = field_name.to_string();
+                            // Use the same property name serde puts on the wire
+                            let field_name_str = parse_serde_rename(&field.attrs).unwrap_or_else(|| {
+                                apply_rename_all_to_field(&field_name.unraw().to_string(), &rename_all)
+                            });
 
                             // Enhanced type mapping with schema references for custom types
                             let (type_schema, _is_custom_type) = match &field.ty {
@@ -1428,24 +2729,19 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                                             "String" | "str" => {
                                                 ("{\"type\":\"string\"}".to_string(), false)
                                             }
-                                            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => {
-                                                ("{\"type\":\"integer\"}".to_string(), false)
-                                            }
-                                            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
-                                                ("{\"type\":\"integer\"}".to_string(), false)
-                                            }
-                                            "f32" | "f64" => {
-                                                ("{\"type\":\"number\"}".to_string(), false)
+                                            "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+                                            | "u8" | "u16" | "u32" | "u64" | "u128" | "usize"
+                                            | "f32" | "f64" => {
+                                                (numeric_type_schema(&type_name).unwrap_or_default(), false)
                                             }
                                             "bool" => ("{\"type\":\"boolean\"}".to_string(), false),
 
                                             // Standard library collection types
-                                            "Vec" => ("{\"type\":\"array\"}".to_string(), false),
-                                            "HashMap" | "BTreeMap" => {
-                                                ("{\"type\":\"object\"}".to_string(), false)
+                                            "Vec" | "HashSet" | "BTreeSet" => {
+                                                (array_type_schema(segment), false)
                                             }
-                                            "HashSet" | "BTreeSet" => {
-                                                ("{\"type\":\"array\"}".to_string(), false)
+                                            "HashMap" | "BTreeMap" => {
+                                                (map_type_schema(segment), false)
                                             }
 
                                             // Common types that should be strings
@@ -1455,9 +2751,9 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                                                 false,
                                             ),
                                             "DateTime" | "NaiveDateTime" | "NaiveDate"
-                                            | "NaiveTime" => (
-                                                "{\"type\":\"string\",\"format\":\"date-time\"}"
-                                                    .to_string(),
+                                            | "NaiveTime" | "Ipv4Addr" | "Ipv6Addr" | "IpAddr"
+                                            | "PathBuf" | "Duration" | "Decimal" => (
+                                                well_known_type_schema(&type_name).unwrap_or_default(),
                                                 false,
                                             ),
                                             "Url" => (
@@ -1466,12 +2762,8 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                                                 false,
                                             ),
 
-                                            // Option wrapper - simplified handling
-                                            "Option" => {
-                                                // For Option<T>, we need to parse the generic parameter
-                                                // For now, default to string but this could be enhanced
-                                                ("{\"type\":\"string\"}".to_string(), false)
-                                            }
+                                            // Option wrapper - documented as the type it wraps
+                                            "Option" => (get_type_schema(&field.ty), false),
 
                                             // Result wrapper - treat as the success type for now
                                             "Result" => {
@@ -1497,15 +2789,26 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                             };
 
                             // Parse field attributes for examples and defaults
-                            let (enhanced_schema, default_value) =
+                            let (mut enhanced_schema, default_value) =
                                 enhance_schema_with_attributes(&field.attrs, type_schema);
+                            if let Some(description) = doc_description(&field.attrs) {
+                                add_schema_keyword(&mut enhanced_schema, "description", &json_string(&description));
+                            }
+                            let nullable = field.attrs.iter().any(|attr| attr.path().is_ident("nullable"))
+                                || (nullable_options && is_option_type(&field.ty) && !has_serde_flag(&field.attrs, "skip_serializing_if"));
+                            if nullable {
+                                add_schema_keyword(&mut enhanced_schema, "nullable", "true");
+                            }
                             properties.push(format!("\"{field_name_str}\":{}", enhanced_schema));
 
                             // If there's a default value, this field is not required
                             let has_default = default_value.is_some();
 
+                            // Conditionally serialized fields may be absent, like Option
+                            let may_be_skipped = has_serde_flag(&field.attrs, "skip_serializing_if");
+
                             // Only add to required if not an Option type and has no default value
-                            if !has_default {
+                            if !has_default && !may_be_skipped {
                                 if let Type::Path(type_path) = &field.ty {
                                     if let Some(segment) = type_path.path.segments.last() {
                                         if segment.ident != "Option" {
@@ -1526,11 +2829,40 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                         format!(",\"required\":[{}]", required.join(","))
                     };
 
-                    format!(
+                    let object_schema = format!(
                         "{{\"type\":\"object\",\"properties\":{{{properties_str}}}{required_str}}}"
-                    )
+                    );
+
+                    if flattened.is_empty() {
+                        object_schema
+                    } else {
+                        // Compose the flattened types with this struct's own properties
+                        flattened.push(object_schema);
+                        format!("{{\"allOf\":[{}]}}", flattened.join(","))
+                    }
+                }
+                // A newtype is serialized as its inner value
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    get_type_schema(&fields.unnamed[0].ty)
+                }
+                // Tuple structs are serialized as fixed-length arrays. OpenAPI 3.0 has no
+                // `prefixItems`, so mixed element types become a `oneOf` of the distinct ones
+                Fields::Unnamed(fields) => {
+                    let mut element_schemas: Vec<String> = Vec::new();
+                    for field in &fields.unnamed {
+                        let schema = get_type_schema(&field.ty);
+                        if !element_schemas.contains(&schema) {
+                            element_schemas.push(schema);
+                        }
+                    }
+                    let items = match element_schemas.as_slice() {
+                        [single] => single.clone(),
+                        _ => format!("{{\"oneOf\":[{}]}}", element_schemas.join(",")),
+                    };
+                    let length = fields.unnamed.len();
+                    format!("{{\"type\":\"array\",\"items\":{items},\"minItems\":{length},\"maxItems\":{length}}}")
                 }
-                _ => "{\"type\":\"object\"}".to_string(),
+                Fields::Unit => "{\"type\":\"object\"}".to_string(),
             }
         }
         Data::Enum(data_enum) => {
@@ -1542,10 +2874,10 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
                     generate_external_tagged_enum_schema(&data_enum.variants, &input.attrs)
                 }
                 EnumTagging::Internal { tag } => {
-                    generate_internal_tagged_enum_schema(&data_enum.variants, &tag)
+                    generate_internal_tagged_enum_schema(&data_enum.variants, &input.attrs, &tag)
                 }
                 EnumTagging::Adjacent { tag, content } => {
-                    generate_adjacent_tagged_enum_schema(&data_enum.variants, &tag, &content)
+                    generate_adjacent_tagged_enum_schema(&data_enum.variants, &input.attrs, &tag, &content)
                 }
                 EnumTagging::Untagged => {
                     // For untagged enums, generate oneOf with variant schemas directly
@@ -1556,6 +2888,22 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
         _ => "{\"type\":\"string\"}".to_string(),
     };
 
+    let mut schema_json = schema_json;
+    if let Data::Struct(_) = &input.data {
+        add_schema_keyword(&mut schema_json, "title", &json_string(&name_str));
+        if let Some(description) = doc_description(&input.attrs) {
+            add_schema_keyword(&mut schema_json, "description", &json_string(&description));
+        }
+    }
+    match parse_schema_extensions(&input.attrs) {
+        Ok(extensions) => {
+            for (key, value) in extensions {
+                add_schema_keyword(&mut schema_json, &key, &value);
+            }
+        }
+        Err(err) => return err.to_compile_error().into(),
+    }
+
     // Convert the schema_json String into a LitStr for embedding as a string literal
     let schema_json_lit = syn::LitStr::new(&schema_json, name.span());
 
@@ -1578,11 +2926,56 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Status code from a variant's `#[status(404)]` attribute
+fn variant_status_attribute(attrs: &[Attribute]) -> syn::Result<Option<u16>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("status")) else {
+        return Ok(None);
+    };
+    let code: syn::LitInt = attr.parse_args()?;
+    match code.base10_parse::<u16>()? {
+        code @ 100..=599 => Ok(Some(code)),
+        _ => Err(syn::Error::new_spanned(code, "status must be an HTTP status code between 100 and 599")),
+    }
+}
+
+/// Status code from a doc comment starting with `404: ...`
+fn doc_status_code(attrs: &[Attribute]) -> Option<u16> {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .find_map(|attr| {
+            let Meta::NameValue(meta) = &attr.meta else { return None };
+            let Expr::Lit(syn::ExprLit { lit: Lit::Str(doc), .. }) = &meta.value else { return None };
+            let doc = doc.value();
+            let (code_part, _) = doc.split_once(':')?;
+            code_part.trim().parse::<u16>().ok()
+        })
+}
+
+/// A variant's doc comment as one line, without the leading `404:` status
+fn variant_description(attrs: &[Attribute]) -> Option<String> {
+    let text = attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(syn::MetaNameValue { value: Expr::Lit(syn::ExprLit { lit: Lit::Str(doc), .. }), .. }) => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let text = match text.split_once(':') {
+        Some((code, rest)) if code.trim().parse::<u16>().is_ok() => rest.trim(),
+        _ => text.trim(),
+    };
+    (!text.is_empty()).then(|| text.to_string())
+}
+
 /// Attribute macro for automatically generating HTTP error responses.
 ///
 /// This macro automatically implements `axum::response::IntoResponse` for error enums,
-/// mapping each variant to an appropriate HTTP status code. Use doc comments with
-/// `/// {code}: {description}` format to specify status codes for variants.
+/// mapping each variant to an appropriate HTTP status code. Put `#[status(404)]` on
+/// a variant, or start its doc comment with `/// {code}: {description}`.
 ///
 /// # Basic Usage
 ///
@@ -1611,14 +3004,31 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// # Explicit Status Codes
+///
+/// `#[status(...)]` wins over the doc comment, so the doc can be phrased freely:
+///
+/// ```rust
+/// # use machined_openapi_gen_macros::api_error;
+/// # use serde::Serialize;
+/// #[api_error]
+/// #[derive(Serialize)]
+/// enum OrderError {
+///     /// The order was already shipped and can no longer change
+///     #[status(409)]
+///     AlreadyShipped { order_id: u64 },
+/// }
+/// ```
+///
 /// # Generated Implementation
 ///
 /// The macro automatically generates:
 /// - `IntoResponse` implementation for HTTP responses
 /// - `Serialize` implementation for JSON serialization
-/// - `OpenApiSchema` implementation for OpenAPI documentation
+/// - `OpenApiSchema` implementation describing the `{"error": ...}` body, with a
+///   `oneOf` entry for each variant that follows the enum's serde tagging
 /// - Maps each variant to its specified status code
-/// - Uses 500 Internal Server Error for variants without doc comments
+/// - Uses 500 Internal Server Error for variants with neither `#[status]` nor a status doc comment
 /// - Serializes the error as JSON in the response body
 ///
 /// # Supported Status Codes
@@ -1716,47 +3126,51 @@ pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
 /// # Requirements
 ///
 /// - The error enum must also have `#[derive(Serialize)]` or implement `Serialize` manually
-/// - Each variant's doc comment should start with a 3-digit HTTP status code followed by a colon
+/// - Each variant needs `#[status(code)]` or a doc comment starting with a 3-digit status code and a colon
 /// - The macro will automatically implement `axum::response::IntoResponse`
 /// - The macro will register the error schema for OpenAPI documentation
 #[proc_macro_attribute]
 pub fn api_error(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as DeriveInput);
-    let name = &input.ident;
-    let name_str = name.to_string();
+    let mut input = parse_macro_input!(item as DeriveInput);
 
-    // Extract status codes from doc comments
+    // `#[status(...)]` takes priority, then a leading "NNN:" in the doc comment
     let mut variant_status_codes = Vec::new();
-
-    if let Data::Enum(data_enum) = &input.data {
-        for variant in &data_enum.variants {
-            let variant_name = &variant.ident;
-            let mut status_code = 500u16; // Default to 500 Internal Server Error
-
-            // Look for status code in doc comments
-            for attr in &variant.attrs {
-                if attr.path().is_ident("doc") {
-                    if let Meta::NameValue(meta) = &attr.meta {
-                        if let Expr::Lit(lit) = &meta.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                let doc = s.value();
-                                // Look for pattern like "404: Description" or "/// 404 Description"
-                                if let Some(colon_pos) = doc.find(':') {
-                                    let code_part = doc[..colon_pos].trim();
-                                    if let Ok(code) = code_part.parse::<u16>() {
-                                        status_code = code;
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
+    let mut status_descriptions: Vec<(u16, String)> = Vec::new();
+
+    if let Data::Enum(data_enum) = &mut input.data {
+        for variant in data_enum.variants.iter_mut() {
+            let status_code = match variant_status_attribute(&variant.attrs) {
+                Ok(Some(code)) => code,
+                Ok(None) => doc_status_code(&variant.attrs).unwrap_or(500), // Default to 500 Internal Server Error
+                Err(err) => return err.to_compile_error().into(),
+            };
+            // `status` is only meaningful to this macro; strip it before serde's derive sees it
+            variant.attrs.retain(|attr| !attr.path().is_ident("status"));
+
+            // The first documented variant for a status describes that response
+            if let Some(description) = variant_description(&variant.attrs) {
+                if !status_descriptions.iter().any(|(code, _)| *code == status_code) {
+                    status_descriptions.push((status_code, description));
                 }
             }
 
-            variant_status_codes.push((variant_name.clone(), status_code));
+            variant_status_codes.push((variant.ident.clone(), status_code));
         }
     }
+    let status_entries = status_descriptions.iter().map(|(code, description)| quote! { (#code, #description) });
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+
+    // The response body wraps the serialized enum in an "error" field
+    let error_schema = match &input.data {
+        Data::Enum(data_enum) => generate_variant_shapes_schema(&data_enum.variants, &input.attrs),
+        _ => "{\"type\":\"object\"}".to_string(),
+    };
+    let schema_json = format!(
+        "{{\"type\":\"object\",\"required\":[\"error\"],\"properties\":{{\"error\":{error_schema}}}}}"
+    );
+    let schema_json_lit = syn::LitStr::new(&schema_json, name.span());
 
     // Generate match arms for IntoResponse implementation
     let match_arms = variant_status_codes
@@ -1792,9 +3206,7 @@ pub fn api_error(_attr: TokenStream, item: TokenStream) -> TokenStream {
         // Also implement OpenApiSchema for the error type
         impl machined_openapi_gen::OpenApiSchema for #name {
             fn schema() -> String {
-                // For error enums, generate a simple schema
-                // In a real implementation, this would analyze variants
-                format!(r#"{{"type":"object","properties":{{"error":{{"type":"object"}}}}}}"#)
+                #schema_json_lit.to_string()
             }
         }
 
@@ -1802,7 +3214,15 @@ pub fn api_error(_attr: TokenStream, item: TokenStream) -> TokenStream {
         machined_openapi_gen::inventory::submit! {
             machined_openapi_gen::SchemaRegistration {
                 type_name: #name_str,
-                schema_json: r#"{"type":"object","properties":{"error":{"type":"object"}}}"#,
+                schema_json: #schema_json_lit,
+            }
+        }
+
+        // Register the variant descriptions for each status code
+        machined_openapi_gen::inventory::submit! {
+            machined_openapi_gen::ErrorResponseRegistration {
+                type_name: #name_str,
+                descriptions: &[#(#status_entries),*],
             }
         }
     };
@@ -1822,8 +3242,8 @@ mod tests {
             Json(body): Json<CreateUserRequest>
         };
 
-        let result = extract_request_body_type(&inputs);
-        assert_eq!(result, Some("CreateUserRequest".to_string()));
+        let result = extract_request_body_type(&inputs).unwrap();
+        assert_eq!(result, Some(("CreateUserRequest".to_string(), false)));
 
         // Test with multiple parameters
         let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
@@ -1831,16 +3251,88 @@ mod tests {
             Json(data): Json<UpdateRequest>
         };
 
-        let result = extract_request_body_type(&inputs);
-        assert_eq!(result, Some("UpdateRequest".to_string()));
+        let result = extract_request_body_type(&inputs).unwrap();
+        assert_eq!(result, Some(("UpdateRequest".to_string(), false)));
+
+        // Option<Json<T>> is an optional body
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<u32>,
+            body: Option<Json<TouchRequest>>
+        };
+
+        let result = extract_request_body_type(&inputs).unwrap();
+        assert_eq!(result, Some(("TouchRequest".to_string(), true)));
 
         // Test without Json parameter
         let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
             Path(id): Path<u32>
         };
 
-        let result = extract_request_body_type(&inputs);
+        let result = extract_request_body_type(&inputs).unwrap();
         assert_eq!(result, None);
+
+        // Two Json<T> parameters are rejected rather than silently picking the first
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Json(first): Json<CreateUserRequest>,
+            Json(second): Json<UpdateRequest>
+        };
+
+        let err = extract_request_body_type(&inputs).unwrap_err();
+        assert!(err.to_string().contains("only take one `Json<T>`"));
+    }
+
+    #[test]
+    fn test_extract_query_params_type() {
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<u32>,
+            Query(filter): Query<UserFilter>
+        };
+        assert_eq!(extract_query_params_type(&inputs), Some("UserFilter".to_string()));
+
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Json(data): Json<UpdateRequest>
+        };
+        assert_eq!(extract_query_params_type(&inputs), None);
+    }
+
+    #[test]
+    fn test_extract_form_body_type() {
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            axum::Form(login): axum::Form<LoginForm>
+        };
+        assert_eq!(extract_form_body_type(&inputs), Some("LoginForm".to_string()));
+
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Json(data): Json<UpdateRequest>
+        };
+        assert_eq!(extract_form_body_type(&inputs), None);
+    }
+
+    #[test]
+    fn test_extract_path_param_schemas() {
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<u32>
+        };
+        assert_eq!(extract_path_param_schemas(&inputs), [r#"{"type":"integer","format":"int64"}"#]);
+
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path((user_id, slug)): Path<(u32, String)>
+        };
+        assert_eq!(
+            extract_path_param_schemas(&inputs),
+            [r#"{"type":"integer","format":"int64"}"#, r#"{"type":"string"}"#]
+        );
+
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(params): Path<PostPath>,
+            Json(data): Json<UpdateRequest>
+        };
+        assert_eq!(extract_path_param_schemas(&inputs), [r##"{"$ref":"#/components/schemas/PostPath"}"##]);
+
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Json(data): Json<UpdateRequest>
+        };
+        assert!(extract_path_param_schemas(&inputs).is_empty());
     }
 
     #[test]
@@ -1869,9 +3361,41 @@ mod tests {
         };
 
         let (response_type, error_type) = extract_response_and_error_types(&output);
-        assert_eq!(response_type, None); // Current implementation doesn't handle tuples
+        assert_eq!(response_type, Some("CreatedResponse".to_string()));
         assert_eq!(error_type, Some("CreateError".to_string()));
 
+        // Test tuple success type without Result
+        let output: ReturnType = parse_quote! {
+            -> (StatusCode, Json<CreatedResponse>)
+        };
+
+        let (response_type, error_type) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("CreatedResponse".to_string()));
+        assert_eq!(error_type, None);
+
+        // Test tuple without a Json member
+        let output: ReturnType = parse_quote! {
+            -> Result<(StatusCode, String), CreateError>
+        };
+
+        let (response_type, _) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, None);
+
+        // Test list payloads
+        let output: ReturnType = parse_quote! {
+            -> Result<Json<Vec<UserResponse>>, ApiError>
+        };
+
+        let (response_type, _) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("Vec<UserResponse>".to_string()));
+
+        let output: ReturnType = parse_quote! {
+            -> (StatusCode, Json<Vec<models::User>>)
+        };
+
+        let (response_type, _) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("Vec<models :: User>".to_string()));
+
         // Test no return type
         let output: ReturnType = ReturnType::Default;
 
@@ -1880,6 +3404,149 @@ mod tests {
         assert_eq!(error_type, None);
     }
 
+    #[test]
+    fn test_tuple_success_status() {
+        // Constant in the body wins
+        let input: ItemFn = parse_quote! {
+            async fn accept_job(Json(job): Json<Job>) -> Result<(StatusCode, Json<JobStatus>), JobError> {
+                Ok((StatusCode::ACCEPTED, Json(JobStatus::queued(job))))
+            }
+        };
+        assert_eq!(tuple_success_status(&input), Some((202, "Accepted")));
+
+        // No constant in the body defaults to 201
+        let input: ItemFn = parse_quote! {
+            async fn create_user(Json(user): Json<NewUser>) -> Result<(StatusCode, Json<User>), UserError> {
+                let status = created_status();
+                Ok((status, Json(User::from(user))))
+            }
+        };
+        assert_eq!(tuple_success_status(&input), Some((201, "Created")));
+
+        // Several 2xx constants are ambiguous, so fall back to 201 as well
+        let input: ItemFn = parse_quote! {
+            async fn upsert_user(Json(user): Json<NewUser>) -> (StatusCode, Json<User>) {
+                if user.exists() { (StatusCode::OK, Json(user.into())) } else { (StatusCode::CREATED, Json(user.into())) }
+            }
+        };
+        assert_eq!(tuple_success_status(&input), Some((201, "Created")));
+
+        // Non-tuple handlers keep their documented responses
+        let input: ItemFn = parse_quote! {
+            async fn get_user() -> Result<Json<User>, UserError> {
+                Ok(Json(User::default()))
+            }
+        };
+        assert_eq!(tuple_success_status(&input), None);
+    }
+
+    #[test]
+    fn test_status_code_success_status() {
+        // A bare StatusCode has no body, so it defaults to 204
+        let input: ItemFn = parse_quote! {
+            async fn delete_user(Path(id): Path<u32>) -> Result<StatusCode, UserError> {
+                remove(id)?;
+                Ok(status_for(id))
+            }
+        };
+        assert_eq!(status_code_success_status(&input), Some((204, "No Content")));
+
+        // A single constant in the body wins, with or without a Result
+        let input: ItemFn = parse_quote! {
+            async fn touch_user() -> axum::http::StatusCode {
+                StatusCode::ACCEPTED
+            }
+        };
+        assert_eq!(status_code_success_status(&input), Some((202, "Accepted")));
+
+        let input: ItemFn = parse_quote! {
+            async fn get_user() -> Result<Json<User>, StatusCode> {
+                Ok(Json(User::default()))
+            }
+        };
+        assert_eq!(status_code_success_status(&input), None);
+    }
+
+    #[test]
+    fn test_parse_success_status_argument() {
+        let attr: proc_macro2::TokenStream = parse_quote!("jobs", success_status = 202);
+        assert_eq!(parse_success_status_argument(&attr).unwrap(), Some((202, "Accepted")));
+
+        let attr: proc_macro2::TokenStream = parse_quote!(success_status = 207);
+        assert_eq!(parse_success_status_argument(&attr).unwrap(), Some((207, "Successful response")));
+
+        let attr: proc_macro2::TokenStream = parse_quote!("jobs", skip);
+        assert_eq!(parse_success_status_argument(&attr).unwrap(), None);
+
+        let attr: proc_macro2::TokenStream = parse_quote!(success_status = 404);
+        assert!(parse_success_status_argument(&attr).is_err());
+    }
+
+    #[test]
+    fn test_redirect_status() {
+        let input: ItemFn = parse_quote! {
+            async fn old_profile() -> Redirect {
+                Redirect::permanent("/profile")
+            }
+        };
+        assert_eq!(redirect_status(&input), Some((308, "Permanent Redirect")));
+
+        let input: ItemFn = parse_quote! {
+            async fn login_callback() -> Result<axum::response::Redirect, AuthError> {
+                let target = session_target()?;
+                Ok(Redirect::to(&target))
+            }
+        };
+        assert_eq!(redirect_status(&input), Some((303, "See Other")));
+
+        // Mixed constructors, or none at all, fall back to 307
+        let input: ItemFn = parse_quote! {
+            async fn moved(Path(id): Path<u32>) -> Redirect {
+                if id == 0 { Redirect::to("/") } else { Redirect::temporary("/items") }
+            }
+        };
+        assert_eq!(redirect_status(&input), Some((307, "Temporary Redirect")));
+
+        let input: ItemFn = parse_quote! {
+            async fn get_user() -> Json<User> {
+                Json(User::default())
+            }
+        };
+        assert_eq!(redirect_status(&input), None);
+    }
+
+    #[test]
+    fn test_has_success_type_named() {
+        let output: ReturnType = parse_quote! {
+            -> Sse<impl Stream<Item = Result<Event, Infallible>>>
+        };
+        assert!(has_success_type_named(&output, "Sse"));
+
+        let output: ReturnType = parse_quote! {
+            -> Result<axum::response::sse::Sse<EventStream>, ApiError>
+        };
+        assert!(has_success_type_named(&output, "Sse"));
+        assert!(!has_success_type_named(&output, "ApiError"));
+
+        let output: ReturnType = parse_quote! {
+            -> Json<Sse>
+        };
+        assert!(!has_success_type_named(&output, "Sse"));
+        assert!(!has_success_type_named(&ReturnType::Default, "Sse"));
+    }
+
+    #[test]
+    fn test_typed_schema_value() {
+        assert_eq!(typed_schema_value(r#"{"type":"integer","format":"int32"}"#, "42"), "42");
+        assert_eq!(typed_schema_value(r#"{"type":"integer"}"#, "4.5"), r#""4.5""#);
+        assert_eq!(typed_schema_value(r#"{"type":"number"}"#, "0.25"), "0.25");
+        assert_eq!(typed_schema_value(r#"{"type":"boolean"}"#, "true"), "true");
+        assert_eq!(typed_schema_value(r#"{"type":"boolean"}"#, "yes"), r#""yes""#);
+        assert_eq!(typed_schema_value(r#"{"type":"array","items":{"type":"string"}}"#, r#"["a","b"]"#), r#"["a","b"]"#);
+        assert_eq!(typed_schema_value(r#"{"type":"string"}"#, "42"), r#""42""#);
+        assert_eq!(typed_schema_value(r##"{"$ref":"#/components/schemas/Tag"}"##, "42"), r#""42""#);
+    }
+
     #[test]
     fn test_sanitize_type_for_identifier() {
         assert_eq!(sanitize_type_for_identifier("Vec<String>"), "Vec_String");
@@ -1890,6 +3557,16 @@ mod tests {
         assert_eq!(sanitize_type_for_identifier("*const u8"), "const_u8");
     }
 
+    #[test]
+    fn test_join_description_lines() {
+        assert_eq!(join_description_lines(&[]), None);
+        assert_eq!(join_description_lines(&["", "  "]), None);
+        assert_eq!(
+            join_description_lines(&["", "First paragraph", "continued", "", "", "- one", "  - nested", ""]).as_deref(),
+            Some("First paragraph\ncontinued\n\n- one\n  - nested")
+        );
+    }
+
     #[test]
     fn test_extract_docs_simple() {
         let attrs = vec![
@@ -1950,6 +3627,103 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_response_attributes_become_response_entries() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[doc = " Get a user"]),
+            parse_quote!(#[response(status = 200, description = "The user", schema = "UserResponse")]),
+            parse_quote!(#[response(status = 204, schema = "UserResponse")]),
+            parse_quote!(#[response(status = "4XX", description = "Client error", schema = "ApiError", content_type = "application/problem+json")]),
+        ];
+
+        let responses = parse_response_attributes(&attrs).unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(
+            response_attribute_entries(&responses),
+            [
+                "200: The user",
+                "Schema: 200 UserResponse",
+                "204:",
+                "4XX: Client error",
+                "Schema: 4XX ApiError",
+                "ContentType: 4XX application/problem+json",
+            ]
+        );
+
+        let invalid: Vec<Attribute> = vec![parse_quote!(#[response(status = 200, colour = "red")])];
+        assert!(parse_response_attributes(&invalid).is_err());
+        let invalid: Vec<Attribute> = vec![parse_quote!(#[response(status = 20)])];
+        assert!(parse_response_attributes(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_request_body_attribute_entries() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[doc = " Upload a user"]),
+            parse_quote!(#[request_body(schema = "CreateUserRequest", content_type = "application/json", required = false)]),
+        ];
+
+        let request_body = parse_request_body_attribute(&attrs).unwrap().unwrap();
+        assert_eq!(
+            request_body_attribute_entries(&request_body),
+            ["Schema: CreateUserRequest", "ContentType: application/json", "Required: false"]
+        );
+
+        assert_eq!(parse_request_body_attribute(&attrs[..1]).unwrap(), None);
+        let invalid: Vec<Attribute> = vec![parse_quote!(#[request_body(required = "no")])];
+        assert!(parse_request_body_attribute(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_callback_attribute_entries() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[doc = " Subscribe to events"]),
+            parse_quote!(#[callback(name = "onEvent", expression = "{$request.body#/callbackUrl}", description = "Event delivery", schema = "EventPayload")]),
+            parse_quote!(#[callback(name = "onPing", expression = "{$request.body#/pingUrl}", method = "GET")]),
+        ];
+
+        let callbacks = parse_callback_attributes(&attrs).unwrap();
+        assert_eq!(
+            callback_attribute_entries(&callbacks),
+            [
+                "onEvent POST {$request.body#/callbackUrl}: Event delivery",
+                "Schema: onEvent EventPayload",
+                "onPing GET {$request.body#/pingUrl}",
+            ]
+        );
+
+        let invalid: Vec<Attribute> = vec![parse_quote!(#[callback(name = "onEvent")])];
+        assert!(parse_callback_attributes(&invalid).is_err());
+        let invalid: Vec<Attribute> = vec![parse_quote!(#[callback(name = "onEvent", expression = "{$url}", method = "SEND")])];
+        assert!(parse_callback_attributes(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_external_docs_attribute_and_see_also() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[doc = " List users"]),
+            parse_quote!(#[external_docs(url = "https://example.com/guides/users", description = "User guide")]),
+        ];
+        assert_eq!(
+            parse_external_docs_attribute(&attrs).unwrap().as_deref(),
+            Some("https://example.com/guides/users User guide")
+        );
+        assert_eq!(parse_external_docs_attribute(&attrs[..1]).unwrap(), None);
+        let invalid: Vec<Attribute> = vec![parse_quote!(#[external_docs(description = "No link")])];
+        assert!(parse_external_docs_attribute(&invalid).is_err());
+
+        assert_eq!(
+            see_also_entry("- Pagination guide: https://example.com/pagination").as_deref(),
+            Some("https://example.com/pagination Pagination guide")
+        );
+        assert_eq!(
+            see_also_entry("https://example.com/pagination - Pagination guide").as_deref(),
+            Some("https://example.com/pagination Pagination guide")
+        );
+        assert_eq!(see_also_entry("- https://example.com/pagination.").as_deref(), Some("https://example.com/pagination"));
+        assert_eq!(see_also_entry("- The pagination guide"), None);
+    }
+
     #[test]
     fn test_extract_docs_with_responses() {
         let attrs = vec![
@@ -0,0 +1,183 @@
+//! Tests for the schemas and status codes `#[api_error]` generates
+
+use axum::Json;
+use machined_openapi_gen::{api_error, api_handler, api_router, get, OpenApiSchema};
+use serde::Serialize;
+
+#[api_error]
+#[derive(Serialize)]
+#[serde(tag = "error", content = "details")]
+enum LookupError {
+    /// 404: Record not found
+    #[serde(rename = "not_found")]
+    NotFound { id: u32, shard: Option<String> },
+
+    /// 400: Malformed record id
+    #[serde(rename = "bad_id")]
+    BadId(String),
+
+    /// 503: Storage unavailable
+    #[serde(rename = "unavailable")]
+    Unavailable,
+}
+
+#[api_error]
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum QuotaError {
+    /// 429: Too many requests
+    RateLimited { retry_after: u64 },
+}
+
+#[api_error]
+#[derive(Serialize)]
+enum OrderError {
+    /// The order already shipped
+    #[status(409)]
+    AlreadyShipped,
+
+    /// 404: Order not found
+    Missing,
+
+    /// 400: Doc says bad request, but the attribute wins
+    #[status(422)]
+    Invalid { field: String },
+
+    Unexpected,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct Record {
+    id: u32,
+}
+
+/// Look up a record
+#[api_handler]
+async fn lookup_record() -> Result<Json<Record>, LookupError> {
+    Err(LookupError::Unavailable)
+}
+
+fn error_shapes(schema: &str) -> Vec<serde_json::Value> {
+    let schema: serde_json::Value = serde_json::from_str(schema).expect("schema should be valid JSON");
+    assert_eq!(schema["required"], serde_json::json!(["error"]));
+    schema["properties"]["error"]["oneOf"].as_array().expect("error should be a oneOf").clone()
+}
+
+#[test]
+fn test_adjacent_tagged_error_variants_have_fields() {
+    let shapes = error_shapes(&LookupError::schema());
+    assert_eq!(shapes.len(), 3);
+
+    let not_found = &shapes[0];
+    assert_eq!(not_found["properties"]["error"]["enum"], serde_json::json!(["not_found"]));
+    assert_eq!(not_found["required"], serde_json::json!(["error", "details"]));
+    let details = &not_found["properties"]["details"];
+    assert_eq!(details["properties"]["id"]["type"], "integer");
+    assert_eq!(details["properties"]["shard"]["type"], "string");
+    assert_eq!(details["required"], serde_json::json!(["id"]));
+
+    assert_eq!(shapes[1]["properties"]["details"]["type"], "string");
+
+    let unavailable = &shapes[2];
+    assert_eq!(unavailable["properties"]["error"]["enum"], serde_json::json!(["unavailable"]));
+    assert_eq!(unavailable["required"], serde_json::json!(["error"]));
+}
+
+#[test]
+fn test_internal_tagged_error_variant_fields_sit_next_to_tag() {
+    let shapes = error_shapes(&QuotaError::schema());
+    assert_eq!(shapes[0]["properties"]["kind"]["enum"], serde_json::json!(["rate_limited"]));
+    assert_eq!(shapes[0]["properties"]["retry_after"]["type"], "integer");
+    assert_eq!(shapes[0]["required"], serde_json::json!(["kind", "retry_after"]));
+}
+
+#[test]
+fn test_error_schema_registered_in_components() {
+    let mut router = api_router!("Records", "1.0.0").route("/records/{id}", get(lookup_record));
+    let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+
+    let registered = &spec["components"]["schemas"]["LookupError"];
+    assert_eq!(registered["properties"]["error"]["oneOf"][0]["properties"]["details"]["properties"]["id"]["type"], "integer");
+}
+
+#[test]
+fn test_error_status_codes_still_come_from_docs() {
+    use axum::response::IntoResponse;
+
+    assert_eq!(LookupError::NotFound { id: 1, shard: None }.into_response().status(), 404);
+    assert_eq!(LookupError::BadId("x".to_string()).into_response().status(), 400);
+    assert_eq!(LookupError::Unavailable.into_response().status(), 503);
+    assert_eq!(QuotaError::RateLimited { retry_after: 30 }.into_response().status(), 429);
+}
+
+#[test]
+fn test_status_attribute_takes_priority_over_docs() {
+    use axum::response::IntoResponse;
+
+    assert_eq!(OrderError::AlreadyShipped.into_response().status(), 409);
+    assert_eq!(OrderError::Missing.into_response().status(), 404);
+    assert_eq!(OrderError::Invalid { field: "sku".to_string() }.into_response().status(), 422);
+    assert_eq!(OrderError::Unexpected.into_response().status(), 500);
+}
+
+#[api_error]
+#[derive(Serialize)]
+enum AccountError {
+    /// 404: No account exists with that id
+    UserNotFound { id: u32 },
+
+    /// 404: The account was closed
+    AccountClosed,
+
+    /// Account is locked after too many failed logins
+    #[status(423)]
+    Locked,
+}
+
+/// Fetch an account
+///
+/// # Responses
+/// - 200: The account Record
+/// - 404: Not found
+/// - 423: Locked
+#[api_handler]
+async fn fetch_account() -> Result<Json<Record>, AccountError> {
+    Err(AccountError::AccountClosed)
+}
+
+#[test]
+fn test_error_response_descriptions_come_from_variants() {
+    let mut router = api_router!("Accounts", "1.0.0").route("/accounts/{id}", get(fetch_account));
+    let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+
+    let responses = &spec["paths"]["/accounts/{id}"]["get"]["responses"];
+    assert_eq!(responses["404"]["description"], "No account exists with that id");
+    assert_eq!(responses["423"]["description"], "Account is locked after too many failed logins");
+    assert_eq!(responses["200"]["description"], "The account Record");
+    // No variant maps to 500, so the handler's description stays
+    assert_eq!(responses["500"]["description"], "Internal server error occurred");
+
+    use axum::response::IntoResponse;
+    assert_eq!(AccountError::UserNotFound { id: 1 }.into_response().status(), 404);
+    assert_eq!(AccountError::Locked.into_response().status(), 423);
+}
+
+/// Archive a record
+///
+/// # Responses
+/// - 200: The archived Record
+/// - default: Unexpected error
+#[api_handler]
+async fn archive_record() -> Result<Json<Record>, LookupError> {
+    Err(LookupError::Unavailable)
+}
+
+#[test]
+fn test_default_response_points_at_error_schema() {
+    let mut router = api_router!("Records", "1.0.0").route("/records/{id}/archive", get(archive_record));
+    let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+
+    let default = &spec["paths"]["/records/{id}/archive"]["get"]["responses"]["default"];
+    assert_eq!(default["description"], "Unexpected error");
+    assert_eq!(default["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/LookupError");
+}
@@ -0,0 +1,1229 @@
+//! Tests for what `#[api_handler]` records about a handler's signature
+
+use axum::{http::StatusCode, Json};
+use machined_openapi_gen::{api_handler, api_router, delete, get, post, OpenApiSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Widget {
+    name: String,
+}
+
+/// Create a widget
+#[api_handler]
+async fn create_widget(Json(widget): Json<Widget>) -> (StatusCode, Json<Widget>) {
+    (StatusCode::CREATED, Json(widget))
+}
+
+/// Queue a widget for review
+///
+/// # Responses
+/// - 202: Widget queued for review
+#[api_handler]
+async fn queue_widget(Json(widget): Json<Widget>) -> Result<(StatusCode, Json<Widget>), StatusCode> {
+    Ok((StatusCode::ACCEPTED, Json(widget)))
+}
+
+fn spec_value(json: &str) -> serde_json::Value {
+    serde_json::from_str(json).expect("generated spec should be valid JSON")
+}
+
+#[test]
+fn test_tuple_response_documents_status_and_schema() {
+    let mut router = api_router!("Widgets", "1.0.0").route("/widgets", post(create_widget));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/widgets"]["post"]["responses"];
+    assert_eq!(responses["201"]["description"], "Created");
+    assert_eq!(
+        responses["201"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Widget"
+    );
+    assert!(responses.get("200").is_none());
+}
+
+#[test]
+fn test_tuple_response_keeps_documented_status() {
+    let mut router = api_router!("Widgets", "1.0.0").route("/review", post(queue_widget));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/review"]["post"]["responses"];
+    assert_eq!(responses["202"]["description"], "Widget queued for review");
+    assert_eq!(
+        responses["202"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Widget"
+    );
+    assert!(responses.get("201").is_none());
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Gadget {
+    id: u32,
+}
+
+/// List gadgets
+///
+/// # Responses
+/// - 200: All gadgets
+#[api_handler]
+async fn list_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_list_response_is_array_of_refs() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets", get(list_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    let schema = &spec["paths"]["/gadgets"]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+    assert_eq!(schema["type"], "array");
+    assert_eq!(schema["items"]["$ref"], "#/components/schemas/Gadget");
+    assert_eq!(spec["components"]["schemas"]["Gadget"]["properties"]["id"]["type"], "integer");
+}
+
+/// Register a gadget
+///
+/// # Responses
+/// - 201: Gadget registered
+///   headers:
+///   - Location (string): URL of the new gadget
+/// - 409: Gadget already exists
+///   headers:
+///
+/// # Request Body
+/// - id (integer): Gadget id
+#[api_handler]
+async fn register_gadget(Json(gadget): Json<Gadget>) -> (StatusCode, Json<Gadget>) {
+    (StatusCode::CREATED, Json(gadget))
+}
+
+#[test]
+fn test_response_headers_block() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets", post(register_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/gadgets"]["post"]["responses"];
+    assert_eq!(responses["201"]["headers"]["Location"]["description"], "URL of the new gadget");
+    assert_eq!(responses["201"]["headers"]["Location"]["schema"]["type"], "string");
+    assert_eq!(responses["409"]["description"], "Gadget already exists");
+
+    // Request body fields after an open headers block aren't mistaken for headers
+    assert!(responses["409"].get("headers").is_none());
+}
+
+/// Clone a gadget
+///
+/// # Responses
+/// - 201: Gadget cloned
+///   headers:
+///   - Location (string): URL of the clone
+///   links:
+///   - GetClone (getGadget, id = $response.body#/id): Fetch the clone
+/// - 404: No such gadget
+#[api_handler]
+async fn clone_gadget(Json(gadget): Json<Gadget>) -> (StatusCode, Json<Gadget>) {
+    (StatusCode::CREATED, Json(gadget))
+}
+
+#[test]
+fn test_response_links_block() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/clone", post(clone_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let created = &spec["paths"]["/gadgets/clone"]["post"]["responses"]["201"];
+    assert_eq!(created["headers"]["Location"]["description"], "URL of the clone");
+    assert!(created["headers"].get("GetClone").is_none());
+
+    let link = &created["links"]["GetClone"];
+    assert_eq!(link["operationId"], "getGadget");
+    assert_eq!(link["parameters"]["id"], "$response.body#/id");
+    assert_eq!(link["description"], "Fetch the clone");
+
+    let missing = &spec["paths"]["/gadgets/clone"]["post"]["responses"]["404"];
+    assert!(missing.get("links").is_none());
+}
+
+/// Export gadgets
+///
+/// # Responses
+/// - 200: All gadgets
+///   Content-Type: application/json, text/csv
+#[api_handler]
+async fn export_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_response_offers_json_and_csv() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/export", get(export_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    let content = &spec["paths"]["/gadgets/export"]["get"]["responses"]["200"]["content"];
+    assert_eq!(content["application/json"]["schema"]["type"], "array");
+    assert_eq!(content["application/json"]["schema"]["items"]["$ref"], "#/components/schemas/Gadget");
+    assert_eq!(content["text/csv"]["schema"]["type"], "string");
+}
+
+/// Sync gadgets with the upstream catalog
+///
+/// # Responses
+/// - 2XX: Success
+/// - 204: Nothing to sync
+/// - 5XX: Server error
+#[api_handler]
+async fn sync_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_status_class_response_keys() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/sync", post(sync_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/gadgets/sync"]["post"]["responses"];
+    assert_eq!(responses["2XX"]["description"], "Success");
+    assert_eq!(responses["2XX"]["content"]["application/json"]["schema"]["items"]["$ref"], "#/components/schemas/Gadget");
+    assert!(responses["204"].get("content").is_none());
+    assert_eq!(responses["5XX"]["description"], "Server error");
+}
+
+/// Replace a gadget
+///
+/// # Responses
+/// - 200: Ignored because the attributes below take over
+#[api_handler]
+#[response(status = 201, description = "Gadget replaced", schema = "Gadget")]
+#[response(status = 204)]
+#[response(status = 422, description = "Rejected", schema = "Widget", content_type = "application/problem+json")]
+async fn replace_gadget(Json(gadget): Json<Gadget>) -> Json<Gadget> {
+    Json(gadget)
+}
+
+#[test]
+fn test_response_attributes_override_doc_responses() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/replace", post(replace_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/gadgets/replace"]["post"]["responses"];
+    assert!(responses.get("200").is_none());
+    assert_eq!(responses["201"]["description"], "Gadget replaced");
+    assert_eq!(responses["201"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Gadget");
+    assert_eq!(responses["204"]["description"], "No Content");
+    assert!(responses["204"].get("content").is_none());
+    assert_eq!(responses["422"]["content"]["application/problem+json"]["schema"]["$ref"], "#/components/schemas/Widget");
+}
+
+/// Import gadgets from a raw upload
+#[api_handler]
+#[request_body(schema = "Gadget", content_type = "application/json", required = false)]
+async fn import_gadgets(body: axum::body::Bytes) -> StatusCode {
+    if body.is_empty() { StatusCode::NO_CONTENT } else { StatusCode::ACCEPTED }
+}
+
+/// Scrap a gadget
+#[api_handler]
+async fn scrap_gadget(axum::extract::Path(id): axum::extract::Path<u32>) -> Result<StatusCode, StatusCode> {
+    if id == 0 { Err(StatusCode::NOT_FOUND) } else { Ok(StatusCode::NO_CONTENT) }
+}
+
+#[test]
+fn test_status_code_handler_defaults_to_no_content() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}", delete(scrap_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/gadgets/{id}"]["delete"]["responses"];
+    assert!(responses.get("200").is_none());
+    assert_eq!(responses["204"]["description"], "No Content");
+    assert!(responses["204"].get("content").is_none());
+    assert!(responses.get("500").is_some());
+}
+
+/// Queue a gadget for recalibration
+#[api_handler("gadgets", success_status = 202)]
+async fn recalibrate_gadget(axum::extract::Path(id): axum::extract::Path<u32>) -> StatusCode {
+    calibration_status(id)
+}
+
+fn calibration_status(id: u32) -> StatusCode {
+    if id == 0 { StatusCode::NOT_FOUND } else { StatusCode::ACCEPTED }
+}
+
+#[test]
+fn test_success_status_argument_documents_status_only_handler() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}/recalibrate", post(recalibrate_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let operation = &spec["paths"]["/gadgets/{id}/recalibrate"]["post"];
+    assert_eq!(operation["tags"], serde_json::json!(["gadgets"]));
+    let responses = &operation["responses"];
+    assert!(responses.get("200").is_none());
+    assert!(responses.get("204").is_none());
+    assert_eq!(responses["202"]["description"], "Accepted");
+}
+
+/// Send the client to a gadget's manual
+#[api_handler]
+async fn gadget_manual(axum::extract::Path(id): axum::extract::Path<u32>) -> Result<axum::response::Redirect, StatusCode> {
+    if id == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(axum::response::Redirect::temporary(&format!("https://docs.example.com/gadgets/{id}")))
+}
+
+#[test]
+fn test_redirect_handler_documents_location_header() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}/manual", get(gadget_manual));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/gadgets/{id}/manual"]["get"]["responses"];
+    assert!(responses.get("200").is_none());
+    assert_eq!(responses["307"]["description"], "Temporary Redirect");
+    assert!(responses["307"].get("content").is_none());
+    assert_eq!(responses["307"]["headers"]["Location"]["schema"]["type"], "string");
+    assert_eq!(responses["307"]["headers"]["Location"]["description"], "URL the client is redirected to");
+}
+
+/// Follow a gadget's sensor readings as they arrive
+#[api_handler]
+async fn gadget_readings(
+    axum::extract::Path(id): axum::extract::Path<u32>,
+) -> axum::response::Sse<impl futures_util::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>> {
+    let reading = axum::response::sse::Event::default().data(format!("gadget {id} is nominal"));
+    axum::response::Sse::new(futures_util::stream::iter([Ok(reading)]))
+}
+
+#[test]
+fn test_sse_handler_documents_event_stream() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}/readings", get(gadget_readings));
+    let spec = spec_value(&router.openapi_json());
+
+    let ok = &spec["paths"]["/gadgets/{id}/readings"]["get"]["responses"]["200"];
+    assert_eq!(ok["description"], "Stream of server-sent events");
+    let content = ok["content"].as_object().unwrap();
+    assert_eq!(content.keys().collect::<Vec<_>>(), ["text/event-stream"]);
+    assert_eq!(content["text/event-stream"]["schema"]["type"], "string");
+}
+
+/// Reset a gadget, optionally to a widget's settings
+#[api_handler]
+async fn reset_gadget(axum::extract::Path(_id): axum::extract::Path<u32>, body: Option<Json<Widget>>) -> StatusCode {
+    match body {
+        Some(Json(_widget)) => StatusCode::OK,
+        None => StatusCode::NO_CONTENT,
+    }
+}
+
+#[test]
+fn test_optional_json_body_is_not_required() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}/reset", post(reset_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let request_body = &spec["paths"]["/gadgets/{id}/reset"]["post"]["requestBody"];
+    assert_eq!(request_body["required"], false);
+    assert_eq!(request_body["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Widget");
+}
+
+/// Archive a gadget
+///
+/// Archived gadgets keep their history
+/// but stop reporting readings.
+///
+///
+/// Archiving is refused when:
+/// - the gadget is still calibrating
+/// - a firmware update is pending
+///
+/// # Responses
+/// - 204: Gadget archived
+#[api_handler]
+async fn archive_gadget(axum::extract::Path(_id): axum::extract::Path<u32>) -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+#[test]
+fn test_description_keeps_paragraphs_and_lists() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}/archive", post(archive_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let operation = &spec["paths"]["/gadgets/{id}/archive"]["post"];
+    assert_eq!(operation["summary"], "Archive a gadget");
+    assert_eq!(
+        operation["description"],
+        "Archived gadgets keep their history\nbut stop reporting readings.\n\n\
+         Archiving is refused when:\n- the gadget is still calibrating\n- a firmware update is pending"
+    );
+    assert_eq!(operation["responses"]["204"]["description"], "Gadget archived");
+}
+
+mod inventory_counts {
+    use super::*;
+
+    /// Count gadgets in stock
+    #[api_handler(tag_from_module)]
+    pub async fn count_gadgets() -> Json<u32> {
+        Json(0)
+    }
+
+    /// Count gadgets on order
+    #[api_handler("orders", tag_from_module)]
+    pub async fn count_ordered_gadgets() -> Json<u32> {
+        Json(0)
+    }
+}
+
+#[test]
+fn test_tag_from_module_uses_enclosing_module() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets/count", get(inventory_counts::count_gadgets))
+        .route("/gadgets/ordered/count", get(inventory_counts::count_ordered_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    assert_eq!(spec["paths"]["/gadgets/count"]["get"]["tags"], serde_json::json!(["inventory_counts"]));
+    // Explicit tags win over the module name
+    assert_eq!(spec["paths"]["/gadgets/ordered/count"]["get"]["tags"], serde_json::json!(["orders"]));
+}
+
+/// Rename a gadget using a widget-shaped body
+#[api_handler]
+#[request_body(schema = "Widget")]
+async fn rename_gadget(Json(gadget): Json<Gadget>) -> Json<Gadget> {
+    Json(gadget)
+}
+
+#[test]
+fn test_request_body_attribute_documents_body() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets/import", post(import_gadgets))
+        .route("/gadgets/rename", post(rename_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let import = &spec["paths"]["/gadgets/import"]["post"]["requestBody"];
+    assert_eq!(import["required"], false);
+    assert_eq!(import["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Gadget");
+
+    // The attribute takes priority over the Json<Gadget> extractor
+    let rename = &spec["paths"]["/gadgets/rename"]["post"]["requestBody"];
+    assert_eq!(rename["required"], true);
+    assert_eq!(rename["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Widget");
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct LoginForm {
+    username: String,
+    password: String,
+}
+
+/// Log in with a classic HTML form
+#[api_handler]
+async fn login(axum::Form(form): axum::Form<LoginForm>) -> StatusCode {
+    if form.username.is_empty() || form.password.is_empty() { StatusCode::UNAUTHORIZED } else { StatusCode::NO_CONTENT }
+}
+
+/// Log in from a legacy client
+#[api_handler]
+#[request_body(schema = "LoginForm", content_type = "application/x-www-form-urlencoded")]
+async fn legacy_login(body: axum::body::Bytes) -> StatusCode {
+    if body.is_empty() { StatusCode::UNAUTHORIZED } else { StatusCode::NO_CONTENT }
+}
+
+#[test]
+fn test_form_encoded_login_body_references_schema() {
+    let mut router = api_router!("Auth", "1.0.0")
+        .route("/login", post(login))
+        .route("/legacy/login", post(legacy_login));
+    let spec = spec_value(&router.openapi_json());
+
+    for path in ["/login", "/legacy/login"] {
+        let content = &spec["paths"][path]["post"]["requestBody"]["content"];
+        assert_eq!(content.as_object().unwrap().len(), 1, "{path}");
+        assert_eq!(
+            content["application/x-www-form-urlencoded"]["schema"]["$ref"],
+            "#/components/schemas/LoginForm",
+            "{path}"
+        );
+    }
+    assert_eq!(spec["components"]["schemas"]["LoginForm"]["required"], serde_json::json!(["username", "password"]));
+}
+
+/// Download a gadget's firmware image
+#[api_handler]
+#[response(status = 200, description = "Firmware image", content_type = "application/octet-stream")]
+async fn download_firmware() -> Vec<u8> {
+    Vec::new()
+}
+
+/// Upload a gadget's firmware image
+///
+/// # Request Body
+/// Content-Type: application/octet-stream
+/// The raw firmware image
+#[api_handler]
+async fn upload_firmware(body: axum::body::Bytes) -> StatusCode {
+    if body.is_empty() { StatusCode::BAD_REQUEST } else { StatusCode::NO_CONTENT }
+}
+
+#[test]
+fn test_binary_download_and_upload() {
+    let mut router = api_router!("Firmware", "1.0.0")
+        .route("/firmware", get(download_firmware).put(upload_firmware));
+    let spec = spec_value(&router.openapi_json());
+    let binary = serde_json::json!({ "type": "string", "format": "binary" });
+
+    let download = &spec["paths"]["/firmware"]["get"]["responses"]["200"]["content"];
+    assert_eq!(download.as_object().unwrap().len(), 1);
+    assert_eq!(download["application/octet-stream"]["schema"], binary);
+
+    let upload = &spec["paths"]["/firmware"]["put"]["requestBody"];
+    assert_eq!(upload["description"], "The raw firmware image");
+    assert_eq!(upload["content"]["application/octet-stream"]["schema"], binary);
+}
+
+/// List gadgets the old way
+#[api_handler]
+#[deprecated(note = "use list_gadgets")]
+async fn list_gadgets_v1() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+/// Count gadgets the old way
+#[api_handler(deprecated, "gadgets")]
+async fn count_gadgets_v1() -> Json<u32> {
+    Json(0)
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_handlers_are_marked() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/v1/gadgets", get(list_gadgets_v1))
+        .route("/v1/gadgets/count", get(count_gadgets_v1))
+        .route("/gadgets", get(list_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    assert_eq!(spec["paths"]["/v1/gadgets"]["get"]["deprecated"], true);
+    let count = &spec["paths"]["/v1/gadgets/count"]["get"];
+    assert_eq!(count["deprecated"], true);
+    assert_eq!(count["tags"], serde_json::json!(["gadgets"]));
+    assert!(spec["paths"]["/gadgets"]["get"].get("deprecated").is_none());
+}
+
+/// Find gadgets
+///
+/// # See Also
+/// - Query syntax: https://example.com/guides/search
+#[api_handler]
+async fn find_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+/// Dump gadgets
+///
+/// # See Also
+/// - https://example.com/guides/search
+#[api_handler]
+#[external_docs(url = "https://example.com/guides/export", description = "Export formats")]
+async fn dump_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_operation_external_docs() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets/find", get(find_gadgets))
+        .route("/gadgets/dump", get(dump_gadgets))
+        .route("/gadgets", get(list_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    assert_eq!(
+        spec["paths"]["/gadgets/find"]["get"]["externalDocs"],
+        serde_json::json!({ "url": "https://example.com/guides/search", "description": "Query syntax" })
+    );
+    // The attribute takes priority over the doc section
+    assert_eq!(
+        spec["paths"]["/gadgets/dump"]["get"]["externalDocs"],
+        serde_json::json!({ "url": "https://example.com/guides/export", "description": "Export formats" })
+    );
+    assert!(spec["paths"]["/gadgets"]["get"].get("externalDocs").is_none());
+}
+
+#[test]
+fn test_nested_router_keeps_handler_docs() {
+    let v1 = api_router!("Gadgets v1", "1.0.0")
+        .route("/gadgets", get(list_gadgets))
+        .route("/gadgets/find", get(find_gadgets));
+    let mut router = api_router!("Gadgets", "2.0.0").nest("/v1", v1);
+    let spec = spec_value(&router.openapi_json());
+
+    assert!(spec["paths"].get("/gadgets").is_none());
+    assert_eq!(spec["paths"]["/v1/gadgets"]["get"]["operationId"], "list_gadgets");
+    assert_eq!(spec["paths"]["/v1/gadgets/find"]["get"]["summary"], "Find gadgets");
+}
+
+/// Show the signed-in user's gadgets
+#[api_handler]
+async fn my_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+/// Gadget catalogue anyone can browse
+#[api_handler(public)]
+async fn gadget_catalogue() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+/// Gadget feed for partners
+///
+/// # Security
+/// - partnerKey
+#[api_handler]
+async fn partner_feed() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_default_security_with_public_override() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .default_security("sessionAuth")
+        .route("/me/gadgets", get(my_gadgets))
+        .route("/catalogue", get(gadget_catalogue))
+        .route("/partners/feed", get(partner_feed));
+    let spec = spec_value(&router.openapi_json());
+
+    assert_eq!(spec["security"], serde_json::json!([{ "sessionAuth": [] }]));
+    assert!(spec["components"]["securitySchemes"]["sessionAuth"].is_object());
+    // Inherits the default, so nothing is repeated on the operation
+    assert!(spec["paths"]["/me/gadgets"]["get"].get("security").is_none());
+    assert_eq!(spec["paths"]["/catalogue"]["get"]["security"], serde_json::json!([]));
+    assert_eq!(spec["paths"]["/partners/feed"]["get"]["security"], serde_json::json!([{ "partnerKey": [] }]));
+}
+
+#[test]
+fn test_public_handler_without_default_security() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/catalogue", get(gadget_catalogue));
+    let spec = spec_value(&router.openapi_json());
+
+    assert!(spec.get("security").is_none());
+    assert!(spec["paths"]["/catalogue"]["get"].get("security").is_none());
+}
+
+/// Page through gadgets
+///
+/// # Parameters
+/// - page (ref)
+/// - category (query): Only gadgets in this category
+#[api_handler]
+async fn page_gadgets() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+/// Page through widgets
+///
+/// # Parameters
+/// - page (ref)
+/// - per_page (ref)
+#[api_handler]
+async fn page_widgets() -> Json<Vec<Widget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_shared_parameter_referenced_from_two_operations() {
+    use machined_openapi_gen::openapi::{Parameter, ReferenceOr, Schema};
+
+    let page = Parameter {
+        name: "page".to_string(),
+        location: "query".to_string(),
+        description: Some("Page number, starting at 1".to_string()),
+        required: false,
+        deprecated: false,
+        style: None,
+        explode: None,
+        schema: ReferenceOr::new_item(Schema { schema_type: Some("integer".to_string()), ..Default::default() }),
+        example: Some(serde_json::json!(1)),
+    };
+    let mut router = api_router!("Catalogue", "1.0.0")
+        .shared_parameter("page", page)
+        .route("/gadgets/pages", get(page_gadgets))
+        .route("/widgets/pages", get(page_widgets));
+    let spec = spec_value(&router.openapi_json());
+
+    let shared = &spec["components"]["parameters"]["page"];
+    assert_eq!(shared["in"], "query");
+    assert_eq!(shared["schema"]["type"], "integer");
+
+    let page_ref = serde_json::json!({ "$ref": "#/components/parameters/page" });
+    let gadgets = spec["paths"]["/gadgets/pages"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(gadgets[0]["name"], "category");
+    assert_eq!(gadgets[1], page_ref);
+    // per_page was never registered, so only page is referenced
+    assert_eq!(spec["paths"]["/widgets/pages"]["get"]["parameters"], serde_json::json!([page_ref]));
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct AuthFailure {
+    reason: String,
+}
+
+/// Rename a gadget
+///
+/// # Responses
+/// - 200: The renamed Gadget
+/// - 401: $ref Unauthorized
+#[api_handler]
+async fn relabel_gadget() -> Json<Gadget> {
+    Json(Gadget { id: 1 })
+}
+
+/// Retire a gadget
+///
+/// # Responses
+/// - 204: Retired
+/// - 401: $ref Unauthorized
+/// - 403: $ref Forbidden
+#[api_handler]
+async fn retire_gadget() -> Result<axum::http::StatusCode, (axum::http::StatusCode, Json<AuthFailure>)> {
+    Err((axum::http::StatusCode::UNAUTHORIZED, Json(AuthFailure { reason: "session expired".to_string() })))
+}
+
+#[test]
+fn test_shared_response_referenced_from_two_operations() {
+    use machined_openapi_gen::openapi::{MediaType, ReferenceOr, Response};
+    use std::collections::BTreeMap;
+
+    let unauthorized = Response {
+        description: "Missing or expired session".to_string(),
+        headers: None,
+        links: None,
+        content: Some(BTreeMap::from([(
+            "application/json".to_string(),
+            MediaType { schema: Some(ReferenceOr::new_ref("#/components/schemas/AuthFailure")) },
+        )])),
+    };
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .shared_response("Unauthorized", unauthorized)
+        .route("/gadgets/{id}/label", post(relabel_gadget))
+        .route("/gadgets/{id}/retire", post(retire_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let shared = &spec["components"]["responses"]["Unauthorized"];
+    assert_eq!(shared["description"], "Missing or expired session");
+    // The schema the shared response points at is registered alongside it
+    assert!(spec["components"]["schemas"]["AuthFailure"].is_object());
+
+    let unauthorized_ref = serde_json::json!({ "$ref": "#/components/responses/Unauthorized" });
+    let relabel = &spec["paths"]["/gadgets/{id}/label"]["post"]["responses"];
+    assert_eq!(relabel["401"], unauthorized_ref);
+    assert_eq!(relabel["200"]["description"], "The renamed Gadget");
+    let retire = &spec["paths"]["/gadgets/{id}/retire"]["post"]["responses"];
+    assert_eq!(retire["401"], unauthorized_ref);
+    // Forbidden was never registered, so its entry stays inline
+    assert!(retire["403"].get("$ref").is_none());
+}
+
+/// Pause a gadget
+///
+/// # Request Body
+/// Content-Type: application/json
+/// - reason (string): Why the gadget is being paused
+/// - until (string): When to resume it
+#[api_handler]
+async fn pause_gadget(body: axum::body::Bytes) -> StatusCode {
+    if body.is_empty() { StatusCode::BAD_REQUEST } else { StatusCode::NO_CONTENT }
+}
+
+/// Mute a gadget
+///
+/// # Request Body
+/// Content-Type: application/json
+/// - reason (string): Why the gadget is being paused
+/// - until (string): When to resume it
+#[api_handler]
+async fn mute_gadget(body: axum::body::Bytes) -> StatusCode {
+    if body.is_empty() { StatusCode::BAD_REQUEST } else { StatusCode::NO_CONTENT }
+}
+
+#[test]
+fn test_dedupe_inline_schemas_hoists_shared_body() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .dedupe_inline_schemas(true)
+        .route("/gadgets/pause", post(pause_gadget))
+        .route("/gadgets/mute", post(mute_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    assert_eq!(schemas.len(), 1);
+    // Named after the first operation to use it, in path order
+    let hoisted = &schemas["MuteGadgetRequest"];
+    assert_eq!(hoisted["properties"]["reason"]["type"], "string");
+
+    let body_ref = serde_json::json!({ "$ref": "#/components/schemas/MuteGadgetRequest" });
+    for path in ["/gadgets/pause", "/gadgets/mute"] {
+        assert_eq!(spec["paths"][path]["post"]["requestBody"]["content"]["application/json"]["schema"], body_ref);
+    }
+
+    // Without the flag both bodies stay inline
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets/pause", post(pause_gadget))
+        .route("/gadgets/mute", post(mute_gadget));
+    let spec = spec_value(&router.openapi_json());
+    let pause = &spec["paths"]["/gadgets/pause"]["post"]["requestBody"]["content"]["application/json"]["schema"];
+    assert_eq!(pause["properties"]["until"]["type"], "string");
+}
+
+// Stands in for a handler from another crate, which can't carry #[api_handler]
+async fn vendor_gadget_status() -> Json<Gadget> {
+    Json(Gadget { id: 1 })
+}
+
+#[test]
+fn test_route_documented_without_macro() {
+    use machined_openapi_gen::RouteDoc;
+
+    let resource = "gadget";
+    let mut router = api_router!("Gadgets", "1.0.0").route_documented(
+        "/gadgets/{id}/status",
+        get(vendor_gadget_status),
+        RouteDoc {
+            summary: "Gadget status".into(),
+            description: "Reported by the vendor firmware".into(),
+            parameters: vec!["id (path): Gadget id".into(), "verbose (query, optional): Include sensor readings".into()],
+            responses: vec!["200: Returns Gadget".into(), format!("404: No such {resource}")],
+            tags: vec![format!("{resource}s")],
+            ..RouteDoc::default()
+        },
+    );
+    let spec = spec_value(&router.openapi_json());
+
+    let operation = &spec["paths"]["/gadgets/{id}/status"]["get"];
+    assert_eq!(operation["summary"], "Gadget status");
+    assert_eq!(operation["description"], "Reported by the vendor firmware");
+    assert_eq!(operation["operationId"], "vendor_gadget_status");
+    assert_eq!(operation["tags"], serde_json::json!(["gadgets"]));
+    assert_eq!(operation["parameters"][0]["in"], "path");
+    assert_eq!(operation["parameters"][1]["name"], "verbose");
+    assert_eq!(operation["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Gadget");
+    assert_eq!(operation["responses"]["404"]["description"], "No such gadget");
+    assert!(operation.get("requestBody").is_none());
+    assert!(spec["components"]["schemas"]["Gadget"].is_object());
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct GadgetReceipt {
+    id: u32,
+    created: bool,
+}
+
+/// Create or replace a gadget
+///
+/// # Responses
+/// - 200: Updated Gadget
+/// - 201: Created GadgetReceipt
+#[api_handler]
+async fn upsert_gadget(Json(gadget): Json<Gadget>) -> (StatusCode, Json<GadgetReceipt>) {
+    (StatusCode::CREATED, Json(GadgetReceipt { id: gadget.id, created: true }))
+}
+
+#[test]
+fn test_two_success_codes_with_their_own_schemas() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/upsert", post(upsert_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let responses = &spec["paths"]["/gadgets/upsert"]["post"]["responses"];
+    assert_eq!(responses["200"]["description"], "Updated Gadget");
+    assert_eq!(responses["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Gadget");
+    assert_eq!(responses["201"]["description"], "Created GadgetReceipt");
+    assert_eq!(responses["201"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/GadgetReceipt");
+    assert!(spec["components"]["schemas"]["GadgetReceipt"].is_object());
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct GadgetEvent {
+    gadget_id: u32,
+    kind: String,
+}
+
+/// Watch a gadget
+///
+/// # Responses
+/// - 201: Subscription created
+#[api_handler]
+#[callback(name = "onGadgetEvent", expression = "{$request.body#/callbackUrl}", description = "Sent when the gadget changes", schema = "GadgetEvent")]
+async fn watch_gadget() -> (StatusCode, Json<GadgetEvent>) {
+    (StatusCode::CREATED, Json(GadgetEvent { gadget_id: 1, kind: "subscribed".to_string() }))
+}
+
+#[test]
+fn test_callback_attribute() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/watch", post(watch_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let callback = &spec["paths"]["/gadgets/watch"]["post"]["callbacks"]["onGadgetEvent"]["{$request.body#/callbackUrl}"];
+    assert_eq!(callback["post"]["description"], "Sent when the gadget changes");
+    assert_eq!(
+        callback["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/GadgetEvent"
+    );
+    assert_eq!(callback["post"]["responses"]["200"]["description"], "Successful response");
+    // The callback's body schema is registered like any other
+    assert!(spec["components"]["schemas"].get("GadgetEvent").is_some());
+}
+
+/// Check that a gadget exists
+///
+/// Answers with headers only.
+///
+/// # Responses
+/// - 200: The gadget exists
+/// - 404: No such gadget
+#[api_handler]
+async fn gadget_exists() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Allowed methods for gadgets
+#[api_handler]
+async fn gadget_options() -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+#[test]
+fn test_head_and_options_operations() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets", get(list_gadgets).head(gadget_exists))
+        .route("/gadgets/probe", machined_openapi_gen::head(gadget_exists))
+        .options("/gadgets/options", gadget_options);
+    let spec = spec_value(&router.openapi_json());
+
+    let gadgets = &spec["paths"]["/gadgets"];
+    assert!(gadgets["get"].is_object());
+    assert_eq!(gadgets["head"]["operationId"], "gadget_exists");
+    assert_eq!(gadgets["head"]["summary"], "Check that a gadget exists");
+    assert_eq!(gadgets["head"]["responses"]["404"]["description"], "No such gadget");
+    assert_eq!(spec["paths"]["/gadgets/probe"]["head"]["operationId"], "gadget_exists");
+    assert_eq!(spec["paths"]["/gadgets/options"]["options"]["operationId"], "gadget_options");
+}
+
+/// Echo the request back for diagnostics
+#[api_handler]
+async fn trace_gadgets() -> StatusCode {
+    StatusCode::OK
+}
+
+#[test]
+fn test_trace_operation() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets", get(list_gadgets).trace(trace_gadgets))
+        .route("/gadgets/echo", machined_openapi_gen::trace(trace_gadgets))
+        .trace("/gadgets/loop", trace_gadgets);
+    let spec = spec_value(&router.openapi_json());
+
+    for path in ["/gadgets", "/gadgets/echo", "/gadgets/loop"] {
+        let trace = &spec["paths"][path]["trace"];
+        assert_eq!(trace["operationId"], "trace_gadgets", "{path}");
+        assert_eq!(trace["summary"], "Echo the request back for diagnostics");
+    }
+    assert!(spec["paths"]["/gadgets"]["get"].is_object());
+}
+
+mod widgets {
+    use super::*;
+
+    /// List widgets
+    ///
+    /// # Responses
+    /// - 200: Returns Widget list
+    #[api_handler]
+    pub async fn list() -> Json<Vec<Widget>> {
+        Json(Vec::new())
+    }
+}
+
+mod gadgets {
+    use super::*;
+
+    /// List gadgets
+    ///
+    /// # Responses
+    /// - 200: Returns Gadget list
+    #[api_handler]
+    pub async fn list() -> Json<Vec<Gadget>> {
+        Json(Vec::new())
+    }
+}
+
+#[test]
+fn test_full_handler_paths_keep_same_named_handlers_apart() {
+    let mut router = api_router!("Catalog", "1.0.0")
+        .full_handler_paths(true)
+        .route("/widgets", get(widgets::list))
+        .route("/gadgets", get(gadgets::list));
+    let spec = spec_value(&router.openapi_json());
+
+    let widgets = &spec["paths"]["/widgets"]["get"];
+    let gadgets = &spec["paths"]["/gadgets"]["get"];
+    assert_eq!(widgets["summary"], "List widgets");
+    assert_eq!(gadgets["summary"], "List gadgets");
+    assert_eq!(widgets["operationId"], "api_handler::widgets::list");
+    assert_eq!(gadgets["operationId"], "api_handler::gadgets::list");
+    assert_eq!(
+        widgets["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+        "#/components/schemas/Widget"
+    );
+    assert_eq!(
+        gadgets["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+        "#/components/schemas/Gadget"
+    );
+    assert!(router.validate().is_empty());
+}
+
+#[test]
+fn test_bare_handler_names_collide_by_default() {
+    let router = api_router!("Catalog", "1.0.0")
+        .route("/widgets", get(widgets::list))
+        .route("/gadgets", get(gadgets::list));
+
+    let warnings = router.validate();
+    assert!(matches!(
+        warnings.as_slice(),
+        [machined_openapi_gen::SpecWarning::DuplicateOperationId { operation_id, .. }] if operation_id == "list"
+    ));
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct GadgetFilter {
+    category: String,
+    limit: Option<u32>,
+}
+
+/// Search gadgets
+///
+/// # Parameters
+/// - limit (query): At most this many gadgets
+#[api_handler]
+async fn search_gadgets(axum::extract::Query(filter): axum::extract::Query<GadgetFilter>) -> Json<Vec<Gadget>> {
+    let _ = (filter.category, filter.limit);
+    Json(Vec::new())
+}
+
+#[test]
+fn test_query_struct_fields_become_query_parameters() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/search", get(search_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    let parameters = spec["paths"]["/gadgets/search"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(parameters.len(), 2);
+
+    // The documented parameter is kept as written rather than duplicated
+    assert_eq!(parameters[0]["name"], "limit");
+    assert_eq!(parameters[0]["description"], "At most this many gadgets");
+    assert_eq!(parameters[0]["required"], false);
+
+    assert_eq!(parameters[1]["name"], "category");
+    assert_eq!(parameters[1]["in"], "query");
+    assert_eq!(parameters[1]["required"], true);
+    assert_eq!(parameters[1]["schema"]["type"], "string");
+}
+
+/// Fetch a gadget
+///
+/// # Parameters
+/// - id (path): Gadget identifier
+#[api_handler]
+async fn get_gadget(axum::extract::Path(id): axum::extract::Path<u32>) -> Json<Gadget> {
+    Json(Gadget { id })
+}
+
+/// Fetch a gadget part
+#[api_handler]
+async fn get_gadget_part(
+    axum::extract::Path((id, part)): axum::extract::Path<(u32, String)>,
+) -> Json<Gadget> {
+    let _ = part;
+    Json(Gadget { id })
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct RevisionPath {
+    id: u64,
+    revision: String,
+}
+
+/// Fetch a gadget revision
+#[api_handler]
+async fn get_gadget_revision(axum::extract::Path(path): axum::extract::Path<RevisionPath>) -> Json<Gadget> {
+    let _ = path.revision;
+    Json(Gadget { id: path.id as u32 })
+}
+
+#[test]
+fn test_single_path_extractor_types_documented_parameter() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}", get(get_gadget));
+    let spec = spec_value(&router.openapi_json());
+
+    let parameters = spec["paths"]["/gadgets/{id}"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(parameters.len(), 1);
+    assert_eq!(parameters[0]["name"], "id");
+    assert_eq!(parameters[0]["in"], "path");
+    assert_eq!(parameters[0]["description"], "Gadget identifier");
+    assert_eq!(parameters[0]["schema"]["type"], "integer");
+}
+
+#[test]
+fn test_tuple_path_extractor_matches_placeholders_by_position() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/gadgets/{id}/parts/{part}", get(get_gadget_part));
+    let spec = spec_value(&router.openapi_json());
+
+    let parameters = spec["paths"]["/gadgets/{id}/parts/{part}"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(parameters[0]["name"], "id");
+    assert_eq!(parameters[0]["required"], true);
+    assert_eq!(parameters[0]["schema"]["type"], "integer");
+    assert_eq!(parameters[1]["name"], "part");
+    assert_eq!(parameters[1]["schema"]["type"], "string");
+}
+
+#[test]
+fn test_struct_path_extractor_matches_placeholders_by_name() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/gadgets/{id}/revisions/{revision}", get(get_gadget_revision));
+    let spec = spec_value(&router.openapi_json());
+
+    let parameters = spec["paths"]["/gadgets/{id}/revisions/{revision}"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(parameters[0]["name"], "id");
+    assert_eq!(parameters[0]["schema"]["type"], "integer");
+    assert_eq!(parameters[0]["schema"]["format"], "int64");
+    assert_eq!(parameters[1]["name"], "revision");
+    assert_eq!(parameters[1]["schema"]["type"], "string");
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct UserKey {
+    /// Tenant the user belongs to
+    tenant: String,
+    /// User number within the tenant
+    user_id: u32,
+}
+
+/// Fetch a user by tenant and number
+///
+/// # Parameters
+/// - tenant (path): Tenant slug
+#[api_handler]
+async fn get_tenant_user(axum::extract::Path(key): axum::extract::Path<UserKey>) -> Json<Gadget> {
+    let _ = key.tenant;
+    Json(Gadget { id: key.user_id })
+}
+
+#[test]
+fn test_struct_path_extractor_fields_become_path_parameters() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/tenants/{tenant}/users/{user_id}", get(get_tenant_user));
+    let spec = spec_value(&router.openapi_json());
+
+    let parameters = spec["paths"]["/tenants/{tenant}/users/{user_id}"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(parameters.len(), 2);
+
+    // The documented description wins over the field's doc comment
+    assert_eq!(parameters[0]["name"], "tenant");
+    assert_eq!(parameters[0]["in"], "path");
+    assert_eq!(parameters[0]["required"], true);
+    assert_eq!(parameters[0]["description"], "Tenant slug");
+    assert_eq!(parameters[0]["schema"]["type"], "string");
+
+    assert_eq!(parameters[1]["name"], "user_id");
+    assert_eq!(parameters[1]["in"], "path");
+    assert_eq!(parameters[1]["required"], true);
+    assert_eq!(parameters[1]["description"], "User number within the tenant");
+    assert_eq!(parameters[1]["schema"]["type"], "integer");
+    assert!(parameters[1]["schema"].get("description").is_none());
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct CategoryFilter {
+    category: String,
+    limit: u32,
+}
+
+/// Search gadgets in a category
+///
+/// # Parameters
+/// - id (path): Category identifier [example: 42]
+/// - limit (query): At most this many gadgets [example: 25, default: 10]
+/// - category (query): Category name [example: 7]
+#[api_handler]
+async fn search_category_gadgets(
+    axum::extract::Path(id): axum::extract::Path<u32>,
+    axum::extract::Query(filter): axum::extract::Query<CategoryFilter>,
+) -> Json<Vec<Gadget>> {
+    let _ = (id, filter.category, filter.limit);
+    Json(Vec::new())
+}
+
+#[test]
+fn test_parameter_examples_follow_schema_type() {
+    let mut router = api_router!("Gadgets", "1.0.0").route("/categories/{id}/gadgets", get(search_category_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    let parameters = spec["paths"]["/categories/{id}/gadgets"]["get"]["parameters"].as_array().unwrap();
+    assert_eq!(parameters.len(), 3);
+
+    assert_eq!(parameters[0]["name"], "id");
+    assert_eq!(parameters[0]["schema"]["type"], "integer");
+    assert_eq!(parameters[0]["example"], 42);
+    assert_eq!(parameters[0]["schema"]["example"], 42);
+
+    // A documented query parameter takes the struct field's type
+    assert_eq!(parameters[1]["name"], "limit");
+    assert_eq!(parameters[1]["description"], "At most this many gadgets");
+    assert_eq!(parameters[1]["schema"]["type"], "integer");
+    assert_eq!(parameters[1]["example"], 25);
+    assert_eq!(parameters[1]["schema"]["default"], 10);
+
+    // Values that are numbers in the docs stay strings for a string schema
+    assert_eq!(parameters[2]["name"], "category");
+    assert_eq!(parameters[2]["schema"]["type"], "string");
+    assert_eq!(parameters[2]["example"], "7");
+}
+
+/// Report internal gadget counters
+#[api_handler("internal", skip)]
+async fn gadget_counters() -> Json<Vec<Gadget>> {
+    Json(Vec::new())
+}
+
+#[test]
+fn test_skipped_handler_is_left_out_of_spec() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .route("/internal/counters", get(gadget_counters))
+        .route("/gadgets", get(list_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    assert!(spec["paths"].get("/internal/counters").is_none());
+    assert!(spec["paths"].get("/gadgets").is_some());
+    assert!(router.validate().is_empty());
+}
+
+/// Rebuild the gadget index
+///
+/// # Extensions
+/// - x-internal: true
+/// - x-codegen-request-body-name: gadget
+#[api_handler]
+async fn reindex_gadgets(Json(gadget): Json<Gadget>) -> Json<Gadget> {
+    Json(gadget)
+}
+
+#[test]
+fn test_vendor_extensions_on_info_and_operation() {
+    let mut router = api_router!("Gadgets", "1.0.0")
+        .info_extension("x-logo", serde_json::json!({"url": "https://example.com/logo.png"}))
+        .route("/gadgets/reindex", post(reindex_gadgets));
+    let spec = spec_value(&router.openapi_json());
+
+    assert_eq!(spec["info"]["x-logo"]["url"], "https://example.com/logo.png");
+
+    let operation = &spec["paths"]["/gadgets/reindex"]["post"];
+    assert_eq!(operation["x-internal"], true);
+    assert_eq!(operation["x-codegen-request-body-name"], "gadget");
+}
@@ -0,0 +1,7 @@
+//! Handlers `#[api_handler]` must refuse to compile
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
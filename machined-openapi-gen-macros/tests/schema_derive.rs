@@ -0,0 +1,969 @@
+//! Tests for the `OpenApiSchema` derive and how its output lands in the generated spec
+
+use axum::Json;
+use machined_openapi_gen::{api_handler, api_router, post, OpenApiSchema};
+use serde::{Deserialize, Serialize};
+
+/// Stand-in for `uuid::Uuid`; the derive maps types by name
+#[derive(Serialize, Deserialize)]
+struct Uuid(String);
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Metrics {
+    id: Uuid,
+    count: i32,
+    total: i64,
+    ratio: f32,
+    average: f64,
+}
+
+/// Record a metrics sample
+///
+/// # Responses
+/// - 200: Sample recorded
+#[api_handler]
+async fn record_metrics(Json(metrics): Json<Metrics>) -> Json<Metrics> {
+    Json(metrics)
+}
+
+fn spec_value(json: &str) -> serde_json::Value {
+    serde_json::from_str(json).expect("generated spec should be valid JSON")
+}
+
+#[test]
+fn test_numeric_formats_in_derived_schema() {
+    let schema = spec_value(&Metrics::schema());
+
+    assert_eq!(schema["properties"]["count"]["type"], "integer");
+    assert_eq!(schema["properties"]["count"]["format"], "int32");
+    assert_eq!(schema["properties"]["total"]["format"], "int64");
+    assert_eq!(schema["properties"]["ratio"]["type"], "number");
+    assert_eq!(schema["properties"]["ratio"]["format"], "float");
+    assert_eq!(schema["properties"]["average"]["format"], "double");
+    assert_eq!(schema["properties"]["id"]["format"], "uuid");
+}
+
+#[test]
+fn test_formats_survive_components() {
+    let mut router = api_router!("Formats", "1.0.0").route("/metrics", post(record_metrics));
+    let spec = spec_value(&router.openapi_json());
+
+    let properties = &spec["components"]["schemas"]["Metrics"]["properties"];
+    assert_eq!(properties["count"]["format"], "int32");
+    assert_eq!(properties["total"]["format"], "int64");
+    assert_eq!(properties["id"]["type"], "string");
+    assert_eq!(properties["id"]["format"], "uuid");
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+enum Shape {
+    Point,
+    Circle(f64),
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Palette {
+    primary: Color,
+}
+
+/// Store a palette
+///
+/// # Responses
+/// - 204: Palette stored
+#[api_handler]
+async fn store_palette(Json(_palette): Json<Palette>) {}
+
+#[test]
+fn test_unit_enum_is_string_enum() {
+    let schema = spec_value(&Color::schema());
+
+    assert_eq!(
+        schema,
+        serde_json::json!({"type": "string", "enum": ["Red", "Green", "Blue"]})
+    );
+}
+
+#[test]
+fn test_mixed_enum_keeps_one_of() {
+    let schema = spec_value(&Shape::schema());
+
+    assert!(schema.get("enum").is_none());
+    let variants = schema["oneOf"].as_array().expect("mixed enum should use oneOf");
+    assert_eq!(variants.len(), 2);
+    assert_eq!(variants[0]["properties"]["Point"]["type"], "null");
+    assert_eq!(variants[1]["properties"]["Circle"]["type"], "number");
+}
+
+#[test]
+fn test_string_enum_survives_components() {
+    let mut router = api_router!("Enums", "1.0.0").route("/palette", post(store_palette));
+    let spec = spec_value(&router.openapi_json());
+
+    let color = &spec["components"]["schemas"]["Color"];
+    assert_eq!(color["type"], "string");
+    assert_eq!(color["enum"], serde_json::json!(["Red", "Green", "Blue"]));
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[serde(rename_all = "camelCase")]
+struct CamelProfile {
+    display_name: String,
+    created_at_ms: u64,
+    #[serde(rename = "e-mail")]
+    email_address: String,
+    nick_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ScreamingConfig {
+    max_retries: u32,
+    r#type: String,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[serde(rename_all = "snake_case")]
+struct SnakeRecord {
+    record_id: u32,
+}
+
+/// Property names the derive emitted, sorted
+fn schema_property_names(schema_json: &str) -> Vec<String> {
+    let schema = spec_value(schema_json);
+    let mut names: Vec<String> = schema["properties"]
+        .as_object()
+        .expect("struct schema should have properties")
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+/// Keys serde actually writes for a value, sorted
+fn serialized_keys<T: Serialize>(value: &T) -> Vec<String> {
+    let mut keys: Vec<String> = serde_json::to_value(value)
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_rename_all_camel_case_matches_serde() {
+    let profile = CamelProfile {
+        display_name: "Ada".to_string(),
+        created_at_ms: 0,
+        email_address: "ada@example.com".to_string(),
+        nick_name: Some("ada".to_string()),
+    };
+
+    assert_eq!(schema_property_names(&CamelProfile::schema()), serialized_keys(&profile));
+
+    // Field-level rename wins over rename_all, and required uses the renamed keys
+    let schema = spec_value(&CamelProfile::schema());
+    assert_eq!(schema["required"], serde_json::json!(["displayName", "createdAtMs", "e-mail"]));
+}
+
+#[test]
+fn test_rename_all_screaming_snake_case_matches_serde() {
+    let config = ScreamingConfig {
+        max_retries: 3,
+        r#type: "fast".to_string(),
+    };
+
+    assert_eq!(schema_property_names(&ScreamingConfig::schema()), serialized_keys(&config));
+    assert_eq!(
+        schema_property_names(&ScreamingConfig::schema()),
+        vec!["MAX_RETRIES".to_string(), "TYPE".to_string()]
+    );
+}
+
+#[test]
+fn test_rename_all_snake_case_matches_serde() {
+    let record = SnakeRecord { record_id: 7 };
+
+    assert_eq!(schema_property_names(&SnakeRecord::schema()), serialized_keys(&record));
+}
+
+#[allow(dead_code)]
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Account {
+    id: u32,
+    #[serde(skip)]
+    cache_key: String,
+    #[serde(skip_serializing)]
+    password_hash: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    roles: Vec<String>,
+    #[serde(skip_deserializing)]
+    login_count: u32,
+}
+
+#[test]
+fn test_skip_omits_property() {
+    let schema = spec_value(&Account::schema());
+
+    assert!(schema["properties"].get("cache_key").is_none());
+    assert!(!schema["required"].as_array().unwrap().contains(&"cache_key".into()));
+}
+
+#[test]
+fn test_skip_serializing_omits_property() {
+    let schema = spec_value(&Account::schema());
+
+    assert!(schema["properties"].get("password_hash").is_none());
+    assert!(!schema["required"].as_array().unwrap().contains(&"password_hash".into()));
+}
+
+#[test]
+fn test_skip_serializing_if_is_optional() {
+    let schema = spec_value(&Account::schema());
+
+    assert_eq!(schema["properties"]["roles"]["type"], "array");
+    assert_eq!(schema["required"], serde_json::json!(["id", "login_count"]));
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct AuditInfo {
+    created_by: String,
+    updated_by: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Document {
+    title: String,
+    #[serde(flatten)]
+    audit: AuditInfo,
+}
+
+/// Create a document
+///
+/// # Responses
+/// - 204: Document created
+#[api_handler]
+async fn create_document(Json(_document): Json<Document>) {}
+
+#[test]
+fn test_flatten_uses_all_of() {
+    let schema = spec_value(&Document::schema());
+
+    let all_of = schema["allOf"].as_array().expect("flattened struct should use allOf");
+    assert_eq!(all_of.len(), 2);
+    assert_eq!(all_of[0]["$ref"], "#/components/schemas/AuditInfo");
+    assert_eq!(all_of[1]["type"], "object");
+    assert_eq!(all_of[1]["properties"]["title"]["type"], "string");
+    assert!(all_of[1]["properties"].get("audit").is_none());
+    assert_eq!(all_of[1]["required"], serde_json::json!(["title"]));
+}
+
+#[test]
+fn test_flattened_type_pulled_into_components() {
+    let mut router = api_router!("Flatten", "1.0.0").route("/documents", post(create_document));
+    let spec = spec_value(&router.openapi_json());
+
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(schemas["Document"]["allOf"][0]["$ref"], "#/components/schemas/AuditInfo");
+    assert_eq!(schemas["AuditInfo"]["properties"]["created_by"]["type"], "string");
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Setting {
+    value: String,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Preferences {
+    labels: std::collections::HashMap<String, String>,
+    settings: std::collections::BTreeMap<String, Setting>,
+}
+
+/// Save preferences
+///
+/// # Responses
+/// - 204: Preferences saved
+#[api_handler]
+async fn save_preferences(Json(_preferences): Json<Preferences>) {}
+
+#[test]
+fn test_string_map_uses_additional_properties() {
+    let schema = spec_value(&Preferences::schema());
+
+    assert_eq!(
+        schema["properties"]["labels"],
+        serde_json::json!({"type": "object", "additionalProperties": {"type": "string"}})
+    );
+}
+
+#[test]
+fn test_custom_map_value_pulled_into_components() {
+    let schema = spec_value(&Preferences::schema());
+    assert_eq!(
+        schema["properties"]["settings"]["additionalProperties"]["$ref"],
+        "#/components/schemas/Setting"
+    );
+
+    let mut router = api_router!("Maps", "1.0.0").route("/preferences", post(save_preferences));
+    let spec = spec_value(&router.openapi_json());
+
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(
+        schemas["Preferences"]["properties"]["settings"]["additionalProperties"]["$ref"],
+        "#/components/schemas/Setting"
+    );
+    assert_eq!(schemas["Setting"]["properties"]["value"]["type"], "string");
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Person {
+    #[minimum = 0]
+    #[maximum = 120]
+    age: u8,
+    #[minimum = "-40"]
+    #[exclusive_minimum]
+    #[maximum = 1.5]
+    #[exclusive_maximum]
+    offset: f64,
+}
+
+/// Register a person
+///
+/// # Responses
+/// - 204: Person registered
+#[api_handler]
+async fn register_person(Json(_person): Json<Person>) {}
+
+#[test]
+fn test_numeric_range_attributes() {
+    let schema = spec_value(&Person::schema());
+
+    let age = &schema["properties"]["age"];
+    assert_eq!(age["type"], "integer");
+    assert_eq!(age["minimum"], 0);
+    assert_eq!(age["maximum"], 120);
+    assert!(age.get("exclusiveMinimum").is_none());
+
+    let offset = &schema["properties"]["offset"];
+    assert_eq!(offset["minimum"], -40);
+    assert_eq!(offset["maximum"], 1.5);
+    assert_eq!(offset["exclusiveMinimum"], true);
+    assert_eq!(offset["exclusiveMaximum"], true);
+}
+
+#[test]
+fn test_numeric_ranges_survive_components() {
+    let mut router = api_router!("Ranges", "1.0.0").route("/people", post(register_person));
+    let spec = spec_value(&router.openapi_json());
+
+    let properties = &spec["components"]["schemas"]["Person"]["properties"];
+    assert_eq!(properties["age"]["minimum"].as_f64(), Some(0.0));
+    assert_eq!(properties["age"]["maximum"].as_f64(), Some(120.0));
+    assert_eq!(properties["offset"]["minimum"].as_f64(), Some(-40.0));
+    assert_eq!(properties["offset"]["exclusiveMaximum"], true);
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Signup {
+    #[min_length = 1]
+    #[max_length = 64]
+    #[pattern = "^[a-z]+$"]
+    username: String,
+    #[pattern = r"^\d{3}-\d{4}$"]
+    phone: String,
+}
+
+/// Sign up
+///
+/// # Responses
+/// - 204: Signed up
+#[api_handler]
+async fn sign_up(Json(_signup): Json<Signup>) {}
+
+#[test]
+fn test_string_length_and_pattern_attributes() {
+    let schema = spec_value(&Signup::schema());
+
+    let username = &schema["properties"]["username"];
+    assert_eq!(username["minLength"], 1);
+    assert_eq!(username["maxLength"], 64);
+    assert_eq!(username["pattern"], "^[a-z]+$");
+}
+
+#[test]
+fn test_pattern_with_escape_sequences() {
+    let schema = spec_value(&Signup::schema());
+    assert_eq!(schema["properties"]["phone"]["pattern"], r"^\d{3}-\d{4}$");
+
+    let mut router = api_router!("Patterns", "1.0.0").route("/signup", post(sign_up));
+    let spec = spec_value(&router.openapi_json());
+
+    let properties = &spec["components"]["schemas"]["Signup"]["properties"];
+    assert_eq!(properties["phone"]["pattern"], r"^\d{3}-\d{4}$");
+    assert_eq!(properties["username"]["maxLength"], 64);
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Batch {
+    #[min_items = 1]
+    #[max_items = 10]
+    #[unique_items]
+    tags: Vec<String>,
+    entries: Vec<Setting>,
+}
+
+/// Submit a batch
+///
+/// # Responses
+/// - 204: Batch accepted
+#[api_handler]
+async fn submit_batch(Json(_batch): Json<Batch>) {}
+
+#[test]
+fn test_array_item_constraints() {
+    let schema = spec_value(&Batch::schema());
+
+    assert_eq!(
+        schema["properties"]["tags"],
+        serde_json::json!({
+            "type": "array",
+            "items": {"type": "string"},
+            "minItems": 1,
+            "maxItems": 10,
+            "uniqueItems": true
+        })
+    );
+}
+
+#[test]
+fn test_array_items_survive_components() {
+    let mut router = api_router!("Arrays", "1.0.0").route("/batches", post(submit_batch));
+    let spec = spec_value(&router.openapi_json());
+
+    let properties = &spec["components"]["schemas"]["Batch"]["properties"];
+    assert_eq!(properties["tags"]["minItems"], 1);
+    assert_eq!(properties["tags"]["uniqueItems"], true);
+    assert_eq!(properties["entries"]["items"]["$ref"], "#/components/schemas/Setting");
+    assert!(spec["components"]["schemas"].get("Setting").is_some());
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct TreeNode {
+    label: String,
+    children: Vec<TreeNode>,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Author {
+    name: String,
+    books: Vec<Book>,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Book {
+    title: String,
+    authors: Vec<Author>,
+}
+
+/// Store a tree
+///
+/// # Responses
+/// - 204: Tree stored
+#[api_handler]
+async fn store_tree(Json(_tree): Json<TreeNode>) {}
+
+/// Add a book
+///
+/// # Responses
+/// - 204: Book added
+#[api_handler]
+async fn add_book(Json(_book): Json<Book>) {}
+
+#[test]
+fn test_recursive_schemas_in_components() {
+    let mut router = api_router!("Recursive", "1.0.0")
+        .route("/trees", post(store_tree))
+        .route("/books", post(add_book));
+    let spec = spec_value(&router.openapi_json());
+
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(schemas["TreeNode"]["properties"]["children"]["items"]["$ref"], "#/components/schemas/TreeNode");
+    assert_eq!(schemas["Book"]["properties"]["authors"]["items"]["$ref"], "#/components/schemas/Author");
+    assert_eq!(schemas["Author"]["properties"]["books"]["items"]["$ref"], "#/components/schemas/Book");
+}
+
+#[derive(Serialize, OpenApiSchema)]
+#[extension("x-internal", true)]
+#[extension("x-display-name", "Audit entry")]
+struct AuditEntry {
+    actor: String,
+}
+
+#[test]
+fn test_extension_attributes_land_in_schema() {
+    let schema = spec_value(&AuditEntry::schema());
+
+    assert_eq!(schema["x-internal"], true);
+    assert_eq!(schema["x-display-name"], "Audit entry");
+    assert_eq!(schema["properties"]["actor"]["type"], "string");
+}
+
+/// A shipment on its way to a warehouse
+///
+/// Only the first paragraph becomes the schema description.
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Shipment {
+    /// Carrier tracking code
+    /// as printed on the label [example: 1Z999AA10123456784]
+    tracking_code: String,
+    /// Parcels in the shipment
+    parcels: Vec<Parcel>,
+    weight_kg: f64,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Listing {
+    /// Items per page [example: 25]
+    page_size: u32,
+    #[example = true]
+    featured: bool,
+    #[example = 19.99]
+    price: f64,
+    /// Stock keeping unit [example: 00042]
+    sku: String,
+    #[example = "7"]
+    rank: i64,
+    #[example = "soon"]
+    restock_days: i32,
+    #[example = 42]
+    min_age: Option<i32>,
+    /// Gift wrapping available [example: false]
+    giftable: Option<bool>,
+}
+
+#[test]
+fn test_examples_take_the_field_type() {
+    let schema = spec_value(&Listing::schema());
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["page_size"]["example"], serde_json::json!(25));
+    assert_eq!(properties["featured"]["example"], serde_json::json!(true));
+    assert_eq!(properties["price"]["example"], serde_json::json!(19.99));
+    assert_eq!(properties["rank"]["example"], serde_json::json!(7));
+    // A string field keeps its digits as text, and a value that doesn't fit stays a string
+    assert_eq!(properties["sku"]["example"], serde_json::json!("00042"));
+    assert_eq!(properties["restock_days"]["example"], serde_json::json!("soon"));
+    // An Option field follows the type it wraps
+    assert_eq!(properties["min_age"]["example"], serde_json::json!(42));
+    assert_eq!(properties["giftable"]["example"], serde_json::json!(false));
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct SearchSettings {
+    query: String,
+    /// Results per page [default: 20]
+    limit: u32,
+    #[default = 0.5]
+    min_score: f64,
+    #[default = true]
+    highlight: bool,
+    /// Sort order [default: relevance]
+    sort: String,
+    #[default = 5]
+    max_pages: Option<u64>,
+    /// Include archived results [default: false]
+    archived: Option<bool>,
+}
+
+#[test]
+fn test_defaults_take_the_field_type() {
+    let schema = spec_value(&SearchSettings::schema());
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["limit"]["default"], serde_json::json!(20));
+    assert_eq!(properties["min_score"]["default"], serde_json::json!(0.5));
+    assert_eq!(properties["highlight"]["default"], serde_json::json!(true));
+    assert_eq!(properties["sort"]["default"], serde_json::json!("relevance"));
+    assert_eq!(properties["max_pages"]["default"], serde_json::json!(5));
+    assert_eq!(properties["archived"]["default"], serde_json::json!(false));
+    // Only the field without a default stays required
+    assert_eq!(schema["required"], serde_json::json!(["query"]));
+}
+
+/// A single box
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Parcel {
+    label: String,
+}
+
+/// Ship a parcel set
+///
+/// # Responses
+/// - 204: Shipment accepted
+#[api_handler]
+async fn ship(Json(_shipment): Json<Shipment>) {}
+
+#[test]
+fn test_doc_comments_become_titles_and_descriptions() {
+    let schema = spec_value(&Shipment::schema());
+
+    assert_eq!(schema["title"], "Shipment");
+    assert_eq!(schema["description"], "A shipment on its way to a warehouse");
+    let tracking_code = &schema["properties"]["tracking_code"];
+    assert_eq!(tracking_code["description"], "Carrier tracking code as printed on the label");
+    assert_eq!(tracking_code["example"], "1Z999AA10123456784");
+    assert_eq!(schema["properties"]["parcels"]["description"], "Parcels in the shipment");
+    assert!(schema["properties"]["weight_kg"].get("description").is_none());
+
+    let mut router = api_router!("Shipping", "1.0.0").route("/shipments", post(ship));
+    let spec = spec_value(&router.openapi_json());
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(schemas["Shipment"]["description"], "A shipment on its way to a warehouse");
+    assert_eq!(schemas["Parcel"]["title"], "Parcel");
+    assert_eq!(schemas["Parcel"]["description"], "A single box");
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Member {
+    #[read_only]
+    id: u64,
+    email: String,
+    #[write_only]
+    password: String,
+}
+
+/// Add a member
+///
+/// # Responses
+/// - 201: Returns Member
+#[api_handler]
+async fn add_member(Json(member): Json<Member>) -> Json<Member> {
+    Json(member)
+}
+
+#[test]
+fn test_read_only_and_write_only_fields() {
+    let schema = spec_value(&Member::schema());
+    assert_eq!(schema["properties"]["id"]["readOnly"], true);
+    assert_eq!(schema["properties"]["password"]["writeOnly"], true);
+    assert!(schema["properties"]["email"].get("readOnly").is_none());
+
+    let mut router = api_router!("Members", "1.0.0").route("/members", post(add_member));
+    let spec = spec_value(&router.openapi_json());
+
+    let properties = &spec["components"]["schemas"]["Member"]["properties"];
+    assert_eq!(properties["id"], serde_json::json!({"type": "integer", "format": "int64", "readOnly": true}));
+    assert_eq!(properties["password"], serde_json::json!({"type": "string", "writeOnly": true}));
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct DraftNote {
+    title: String,
+    #[nullable]
+    body: Option<String>,
+    due: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[nullable]
+struct PublishedNote {
+    title: String,
+    body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+}
+
+/// Publish a note
+///
+/// # Responses
+/// - 201: Returns PublishedNote
+#[api_handler]
+async fn publish_note(Json(_draft): Json<DraftNote>) -> Json<PublishedNote> {
+    Json(PublishedNote { title: String::new(), body: None, due: None })
+}
+
+#[test]
+fn test_nullable_fields() {
+    // Per field: only the marked Option gets nullable
+    let draft = spec_value(&DraftNote::schema());
+    assert_eq!(draft["properties"]["body"]["nullable"], true);
+    assert!(draft["properties"]["due"].get("nullable").is_none());
+    assert!(draft["properties"]["title"].get("nullable").is_none());
+
+    // Per struct: every Option that serializes None as null, but not one that is skipped instead
+    let published = spec_value(&PublishedNote::schema());
+    assert_eq!(published["properties"]["body"]["nullable"], true);
+    assert!(published["properties"]["due"].get("nullable").is_none());
+    assert!(published["properties"]["title"].get("nullable").is_none());
+    assert_eq!(serde_json::to_value(PublishedNote { title: String::new(), body: None, due: None }).unwrap()["body"], serde_json::Value::Null);
+
+    let mut router = api_router!("Notes", "1.0.0").route("/notes", post(publish_note));
+    let spec = spec_value(&router.openapi_json());
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(schemas["DraftNote"]["properties"]["body"]["nullable"], true);
+    assert_eq!(schemas["PublishedNote"]["properties"]["body"]["nullable"], true);
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Survey {
+    #[nullable]
+    age: Option<i32>,
+    scores: Option<Vec<u32>>,
+    #[nullable]
+    consent: Option<bool>,
+}
+
+#[test]
+fn test_option_fields_take_the_inner_type() {
+    let schema = spec_value(&Survey::schema());
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["age"], serde_json::json!({"type": "integer", "format": "int32", "nullable": true}));
+    assert_eq!(properties["scores"], serde_json::json!({"type": "array", "items": {"type": "integer", "format": "int64"}}));
+    assert_eq!(properties["consent"], serde_json::json!({"type": "boolean", "nullable": true}));
+    assert!(schema.get("required").is_none());
+}
+
+/// Stand-ins for the chrono types, which the derive also maps by name
+mod chrono {
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    pub struct Utc;
+    #[derive(Serialize)]
+    pub struct DateTime<Tz>(pub String, pub Tz);
+    #[derive(Serialize)]
+    pub struct NaiveDateTime(pub String);
+    #[derive(Serialize)]
+    pub struct NaiveDate(pub String);
+    #[derive(Serialize)]
+    pub struct NaiveTime(pub String);
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct Appointment {
+    booked_at: chrono::DateTime<chrono::Utc>,
+    starts_at: chrono::NaiveDateTime,
+    day: chrono::NaiveDate,
+    reminder: chrono::NaiveTime,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+enum Deadline {
+    On(chrono::NaiveDate),
+    At(chrono::NaiveTime),
+}
+
+#[test]
+fn test_chrono_types_get_distinct_formats() {
+    let appointment = Appointment {
+        booked_at: chrono::DateTime("2024-05-01T09:30:00Z".to_string(), chrono::Utc),
+        starts_at: chrono::NaiveDateTime("2024-05-02T10:00:00".to_string()),
+        day: chrono::NaiveDate("2024-05-02".to_string()),
+        reminder: chrono::NaiveTime("09:45:00".to_string()),
+    };
+    assert!(serde_json::to_value(&appointment).is_ok());
+
+    let schema = spec_value(&Appointment::schema());
+    let format = |field: &str| schema["properties"][field]["format"].clone();
+    assert_eq!(format("booked_at"), "date-time");
+    assert_eq!(format("starts_at"), "date-time");
+    assert_eq!(format("day"), "date");
+    assert_eq!(format("reminder"), "time");
+    assert_eq!(schema["properties"]["day"]["type"], "string");
+
+    let deadline = spec_value(&Deadline::schema());
+    assert!(serde_json::to_value([Deadline::On(chrono::NaiveDate(String::new())), Deadline::At(chrono::NaiveTime(String::new()))]).is_ok());
+    assert_eq!(deadline["oneOf"][0]["properties"]["On"]["format"], "date");
+    assert_eq!(deadline["oneOf"][1]["properties"]["At"]["format"], "time");
+}
+
+/// Stand-in for `rust_decimal::Decimal`
+#[derive(Serialize, Deserialize)]
+struct Decimal(f64);
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Node {
+    address: std::net::IpAddr,
+    ipv4: std::net::Ipv4Addr,
+    ipv6: std::net::Ipv6Addr,
+    data_dir: std::path::PathBuf,
+    heartbeat: std::time::Duration,
+    balance: Decimal,
+}
+
+/// Register a node
+///
+/// # Responses
+/// - 204: Node registered
+#[api_handler]
+async fn register_node(Json(_node): Json<Node>) {}
+
+#[test]
+fn test_std_and_ecosystem_types_map_to_formats() {
+    let schema = spec_value(&Node::schema());
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["address"], serde_json::json!({"type": "string"}));
+    assert_eq!(properties["ipv4"], serde_json::json!({"type": "string", "format": "ipv4"}));
+    assert_eq!(properties["ipv6"], serde_json::json!({"type": "string", "format": "ipv6"}));
+    assert_eq!(properties["data_dir"], serde_json::json!({"type": "string"}));
+    assert_eq!(properties["heartbeat"], serde_json::json!({"type": "string", "format": "duration"}));
+    assert_eq!(properties["balance"], serde_json::json!({"type": "number"}));
+
+    let mut router = api_router!("Nodes", "1.0.0").route("/nodes", post(register_node));
+    let spec = router.openapi_json();
+    assert!(!spec.contains("#/components/schemas/Ip"));
+    assert!(!spec.contains("#/components/schemas/PathBuf"));
+    assert!(!spec.contains("#/components/schemas/Duration"));
+    assert!(!spec.contains("#/components/schemas/Decimal"));
+    assert!(router.validate().is_empty());
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct UserId(u32);
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Point(f64, f64);
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Label(String, u32);
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct Pin {
+    owner: UserId,
+    at: Point,
+    label: Label,
+}
+
+/// Drop a pin
+///
+/// # Responses
+/// - 204: Pin dropped
+#[api_handler]
+async fn drop_pin(Json(_pin): Json<Pin>) {}
+
+#[test]
+fn test_newtype_and_tuple_structs() {
+    let user_id = spec_value(&UserId::schema());
+    assert_eq!(user_id["type"], "integer");
+    assert_eq!(user_id["format"], "int64");
+    assert_eq!(serde_json::to_value(UserId(7)).unwrap(), serde_json::json!(7));
+
+    let point = spec_value(&Point::schema());
+    assert_eq!(point["type"], "array");
+    assert_eq!(point["items"], serde_json::json!({"type": "number", "format": "double"}));
+    assert_eq!(point["minItems"], 2);
+    assert_eq!(point["maxItems"], 2);
+    assert_eq!(serde_json::to_value(Point(1.5, 2.0)).unwrap(), serde_json::json!([1.5, 2.0]));
+
+    let label = spec_value(&Label::schema());
+    assert_eq!(label["items"]["oneOf"], serde_json::json!([{"type": "string"}, {"type": "integer", "format": "int64"}]));
+    assert!(serde_json::to_value(Label("home".to_string(), 1)).unwrap().is_array());
+
+    let mut router = api_router!("Pins", "1.0.0").route("/pins", post(drop_pin));
+    let spec = spec_value(&router.openapi_json());
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(schemas["Pin"]["properties"]["owner"]["$ref"], "#/components/schemas/UserId");
+    assert_eq!(schemas["UserId"]["type"], "integer");
+    assert_eq!(schemas["Point"]["maxItems"], 2);
+    assert_eq!(schemas["Label"]["type"], "array");
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+struct CardPayment {
+    last4: String,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Payment {
+    Card(CardPayment),
+    BankTransfer { iban: String },
+    Cash,
+}
+
+/// Take a payment
+///
+/// # Responses
+/// - 204: Payment taken
+#[api_handler]
+async fn take_payment(Json(_payment): Json<Payment>) {}
+
+#[test]
+fn test_internally_tagged_enum_discriminator() {
+    let schema = spec_value(&Payment::schema());
+
+    assert_eq!(
+        schema["discriminator"],
+        serde_json::json!({
+            "propertyName": "method",
+            "mapping": {"card": "#/components/schemas/CardPayment"}
+        })
+    );
+    let variants = schema["oneOf"].as_array().unwrap();
+    assert_eq!(variants.len(), 3);
+    assert_eq!(variants[0]["allOf"][0]["$ref"], "#/components/schemas/CardPayment");
+    assert_eq!(variants[0]["allOf"][1]["properties"]["method"]["enum"], serde_json::json!(["card"]));
+    assert_eq!(variants[1]["properties"]["method"]["enum"], serde_json::json!(["bank_transfer"]));
+    assert_eq!(variants[2]["properties"]["method"]["enum"], serde_json::json!(["cash"]));
+
+    // The tag values match what serde writes
+    let card = serde_json::to_value(Payment::Card(CardPayment { last4: "4242".to_string() })).unwrap();
+    assert_eq!(card, serde_json::json!({"method": "card", "last4": "4242"}));
+    let transfer = serde_json::to_value(Payment::BankTransfer { iban: String::new() }).unwrap();
+    assert_eq!(transfer["method"], "bank_transfer");
+    assert_eq!(serde_json::to_value(Payment::Cash).unwrap()["method"], "cash");
+
+    let mut router = api_router!("Payments", "1.0.0").route("/payments", post(take_payment));
+    let spec = spec_value(&router.openapi_json());
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(schemas["Payment"]["discriminator"]["propertyName"], "method");
+    assert!(schemas["CardPayment"].is_object());
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[serde(tag = "kind")]
+enum Refund {
+    Card(CardPayment),
+    BankTransfer { iban: String },
+    #[serde(rename = "voucher-credit")]
+    VoucherCredit,
+}
+
+#[derive(Serialize, Deserialize, OpenApiSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum Payout {
+    Card(CardPayment),
+    BankTransfer { iban: String },
+}
+
+#[test]
+fn test_internally_tagged_enum_values_follow_serde_names() {
+    // Without rename_all serde writes the variant name as-is, unless the variant renames itself
+    let refund = spec_value(&Refund::schema());
+    assert_eq!(refund["discriminator"]["mapping"], serde_json::json!({"Card": "#/components/schemas/CardPayment"}));
+    let variants = refund["oneOf"].as_array().unwrap();
+    assert_eq!(variants[0]["allOf"][1]["properties"]["kind"]["enum"], serde_json::json!(["Card"]));
+    assert_eq!(variants[1]["properties"]["kind"]["enum"], serde_json::json!(["BankTransfer"]));
+    assert_eq!(variants[2]["properties"]["kind"]["enum"], serde_json::json!(["voucher-credit"]));
+    let card = serde_json::to_value(Refund::Card(CardPayment { last4: "4242".to_string() })).unwrap();
+    assert_eq!(card["kind"], "Card");
+    assert_eq!(serde_json::to_value(Refund::BankTransfer { iban: String::new() }).unwrap()["kind"], "BankTransfer");
+    assert_eq!(serde_json::to_value(Refund::VoucherCredit).unwrap()["kind"], "voucher-credit");
+
+    let payout = spec_value(&Payout::schema());
+    assert_eq!(payout["discriminator"]["mapping"], serde_json::json!({"card": "#/components/schemas/CardPayment"}));
+    assert_eq!(payout["oneOf"][1]["properties"]["kind"]["enum"], serde_json::json!(["bankTransfer"]));
+    assert_eq!(serde_json::to_value(Payout::BankTransfer { iban: String::new() }).unwrap()["kind"], "bankTransfer");
+}
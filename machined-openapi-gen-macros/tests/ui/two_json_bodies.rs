@@ -0,0 +1,9 @@
+use machined_openapi_gen::api_handler;
+
+/// Merge two widgets
+#[api_handler]
+async fn merge_widgets(axum::Json(first): axum::Json<String>, axum::Json(second): axum::Json<String>) -> String {
+    first + &second
+}
+
+fn main() {}
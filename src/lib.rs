@@ -1,7 +1,16 @@
 //! Simple stonehm implementation without serde dependencies
 
+pub mod markdown;
+pub mod openapi;
+pub mod spec_diff;
+#[cfg(feature = "typescript")]
+pub mod typescript;
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod openapi_tests;
+
 use axum::Router;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // Re-export Axum types so users can import everything from stonehm
 pub use axum::{
@@ -13,10 +22,13 @@ pub use axum::{
     handler::Handler,
 };
 
+pub use spec_diff::{diff, SpecChange, SpecDiff};
+
 // Simple OpenAPI types
 #[derive(Debug, Clone)]
 pub struct OpenAPI {
     pub info: Info,
+    pub servers: Vec<openapi::Server>,
     pub paths: HashMap<String, PathItem>,
     pub components: Option<Components>,
     pub tags: Vec<Tag>,
@@ -45,19 +57,149 @@ pub struct RouteInfo {
     pub path: String,
     pub method: String,
     pub function_name: String,
+    /// Fully qualified handler path, used instead of `function_name` when
+    /// `full_handler_paths` is enabled
+    pub handler_path: String,
     pub summary: Option<String>,
     pub description: Option<String>,
+    /// Documentation attached with `route_documented()`, used in place of the
+    /// handler's `#[api_handler]` registration
+    pub doc: Option<HandlerDocumentation<String>>,
 }
 
-#[derive(Debug, Clone)]
-pub struct HandlerDocumentation {
-    pub function_name: &'static str,
-    pub summary: &'static str,
-    pub description: &'static str,
-    pub parameters: &'static str,
-    pub responses: &'static str,
-    pub request_body: &'static str,
-    pub tags: &'static str,
+/// A problem in the generated spec found by `ApiRouter::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecWarning {
+    /// Several routes share a handler and therefore an operationId
+    DuplicateOperationId { operation_id: String, routes: Vec<String> },
+    /// The same method and path were registered more than once
+    DuplicateRoute { method: String, path: String },
+    /// A schema references a type that isn't registered
+    UnresolvedSchemaRef { schema: String, referenced_by: String },
+    /// A handler uses a tag that was never declared with `tag()`
+    UndeclaredTag { tag: String, operation: String },
+    /// `info.title` or `info.version` is empty, though OpenAPI requires both
+    EmptyInfoField { field: String },
+}
+
+impl std::fmt::Display for SpecWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecWarning::DuplicateOperationId { operation_id, routes } => {
+                write!(f, "operationId '{operation_id}' is used by {}", routes.join(", "))
+            }
+            SpecWarning::DuplicateRoute { method, path } => {
+                write!(f, "{method} {path} is registered more than once")
+            }
+            SpecWarning::UnresolvedSchemaRef { schema, referenced_by } => {
+                write!(f, "schema '{referenced_by}' references '{schema}', which is not a registered schema")
+            }
+            SpecWarning::UndeclaredTag { tag, operation } => {
+                write!(f, "tag '{tag}' used by '{operation}' is not declared")
+            }
+            SpecWarning::EmptyInfoField { field } => {
+                write!(f, "info.{field} is empty; OpenAPI requires a non-empty {field}")
+            }
+        }
+    }
+}
+
+/// A handler's documentation as `#[api_handler]` registers it
+///
+/// Registrations hold `&'static str`s; documentation attached at runtime with
+/// `route_documented()` is stored with owned `String`s.
+#[derive(Debug, Clone, Copy)]
+pub struct HandlerDocumentation<S = &'static str> {
+    pub function_name: S,
+    /// `module_path!()` of the module the handler is defined in
+    pub module_path: S,
+    /// Set by `#[api_handler(skip)]`; the route is served but left out of the spec
+    pub hidden: bool,
+    /// Set by a `#[deprecated]` attribute on the handler or `#[api_handler(deprecated)]`
+    pub deprecated: bool,
+    pub summary: S,
+    pub description: S,
+    pub parameters: S,
+    pub responses: S,
+    pub request_body: S,
+    pub tags: S,
+    /// Set by `#[api_handler(tag_from_module)]`; an untagged handler is tagged with
+    /// the innermost module of `module_path`
+    pub tag_from_module: bool,
+    /// JSON array of "x-name: value" vendor extensions for the operation
+    pub extensions: S,
+    /// "URL description" from `#[external_docs]` or a "# See Also" doc section; empty when absent
+    pub external_docs: S,
+    /// JSON array of security scheme names from a "# Security" section, `[]` for
+    /// `#[api_handler(public)]`; empty when the handler doesn't say
+    pub security: S,
+    /// JSON array of "name METHOD expression: description" entries from `#[callback]`
+    /// attributes, each optionally followed by a "Schema: name Type" entry for its body
+    pub callbacks: S,
+}
+
+impl<S: AsRef<str>> HandlerDocumentation<S> {
+    /// Borrow every field, so registered and owned documentation are read the same way
+    fn as_str(&self) -> HandlerDocumentation<&str> {
+        HandlerDocumentation {
+            function_name: self.function_name.as_ref(),
+            module_path: self.module_path.as_ref(),
+            hidden: self.hidden,
+            deprecated: self.deprecated,
+            summary: self.summary.as_ref(),
+            description: self.description.as_ref(),
+            parameters: self.parameters.as_ref(),
+            responses: self.responses.as_ref(),
+            request_body: self.request_body.as_ref(),
+            tags: self.tags.as_ref(),
+            tag_from_module: self.tag_from_module,
+            extensions: self.extensions.as_ref(),
+            external_docs: self.external_docs.as_ref(),
+            security: self.security.as_ref(),
+            callbacks: self.callbacks.as_ref(),
+        }
+    }
+}
+
+/// Documentation for a route registered with `ApiRouter::route_documented`
+///
+/// The list fields take the same lines a handler's doc comment sections would,
+/// e.g. `parameters: vec!["id (path): User ID".into()]` or
+/// `responses: vec!["200: Returns UserResponse".into(), "404: Not found".into()]`.
+/// Fields left at their default are omitted.
+#[derive(Debug, Clone, Default)]
+pub struct RouteDoc {
+    pub summary: String,
+    pub description: String,
+    pub parameters: Vec<String>,
+    pub responses: Vec<String>,
+    pub request_body: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl RouteDoc {
+    /// The registration `#[api_handler]` would have produced for the handler at `handler_path`
+    fn to_handler_documentation(&self, handler_path: &str) -> HandlerDocumentation<String> {
+        let (module_path, function_name) = handler_path.rsplit_once("::").unwrap_or(("", handler_path));
+        let lines = |lines: &[String]| if lines.is_empty() { String::new() } else { serde_json::json!(lines).to_string() };
+        HandlerDocumentation {
+            function_name: function_name.to_string(),
+            module_path: module_path.to_string(),
+            hidden: false,
+            deprecated: false,
+            summary: self.summary.clone(),
+            description: self.description.clone(),
+            parameters: lines(&self.parameters),
+            responses: lines(&self.responses),
+            request_body: lines(&self.request_body),
+            tags: lines(&self.tags),
+            tag_from_module: false,
+            extensions: "[]".to_string(),
+            external_docs: String::new(),
+            security: String::new(),
+            callbacks: "[]".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -66,8 +208,17 @@ pub struct SchemaRegistration {
     pub schema_json: &'static str,
 }
 
+/// Response descriptions from an `#[api_error]` enum's variant docs
+#[derive(Debug, Clone)]
+pub struct ErrorResponseRegistration {
+    pub type_name: &'static str,
+    /// Status code and description of the first documented variant with that status
+    pub descriptions: &'static [(u16, &'static str)],
+}
+
 inventory::collect!(HandlerDocumentation);
 inventory::collect!(SchemaRegistration);
+inventory::collect!(ErrorResponseRegistration);
 
 impl OpenAPI {
     pub fn new(title: &str, version: &str) -> Self {
@@ -79,7 +230,9 @@ impl OpenAPI {
                 terms_of_service: None,
                 contact: None,
                 license: None,
+                extensions: BTreeMap::new(),
             },
+            servers: Vec::new(),
             paths: HashMap::new(),
             components: None,
             tags: Vec::new(),
@@ -107,6 +260,7 @@ pub struct Info {
     pub terms_of_service: Option<String>,
     pub contact: Option<Contact>,
     pub license: Option<License>,
+    pub extensions: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -125,9 +279,168 @@ pub struct License {
 #[derive(Debug, Clone)]
 pub struct PathItem;
 
-// Helper function to extract handler name from type
-fn extract_handler_name<H>() -> String {
+/// Legal values for a parameter's `in` field
+const PARAMETER_LOCATIONS: [&str; 4] = ["path", "query", "header", "cookie"];
+
+/// Legal values for a parameter's `style` field
+const PARAMETER_STYLES: [&str; 7] = ["matrix", "label", "form", "simple", "spaceDelimited", "pipeDelimited", "deepObject"];
+
+/// Metadata from a trailing "[example: ..., default: ..., deprecated]" block in a parameter doc line
+#[derive(Debug, Default, PartialEq)]
+struct DescriptionMetadata {
+    description: String,
+    example: Option<String>,
+    default: Option<String>,
+    deprecated: bool,
+    style: Option<String>,
+    explode: Option<bool>,
+}
+
+/// A serialized spec as served by the spec routes, tagged with its `ETag`
+#[derive(Clone)]
+struct ServedSpec {
+    content_type: &'static str,
+    body: String,
+    etag: String,
+    /// The body compressed once up front, under a tag of its own since the bytes differ
+    #[cfg(feature = "gzip")]
+    gzipped: Option<(axum::body::Bytes, String)>,
+}
+
+impl ServedSpec {
+    fn new(content_type: &'static str, body: String) -> Self {
+        // FNV-1a, so the tag only changes when the spec does
+        let hash = body.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        Self {
+            content_type,
+            #[cfg(feature = "gzip")]
+            gzipped: Self::gzip(&body).map(|bytes| (bytes, format!("\"{hash:016x}-gzip\""))),
+            body,
+            etag: format!("\"{hash:016x}\""),
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    fn gzip(body: &str) -> Option<axum::body::Bytes> {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        match encoder.write_all(body.as_bytes()).and_then(|()| encoder.finish()) {
+            Ok(compressed) => Some(compressed.into()),
+            Err(err) => {
+                eprintln!("Warning: could not gzip the spec, serving it uncompressed: {err}");
+                None
+            }
+        }
+    }
+
+    /// Whether an `Accept-Encoding` header allows gzip, i.e. lists `gzip` or `*` without `q=0`
+    #[cfg(feature = "gzip")]
+    fn accepts_gzip(headers: &http::HeaderMap) -> bool {
+        headers
+            .get_all(http::header::ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|coding| {
+                let mut parts = coding.split(';').map(str::trim);
+                let name = parts.next().unwrap_or_default().to_ascii_lowercase();
+                let refused = parts.any(|param| {
+                    param.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()).is_some_and(|q| q == 0.0)
+                });
+                (name == "gzip" || name == "*") && !refused
+            })
+    }
+
+    /// 304 when `If-None-Match` names this spec's tag, the full spec otherwise
+    fn respond(&self, headers: &http::HeaderMap, cache_control: &str, cors_origin: Option<&http::HeaderValue>) -> axum::response::Response {
+        use axum::response::IntoResponse;
+
+        #[cfg(feature = "gzip")]
+        let gzipped = self.gzipped.as_ref().filter(|_| Self::accepts_gzip(headers));
+        #[cfg(feature = "gzip")]
+        let etag = gzipped.map_or(&self.etag, |(_, etag)| etag);
+        #[cfg(not(feature = "gzip"))]
+        let etag = &self.etag;
+
+        let cache_headers = [
+            (http::header::ETAG, etag.clone()),
+            (http::header::CACHE_CONTROL, cache_control.to_string()),
+        ];
+        let not_modified = headers
+            .get_all(http::header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag);
+        let mut response = if not_modified {
+            (http::StatusCode::NOT_MODIFIED, cache_headers).into_response()
+        } else {
+            (cache_headers, [(http::header::CONTENT_TYPE, self.content_type)], self.body.clone()).into_response()
+        };
+
+        #[cfg(feature = "gzip")]
+        {
+            if let Some((compressed, _)) = gzipped.filter(|_| !not_modified) {
+                *response.body_mut() = axum::body::Body::from(compressed.clone());
+                response.headers_mut().insert(http::header::CONTENT_ENCODING, http::HeaderValue::from_static("gzip"));
+            }
+            // Caches must not hand the compressed bytes to a client that didn't ask for them
+            response.headers_mut().insert(http::header::VARY, http::HeaderValue::from_static("Accept-Encoding"));
+        }
+
+        // Cross-origin scripts can only read the ETag when it is exposed
+        if let Some(origin) = cors_origin {
+            response.headers_mut().insert(http::header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+            response.headers_mut().insert(http::header::ACCESS_CONTROL_EXPOSE_HEADERS, http::HeaderValue::from_static("ETag"));
+        }
+        response
+    }
+
+    /// Answer a CORS preflight for the spec routes
+    fn preflight(cors_origin: &http::HeaderValue) -> axum::response::Response {
+        use axum::response::IntoResponse;
+
+        (
+            http::StatusCode::NO_CONTENT,
+            [
+                (http::header::ACCESS_CONTROL_ALLOW_ORIGIN, cors_origin.clone()),
+                (http::header::ACCESS_CONTROL_ALLOW_METHODS, http::HeaderValue::from_static("GET, OPTIONS")),
+                (http::header::ACCESS_CONTROL_ALLOW_HEADERS, http::HeaderValue::from_static("Accept, If-None-Match")),
+            ],
+        )
+            .into_response()
+    }
+}
+
+// Fully qualified path of a handler, e.g. `my_crate::users::list`
+fn extract_handler_path<H>() -> &'static str {
     std::any::type_name::<H>()
+}
+
+// Match a path against a glob pattern where `*` matches any run of characters
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == path,
+        Some((prefix, rest)) => {
+            let Some(remaining) = path.strip_prefix(prefix) else {
+                return false;
+            };
+            remaining
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain(std::iter::once(remaining.len()))
+                .any(|index| glob_matches(rest, &remaining[index..]))
+        }
+    }
+}
+
+// Bare function name at the end of a handler path
+fn handler_name_from_path(handler_path: &str) -> String {
+    handler_path
         .split("::")
         .last()
         .unwrap_or("unknown")
@@ -135,9 +448,11 @@ fn extract_handler_name<H>() -> String {
 }
 
 // Custom routing helper that carries handler metadata
+//
+// Each method keeps a router of its own, so a method that is already routed on
+// the path can be left out when the rest are registered.
 pub struct TrackedMethodRouter<S = ()> {
-    method_router: MethodRouter<S>,
-    handlers: Vec<(&'static str, String)>, // (method, handler_name) pairs
+    handlers: Vec<(&'static str, &'static str, MethodRouter<S>)>, // (method, handler_path, router) triples
 }
 
 impl<S> TrackedMethodRouter<S>
@@ -149,15 +464,12 @@ where
         H: axum::handler::Handler<T, S>,
         T: 'static,
     {
-        let fn_name = extract_handler_name::<H>();
+        let handler_path = extract_handler_path::<H>();
         
         let mut handlers = self.handlers;
-        handlers.push(("GET", fn_name));
+        handlers.push(("GET", handler_path, axum::routing::get(handler)));
         
-        Self {
-            method_router: self.method_router.get(handler),
-            handlers,
-        }
+        Self { handlers }
     }
 
     pub fn post<H, T>(self, handler: H) -> Self
@@ -165,15 +477,12 @@ where
         H: axum::handler::Handler<T, S>,
         T: 'static,
     {
-        let fn_name = extract_handler_name::<H>();
+        let handler_path = extract_handler_path::<H>();
         
         let mut handlers = self.handlers;
-        handlers.push(("POST", fn_name));
+        handlers.push(("POST", handler_path, axum::routing::post(handler)));
         
-        Self {
-            method_router: self.method_router.post(handler),
-            handlers,
-        }
+        Self { handlers }
     }
 
     pub fn put<H, T>(self, handler: H) -> Self
@@ -181,15 +490,12 @@ where
         H: axum::handler::Handler<T, S>,
         T: 'static,
     {
-        let fn_name = extract_handler_name::<H>();
+        let handler_path = extract_handler_path::<H>();
         
         let mut handlers = self.handlers;
-        handlers.push(("PUT", fn_name));
+        handlers.push(("PUT", handler_path, axum::routing::put(handler)));
         
-        Self {
-            method_router: self.method_router.put(handler),
-            handlers,
-        }
+        Self { handlers }
     }
 
     pub fn delete<H, T>(self, handler: H) -> Self
@@ -197,15 +503,12 @@ where
         H: axum::handler::Handler<T, S>,
         T: 'static,
     {
-        let fn_name = extract_handler_name::<H>();
+        let handler_path = extract_handler_path::<H>();
         
         let mut handlers = self.handlers;
-        handlers.push(("DELETE", fn_name));
+        handlers.push(("DELETE", handler_path, axum::routing::delete(handler)));
         
-        Self {
-            method_router: self.method_router.delete(handler),
-            handlers,
-        }
+        Self { handlers }
     }
 
     pub fn patch<H, T>(self, handler: H) -> Self
@@ -213,15 +516,51 @@ where
         H: axum::handler::Handler<T, S>,
         T: 'static,
     {
-        let fn_name = extract_handler_name::<H>();
+        let handler_path = extract_handler_path::<H>();
         
         let mut handlers = self.handlers;
-        handlers.push(("PATCH", fn_name));
+        handlers.push(("PATCH", handler_path, axum::routing::patch(handler)));
         
-        Self {
-            method_router: self.method_router.patch(handler),
-            handlers,
-        }
+        Self { handlers }
+    }
+
+    pub fn head<H, T>(self, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, S>,
+        T: 'static,
+    {
+        let handler_path = extract_handler_path::<H>();
+        
+        let mut handlers = self.handlers;
+        handlers.push(("HEAD", handler_path, axum::routing::head(handler)));
+        
+        Self { handlers }
+    }
+
+    pub fn options<H, T>(self, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, S>,
+        T: 'static,
+    {
+        let handler_path = extract_handler_path::<H>();
+        
+        let mut handlers = self.handlers;
+        handlers.push(("OPTIONS", handler_path, axum::routing::options(handler)));
+        
+        Self { handlers }
+    }
+
+    pub fn trace<H, T>(self, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, S>,
+        T: 'static,
+    {
+        let handler_path = extract_handler_path::<H>();
+        
+        let mut handlers = self.handlers;
+        handlers.push(("TRACE", handler_path, axum::routing::trace(handler)));
+        
+        Self { handlers }
     }
 }
 
@@ -232,21 +571,80 @@ pub trait OpenApiSchema {
     }
 }
 
+// How many times `registered_schema_names()` has walked inventory on this thread
+#[cfg(test)]
+thread_local! {
+    static REGISTERED_SCHEMA_WALKS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 // Simple router wrapper
 pub struct ApiRouter<S = ()> {
     router: Router<S>,
     openapi: OpenAPI,
     routes: Vec<RouteInfo>,
     used_schemas: std::collections::HashSet<String>,
+    /// Key handlers by their fully qualified path rather than the bare function name
+    full_handler_paths: bool,
+    /// Glob patterns for paths left out of the spec
+    excluded_paths: Vec<String>,
+    /// Where the JSON spec is served, set by `with_openapi_routes*`
+    #[cfg_attr(not(any(feature = "swagger-ui", feature = "redoc")), allow(dead_code))]
+    spec_json_path: Option<String>,
+    /// Spec built by `build_spec`, cleared whenever routes or metadata change
+    spec_cache: Option<openapi::OpenAPI>,
+    /// Security scheme every operation requires unless its handler says otherwise
+    default_security: Option<String>,
+    /// Parameters registered once under `components.parameters`
+    shared_parameters: BTreeMap<String, openapi::Parameter>,
+    /// Responses registered once under `components.responses`
+    shared_responses: BTreeMap<String, openapi::Response>,
+    /// Hoist inline schemas that appear more than once into `components.schemas`
+    dedupe_inline_schemas: bool,
+    /// Serve the JSON spec pretty-printed from `with_openapi_routes()`
+    pretty_json: bool,
+    /// `Cache-Control` max-age in seconds for the spec routes; `None` sends `no-cache`
+    spec_max_age: Option<u64>,
+    /// `Access-Control-Allow-Origin` sent by the spec routes; `None` sends no CORS headers
+    spec_cors_origin: Option<http::HeaderValue>,
+    /// Hand-written specs folded into the generated one by `merge_spec()`
+    merged_specs: Vec<openapi::OpenAPI>,
+    /// Whether merged specs replace generated definitions that share a name
+    merged_specs_override: bool,
+    /// `openapi` field of the document, `3.0.0` when unset
+    openapi_version: Option<String>,
+    /// Panic instead of warning when a method and path are routed twice
+    panic_on_duplicate_routes: bool,
+    /// `(method, path)` of registrations skipped because the route was already taken
+    duplicate_routes: Vec<(String, String)>,
 }
 
 impl ApiRouter<()> {
+    /// Create a router documenting an API called `title` at `version`
+    ///
+    /// Both end up in the spec's `info` object, where OpenAPI requires them to be
+    /// non-empty; `validate()` reports an empty one.
     pub fn new(title: &str, version: &str) -> Self {
         Self {
             router: Router::new(),
             openapi: OpenAPI::new(title, version),
             routes: Vec::new(),
             used_schemas: std::collections::HashSet::new(),
+            full_handler_paths: false,
+            excluded_paths: Vec::new(),
+            spec_json_path: None,
+            spec_cache: None,
+            default_security: None,
+            shared_parameters: BTreeMap::new(),
+            shared_responses: BTreeMap::new(),
+            dedupe_inline_schemas: false,
+            pretty_json: false,
+            spec_max_age: None,
+            spec_cors_origin: None,
+            merged_specs: Vec::new(),
+            merged_specs_override: false,
+            openapi_version: None,
+            panic_on_duplicate_routes: false,
+            duplicate_routes: Vec::new(),
         }
     }
 }
@@ -279,30 +677,107 @@ where
             openapi: OpenAPI::new(title, version),
             routes: Vec::new(),
             used_schemas: std::collections::HashSet::new(),
+            full_handler_paths: false,
+            excluded_paths: Vec::new(),
+            spec_json_path: None,
+            spec_cache: None,
+            default_security: None,
+            shared_parameters: BTreeMap::new(),
+            shared_responses: BTreeMap::new(),
+            dedupe_inline_schemas: false,
+            pretty_json: false,
+            spec_max_age: None,
+            spec_cors_origin: None,
+            merged_specs: Vec::new(),
+            merged_specs_override: false,
+            openapi_version: None,
+            panic_on_duplicate_routes: false,
+            duplicate_routes: Vec::new(),
         }
     }
 
     // Use into_router().with_state(your_state) for state management
     pub fn route(mut self, path: &str, tracked: TrackedMethodRouter<S>) -> Self {
-        // Track all handlers in this method router
-        for (method, handler_name) in tracked.handlers {
+        // Track all handlers in this method router, leaving out methods already routed
+        let mut method_router: Option<MethodRouter<S>> = None;
+        for (method, handler_path, handler_router) in tracked.handlers {
+            if self.route_claimed(path, method, handler_path) {
+                continue;
+            }
             self.routes.push(RouteInfo {
                 path: path.to_string(),
                 method: method.to_string(),
-                function_name: handler_name.clone(),
+                function_name: handler_name_from_path(handler_path),
+                handler_path: handler_path.to_string(),
                 summary: Some(format!("{} {}", method, path)),
                 description: None,
+                doc: None,
+            });
+            method_router = Some(match method_router {
+                Some(method_router) => method_router.merge(handler_router),
+                None => handler_router,
             });
         }
 
         // Update OpenAPI spec
         self.openapi.paths.insert(path.to_string(), PathItem);
+        self.spec_cache = None;
 
         // Register route with the underlying router
-        self.router = self.router.route(path, tracked.method_router);
+        if let Some(method_router) = method_router {
+            self.router = self.router.route(path, method_router);
+        }
+        self
+    }
+
+    /// Register a route together with its documentation
+    ///
+    /// For handlers that can't carry `#[api_handler]`, such as ones from another
+    /// crate. `doc` applies to every handler in `tracked` and takes precedence
+    /// over any `#[api_handler]` registration they have.
+    pub fn route_documented(mut self, path: &str, tracked: TrackedMethodRouter<S>, doc: RouteDoc) -> Self {
+        let first_route = self.routes.len();
+        let handler_paths: Vec<&'static str> = tracked.handlers.iter().map(|(_, handler_path, _)| *handler_path).collect();
+        self = self.route(path, tracked);
+        for route in &mut self.routes[first_route..] {
+            if let Some(handler_path) = handler_paths.iter().find(|handler_path| **handler_path == route.handler_path) {
+                route.doc = Some(doc.to_handler_documentation(handler_path));
+            }
+        }
+        self
+    }
+
+    /// Panic on a method and path that are routed twice instead of skipping the second
+    ///
+    /// By default the second registration is left out with a warning, so the first
+    /// handler keeps serving and documenting the route and `validate()` reports a
+    /// [`SpecWarning::DuplicateRoute`].
+    pub fn panic_on_duplicate_routes(mut self, enabled: bool) -> Self {
+        self.panic_on_duplicate_routes = enabled;
         self
     }
 
+    /// Whether `method path` is already routed, in which case the new handler is skipped
+    ///
+    /// The warning, or the panic with `panic_on_duplicate_routes(true)`, names both
+    /// handlers; Axum's own overlap panic names only the method and path.
+    fn route_claimed(&mut self, path: &str, method: &str, handler_path: &str) -> bool {
+        let Some(existing) = self.routes.iter().find(|route| route.path == path && route.method == method) else {
+            return false;
+        };
+        let message = format!(
+            "{method} {path} is routed twice, to {} and to {}",
+            existing.function_name,
+            handler_name_from_path(handler_path)
+        );
+        if self.panic_on_duplicate_routes {
+            panic!("{message}");
+        }
+        eprintln!("Warning: {message}; keeping {}", existing.function_name);
+        self.duplicate_routes.push((method.to_string(), path.to_string()));
+        true
+    }
+
     // Helper method to register an HTTP method handler
     fn register_http_method<H, T>(
         mut self,
@@ -315,17 +790,23 @@ where
         H: axum::handler::Handler<T, S>,
         T: 'static,
     {
-        let fn_name = extract_handler_name::<H>();
+        let handler_path = extract_handler_path::<H>();
+        if self.route_claimed(path, method, handler_path) {
+            return self;
+        }
 
         self.routes.push(RouteInfo {
             path: path.to_string(),
             method: method.to_string(),
-            function_name: fn_name,
+            function_name: handler_name_from_path(handler_path),
+            handler_path: handler_path.to_string(),
             summary: Some(format!("{method} {path}")),
             description: None,
+            doc: None,
         });
 
         self.openapi.paths.insert(path.to_string(), PathItem);
+        self.spec_cache = None;
         self.router = self.router.route(path, route_fn(handler));
         self
     }
@@ -370,19 +851,52 @@ where
         self.register_http_method(path, "PATCH", handler, axum::routing::patch)
     }
 
+    pub fn head<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, S>,
+        T: 'static,
+    {
+        self.register_http_method(path, "HEAD", handler, axum::routing::head)
+    }
+
+    pub fn options<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, S>,
+        T: 'static,
+    {
+        self.register_http_method(path, "OPTIONS", handler, axum::routing::options)
+    }
+
+    pub fn trace<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, S>,
+        T: 'static,
+    {
+        self.register_http_method(path, "TRACE", handler, axum::routing::trace)
+    }
+
     pub fn openapi_spec(&self) -> &OpenAPI {
         &self.openapi
     }
 
+    /// The routes registered so far, in registration order, one per method
+    ///
+    /// Reads what `route()` and the method helpers tracked without generating the spec.
+    pub fn routes(&self) -> impl Iterator<Item = &RouteInfo> {
+        self.routes.iter()
+    }
+
     /// Set the API description
     pub fn description(mut self, description: &str) -> Self {
         self.openapi.info.description = Some(description.to_string());
+        self.spec_cache = None;
         self
     }
 
     /// Set the terms of service URL
     pub fn terms_of_service(mut self, terms_of_service: &str) -> Self {
         self.openapi.info.terms_of_service = Some(terms_of_service.to_string());
+        self.spec_cache = None;
         self
     }
 
@@ -393,6 +907,7 @@ where
             url: url.map(|s| s.to_string()),
             email: email.map(|s| s.to_string()),
         });
+        self.spec_cache = None;
         self
     }
 
@@ -403,6 +918,7 @@ where
             url: None,
             email: Some(email.to_string()),
         });
+        self.spec_cache = None;
         self
     }
 
@@ -412,6 +928,46 @@ where
             name: name.to_string(),
             url: url.map(|s| s.to_string()),
         });
+        self.spec_cache = None;
+        self
+    }
+
+    /// Add a server the API is reachable at
+    ///
+    /// Servers are emitted in the order they are added.
+    pub fn server(mut self, url: &str, description: Option<&str>) -> Self {
+        self.openapi.servers.push(openapi::Server {
+            url: url.to_string(),
+            description: description.map(|s| s.to_string()),
+            variables: None,
+        });
+        self.spec_cache = None;
+        self
+    }
+
+    /// Add a server whose URL has `{name}` placeholders, e.g.
+    /// `https://{tenant}.api.example.com/{version}`
+    ///
+    /// Each placeholder should have an entry in `variables`; missing ones are
+    /// reported as a warning.
+    pub fn server_with_variables<'a>(
+        mut self,
+        url: &str,
+        variables: impl IntoIterator<Item = (&'a str, openapi::ServerVariable)>,
+    ) -> Self {
+        let variables: BTreeMap<String, openapi::ServerVariable> =
+            variables.into_iter().map(|(name, variable)| (name.to_string(), variable)).collect();
+        for placeholder in url.split('{').skip(1).filter_map(|rest| rest.split_once('}')).map(|(name, _)| name) {
+            if !variables.contains_key(placeholder) {
+                eprintln!("Warning: server URL {url} uses {{{placeholder}}}, which has no variable");
+            }
+        }
+        self.openapi.servers.push(openapi::Server {
+            url: url.to_string(),
+            description: None,
+            variables: Some(variables).filter(|variables| !variables.is_empty()),
+        });
+        self.spec_cache = None;
         self
     }
 
@@ -422,6 +978,7 @@ where
             description: description.map(|s| s.to_string()),
             external_docs: None,
         });
+        self.spec_cache = None;
         self
     }
 
@@ -435,277 +992,998 @@ where
                 url: docs_url.to_string(),
             }),
         });
+        self.spec_cache = None;
         self
     }
 
-    pub fn openapi_json(&mut self) -> String {
-        // Clear used schemas to track fresh usage
-        self.used_schemas.clear();
-
-        // Build info section with all optional fields
-        let mut info_parts = vec![
-            format!("\"title\":\"{}\"", self.openapi.info.title),
-            format!("\"version\":\"{}\"", self.openapi.info.version),
-        ];
-
-        if let Some(ref description) = self.openapi.info.description {
-            info_parts.push(format!("\"description\":\"{}\"", description.replace("\"", "\\\"")));
-        }
+    /// Register a parameter under `components.parameters` for handlers to share
+    ///
+    /// Handlers opt in with a `- page (ref)` line in their `# Parameters` doc
+    /// section, which documents `$ref: "#/components/parameters/page"` instead
+    /// of repeating the parameter.
+    pub fn shared_parameter(mut self, name: &str, parameter: openapi::Parameter) -> Self {
+        self.shared_parameters.insert(name.to_string(), parameter);
+        self.spec_cache = None;
+        self
+    }
 
-        if let Some(ref terms_of_service) = self.openapi.info.terms_of_service {
-            info_parts.push(format!("\"termsOfService\":\"{terms_of_service}\""));
-        }
+    /// Register a response under `components.responses` for handlers to share
+    ///
+    /// A handler refers to it from its `# Responses` doc section with an entry
+    /// like `- 401: $ref Unauthorized`, so a standard error envelope is
+    /// described once.
+    pub fn shared_response(mut self, name: &str, response: openapi::Response) -> Self {
+        self.shared_responses.insert(name.to_string(), response);
+        self.spec_cache = None;
+        self
+    }
 
-        if let Some(ref contact) = self.openapi.info.contact {
-            let mut contact_parts = Vec::new();
-            if let Some(ref name) = contact.name {
-                contact_parts.push(format!("\"name\":\"{name}\""));
-            }
-            if let Some(ref url) = contact.url {
-                contact_parts.push(format!("\"url\":\"{url}\""));
-            }
-            if let Some(ref email) = contact.email {
-                contact_parts.push(format!("\"email\":\"{email}\""));
-            }
-            if !contact_parts.is_empty() {
-                info_parts.push(format!("\"contact\":{{{}}}", contact_parts.join(",")));
-            }
-        }
+    /// Move repeated inline body schemas into `components.schemas`
+    ///
+    /// Object schemas written out in full under more than one request or
+    /// response, such as bodies documented field by field in the doc comment,
+    /// are stored once under a name derived from the first operation using
+    /// them (`CreateUserRequest`) and replaced with `$ref`s. Off by default,
+    /// since adding a route can change which name a schema gets.
+    pub fn dedupe_inline_schemas(mut self, enabled: bool) -> Self {
+        self.dedupe_inline_schemas = enabled;
+        self.spec_cache = None;
+        self
+    }
 
-        if let Some(ref license) = self.openapi.info.license {
-            let mut license_parts = vec![format!("\"name\":\"{}\"", license.name)];
-            if let Some(ref url) = license.url {
-                license_parts.push(format!("\"url\":\"{url}\""));
-            }
-            info_parts.push(format!("\"license\":{{{}}}", license_parts.join(",")));
-        }
+    /// Require a security scheme on every operation by default
+    ///
+    /// The requirement is written once at the top of the spec. Handlers opt out
+    /// with `#[api_handler(public)]`, which documents `security: []`, or name
+    /// other schemes in a `# Security` doc section. Operations only carry their
+    /// own `security` when it differs from this default.
+    pub fn default_security(mut self, scheme_name: &str) -> Self {
+        self.default_security = Some(scheme_name.to_string());
+        self.spec_cache = None;
+        self
+    }
 
-        let mut json = format!(
-            r#"{{"openapi":"3.0.0","info":{{{}}},"#,
-            info_parts.join(",")
-        );
+    /// Identify handlers by their fully qualified path instead of the bare function name
+    ///
+    /// Handlers with the same name in different modules otherwise share
+    /// documentation and an operationId. With this enabled, operationIds
+    /// become paths such as `my_crate::users::list`.
+    pub fn full_handler_paths(mut self, enabled: bool) -> Self {
+        self.full_handler_paths = enabled;
+        self.spec_cache = None;
+        self
+    }
 
-        // Collect all registered handler documentation
-        let handler_docs: HashMap<&str, &HandlerDocumentation> = inventory::iter::<HandlerDocumentation>()
-            .map(|doc| (doc.function_name, doc))
-            .collect();
+    /// Leave routes whose path matches a glob pattern out of the spec
+    ///
+    /// `*` matches any run of characters, including `/`, so `/internal/*` hides
+    /// everything under `/internal/`. Patterns are checked against both the
+    /// registered path and its `{param}` form. Matching routes are still served.
+    pub fn exclude_path(mut self, pattern: &str) -> Self {
+        self.excluded_paths.push(pattern.to_string());
+        self.spec_cache = None;
+        self
+    }
 
-        // First pass: Process all documentation to track schema usage
-        let routes_clone = self.routes.clone();
-        for route in &routes_clone {
-            if let Some(doc) = handler_docs.get(route.function_name.as_str()) {
-                if !doc.request_body.is_empty() && doc.request_body != "[]" {
-                    let _ = self.parse_request_body_to_openapi(doc.request_body);
-                }
-                if !doc.responses.is_empty() && doc.responses != "[]" {
-                    let _ = self.parse_responses_to_openapi(doc.responses);
-                }
-            }
+    /// Set the `openapi` version written to the document, e.g. `3.0.3`
+    ///
+    /// The generated document follows OpenAPI 3.0, so only `3.0.x` versions are
+    /// accepted; anything else is reported and the current version kept.
+    pub fn openapi_version(mut self, version: &str) -> Self {
+        let patch = version.strip_prefix("3.0.");
+        if !patch.is_some_and(|patch| !patch.is_empty() && patch.chars().all(|c| c.is_ascii_digit())) {
+            eprintln!("Warning: OpenAPI version {version} is not supported, expected 3.0.x");
+            return self;
         }
+        self.openapi_version = Some(version.to_string());
+        self.spec_cache = None;
+        self
+    }
 
-        // Group routes by path
-        let mut path_methods: HashMap<String, Vec<&RouteInfo>> = HashMap::new();
-        for route in &self.routes {
-            path_methods.entry(route.path.clone()).or_default().push(route);
-        }
+    /// Add a vendor extension to the info object, e.g. `x-logo` for ReDoc
+    ///
+    /// Names should start with `x-`; they are serialized alongside the standard fields.
+    pub fn info_extension(mut self, name: &str, value: serde_json::Value) -> Self {
+        self.openapi.info.extensions.insert(name.to_string(), value);
+        self.spec_cache = None;
+        self
+    }
 
-        // Clone the routes to avoid borrowing issues
-        let routes_clone = self.routes.clone();
+    pub fn openapi_json(&mut self) -> String {
+        self.build_spec()
+            .to_json_compact()
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to serialize OpenAPI spec: {e}");
+                "{}".to_string()
+            })
+    }
 
-        // Collect used schemas separately to avoid borrowing issues
-        let mut all_used_schemas = std::collections::HashSet::new();
+    /// The JSON spec indented for reading, `openapi_json()` stays compact
+    pub fn openapi_json_pretty(&mut self) -> String {
+        self.build_spec()
+            .to_json()
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to serialize OpenAPI spec: {e}");
+                "{}".to_string()
+            })
+    }
 
-        // Process each path and collect schemas
-        for route in &routes_clone {
-            let doc = handler_docs.get(route.function_name.as_str());
+    /// Serve pretty-printed JSON from the spec routes instead of the compact form
+    ///
+    /// Handy when the spec is mostly read in a browser; clients that parse it
+    /// see the same document either way.
+    pub fn pretty_json(mut self, enabled: bool) -> Self {
+        self.pretty_json = enabled;
+        self
+    }
 
-            if let Some(doc) = doc {
-                // Process request body schemas
-                if !doc.request_body.is_empty() && doc.request_body != "[]" {
-                    let mut temp_router: ApiRouter<()> = ApiRouter::new("temp", "temp");
-                    let _ = temp_router.parse_request_body_to_openapi(doc.request_body);
-                    for schema in temp_router.used_schemas {
-                        all_used_schemas.insert(schema);
-                    }
-                }
+    /// Let clients cache the served spec for `seconds` without revalidating
+    ///
+    /// By default the spec routes send `Cache-Control: no-cache`, so clients
+    /// revalidate with the `ETag` on every fetch. A spec that only changes on
+    /// deploy can be given a max-age instead.
+    pub fn spec_max_age(mut self, seconds: u64) -> Self {
+        self.spec_max_age = Some(seconds);
+        self
+    }
 
-                // Process response schemas
-                if !doc.responses.is_empty() && doc.responses != "[]" {
-                    let mut temp_router: ApiRouter<()> = ApiRouter::new("temp", "temp");
-                    let _ = temp_router.parse_responses_to_openapi(doc.responses);
-                    for schema in temp_router.used_schemas {
-                        all_used_schemas.insert(schema);
-                    }
-                }
-            }
+    /// Let browser tools on another origin fetch the served spec
+    ///
+    /// The spec routes then send `Access-Control-Allow-Origin: origin` and answer
+    /// CORS preflight requests; pass `"*"` to allow any origin. Routes added for
+    /// handlers get no CORS headers from this.
+    pub fn spec_cors(mut self, origin: &str) -> Self {
+        match http::HeaderValue::from_str(origin) {
+            Ok(origin) => self.spec_cors_origin = Some(origin),
+            Err(_) => eprintln!("Warning: spec CORS origin '{origin}' is not a valid header value, ignoring it"),
         }
+        self
+    }
 
-        let paths: Vec<String> = path_methods.iter().map(|(path, routes)| {
-            // Convert Axum path format (:param) to OpenAPI format ({param})
-            let openapi_path = self.convert_path_to_openapi(path);
-            let methods: Vec<String> = routes.iter().map(|route| {
-                // Look up documentation for this handler
-                let doc = handler_docs.get(route.function_name.as_str());
+    /// The JSON spec in the form the spec routes serve it
+    fn served_openapi_json(&mut self) -> String {
+        if self.pretty_json {
+            self.openapi_json_pretty()
+        } else {
+            self.openapi_json()
+        }
+    }
 
-                let (summary, description) = if let Some(doc) = doc {
-                    (doc.summary.to_string(), doc.description.to_string())
-                } else {
-                    (
-                        route.summary.clone().unwrap_or_else(|| format!("{} {}", route.method, path)),
-                        "No description available".to_string()
-                    )
-                };
+    /// Write the pretty-printed JSON spec to `path`
+    ///
+    /// Meant for `build.rs` scripts and xtask binaries that keep a committed
+    /// copy of the spec in sync. IO and serialization failures are returned.
+    pub fn write_openapi_json(&mut self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let json = self.build_spec().to_json().map_err(std::io::Error::other)?;
+        std::fs::write(path, json + "\n")
+    }
 
-                // Build proper OpenAPI method object
-                let mut method_parts = vec![
-                    format!(r#""summary": "{}""#, summary.replace("\"", "\\\"")),
-                    format!(r#""description": "{}""#, description.replace("\"", "\\\""))
-                ];
-
-                // Add tags if present
-                if let Some(doc) = doc {
-                    if !doc.tags.is_empty() && doc.tags != "[]" {
-                        let tags = self.parse_tags_to_openapi(doc.tags);
-                        if !tags.is_empty() {
-                            method_parts.push(format!(r#""tags": {tags}"#));
-                        }
-                    }
+    /// Write the YAML spec to `path`, the YAML counterpart of `write_openapi_json()`
+    pub fn write_openapi_yaml(&mut self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.build_spec().to_yaml())
+    }
 
-                    // Add parameters in proper OpenAPI format
-                    if !doc.parameters.is_empty() && doc.parameters != "[]" {
-                        let parameters = self.parse_parameters_to_openapi(doc.parameters);
-                        if !parameters.is_empty() {
-                            method_parts.push(format!(r#""parameters": {parameters}"#));
-                        }
-                    }
+    /// The spec as a Markdown reference, a section per tag and a subsection per
+    /// operation; see [`markdown::to_markdown`]
+    pub fn to_markdown(&mut self) -> String {
+        markdown::to_markdown(self.build_spec())
+    }
 
-                    // Add security requirements for authenticated endpoints
-                    if doc.parameters.contains("__REQUIRES_AUTH__") {
-                        method_parts.push(r#""security": [{"sessionAuth": []}]"#.to_string());
-                    }
+    /// TypeScript declarations for the spec's component schemas
+    ///
+    /// Each schema becomes an `interface`, or a `type` alias when it isn't a
+    /// plain object; see [`typescript::to_typescript`] for what is covered.
+    #[cfg(feature = "typescript")]
+    pub fn to_typescript(&mut self) -> String {
+        typescript::to_typescript(self.build_spec())
+    }
 
-                    // Add request body in proper OpenAPI format (processing already done in first pass)
-                    if !doc.request_body.is_empty() && doc.request_body != "[]" {
-                        // Create a temporary router to avoid borrowing issues
-                        let mut temp_router: ApiRouter<()> = ApiRouter::new("temp", "temp");
-                        let request_body = temp_router.parse_request_body_to_openapi(doc.request_body);
-                        method_parts.push(format!(r#""requestBody": {request_body}"#));
-                    }
+    /// Build the spec as JSON with only the operations tagged with one of `tags`
+    ///
+    /// The spec is built with the router's full configuration, keeping only the
+    /// routes and `merge_spec()` operations with a matching tag. Component schemas,
+    /// merged ones included, are pruned to those the remaining document uses,
+    /// directly or transitively, and declared tags that no remaining operation
+    /// uses are dropped. The cached full spec is left untouched.
+    pub fn openapi_json_filtered(&mut self, tags: &[&str]) -> String {
+        let handler_docs = self.collect_handler_docs();
+
+        // The filtered build records its own schema usage; keep the full spec's
+        let used_schemas = std::mem::take(&mut self.used_schemas);
+        let mut spec = self.build_openapi_with_docs(&handler_docs, Some(tags));
+        self.used_schemas = used_schemas;
+
+        Self::retain_referenced_schemas(&mut spec);
+        let used_tags: std::collections::BTreeSet<String> = spec.paths.values()
+            .flat_map(|path_item| path_item.operations())
+            .flat_map(|operation| operation.tags.iter().cloned())
+            .collect();
+        spec.tags = spec.tags
+            .map(|spec_tags| spec_tags.into_iter().filter(|tag| used_tags.contains(&tag.name)).collect::<Vec<_>>())
+            .filter(|spec_tags| !spec_tags.is_empty());
+        spec.to_json_compact().unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to serialize OpenAPI spec: {e}");
+            "{}".to_string()
+        })
+    }
 
-                    // Add responses in proper OpenAPI format (processing already done in first pass)
-                    if !doc.responses.is_empty() && doc.responses != "[]" {
-                        // Create a temporary router to avoid borrowing issues
-                        let mut temp_router: ApiRouter<()> = ApiRouter::new("temp", "temp");
-                        let responses = temp_router.parse_responses_to_openapi(doc.responses);
-                        method_parts.push(format!(r#""responses": {responses}"#));
-                    } else {
-                        // Default response structure
-                        method_parts.push(r#""responses": {"200": {"description": "Successful response"}}"#.to_string());
-                    }
-                } else {
-                    // Default response structure
-                    method_parts.push(r#""responses": {"200": {"description": "Successful response"}}"#.to_string());
-                }
+    /// Build the typed OpenAPI document, reusing the cached one if nothing changed
+    ///
+    /// The cache is cleared by any builder that adds routes, tags, servers or
+    /// info metadata, and by `merge()`.
+    pub fn build_spec(&mut self) -> &openapi::OpenAPI {
+        let spec = match self.spec_cache.take() {
+            Some(spec) => spec,
+            None => self.build_openapi_struct(),
+        };
+        self.spec_cache.insert(spec)
+    }
 
-                format!(r#""{}": {{{}}}"#, route.method.to_lowercase(), method_parts.join(","))
-            }).collect();
+    /// Fold a hand-written spec into the generated one
+    ///
+    /// Its paths, component schemas, parameters, responses and security schemes,
+    /// and tags are added to every spec this router builds. Operations are merged
+    /// method by method, so a hand-written `DELETE /users/{id}` sits next to a
+    /// generated `GET /users/{id}`. Where both define the same thing the
+    /// generated definition is kept, unless `merged_specs_override(true)` is set.
+    pub fn merge_spec(&mut self, spec: openapi::OpenAPI) {
+        self.merged_specs.push(spec);
+        self.spec_cache = None;
+    }
 
-            format!(r#""{}": {{{}}}"#, openapi_path, methods.join(","))
-        }).collect();
+    /// Let definitions from `merge_spec()` replace generated ones with the same name
+    pub fn merged_specs_override(mut self, enabled: bool) -> Self {
+        self.merged_specs_override = enabled;
+        self.spec_cache = None;
+        self
+    }
+
+    /// Build an owned copy of the typed OpenAPI document
+    ///
+    /// Goes through the same pipeline (and cache) as `openapi_json()`, so the
+    /// result can be inspected or edited before serializing it yourself.
+    pub fn build_openapi(&mut self) -> openapi::OpenAPI {
+        self.build_spec().clone()
+    }
+
+    /// Build the typed OpenAPI document for every registered route
+    fn build_openapi_struct(&mut self) -> openapi::OpenAPI {
+        let handler_docs = self.collect_handler_docs();
+        self.build_openapi_with_docs(&handler_docs, None)
+    }
+
+    /// Build the typed OpenAPI document from handler docs already gathered from inventory
+    ///
+    /// Both passes below read the same `handler_docs`, so inventory is walked once per build.
+    /// With `tags`, only routes and merged operations carrying one of them are included.
+    fn build_openapi_with_docs(
+        &mut self,
+        handler_docs: &HashMap<String, &'static HandlerDocumentation>,
+        tags: Option<&[&str]>,
+    ) -> openapi::OpenAPI {
+        // First pass: track the schemas the included routes use, starting fresh
+        let registered_schemas = Self::registered_schema_names();
+        let mut used_schemas = std::collections::HashSet::new();
+        for route in self.routes.iter().filter(|route| self.in_spec(route, handler_docs, tags)) {
+            if let Some(doc) = self.route_doc(route, handler_docs) {
+                Self::collect_schemas_for_handler(&mut used_schemas, &registered_schemas, &doc);
+            }
+        }
+        self.used_schemas = used_schemas;
+
+        // Shared parameters and responses can point at registered schemas too
+        let shared_components = serde_json::json!([self.shared_parameters, self.shared_responses]);
+        let mut shared_refs = Vec::new();
+        Self::collect_schema_references(&shared_components, &mut shared_refs);
+        self.used_schemas.extend(shared_refs);
 
-        // Add paths section
-        json.push_str(r#""paths":{"#);
-        json.push_str(&paths.join(","));
-        json.push('}');
+        // Recursively collect all transitively referenced schemas
+        for (schema, referenced_by) in self.collect_transitive_schema_dependencies() {
+            eprintln!("Warning: schema '{referenced_by}' references '{schema}', which is not a registered schema");
+        }
 
-        // Add tags section if there are tags
+        let mut spec = openapi::OpenAPI::new(&self.openapi.info.title, &self.openapi.info.version);
+        if let Some(version) = &self.openapi_version {
+            spec.openapi = version.clone();
+        }
+        spec.info = self.build_info();
+        spec.servers = self.openapi.servers.clone();
+        spec.paths = self.build_paths(handler_docs, &registered_schemas, tags);
+        spec.components = self.build_components(handler_docs, tags);
+        spec.security = self.default_security.as_deref().map(Self::security_requirement);
         if !self.openapi.tags.is_empty() {
-            json.push_str(r#","tags":["#);
-            let tag_entries: Vec<String> = self.openapi.tags.iter()
-                .map(|tag| {
-                    let mut tag_obj = vec![format!(r#""name":"{}""#, tag.name)];
-                    if let Some(ref description) = tag.description {
-                        tag_obj.push(format!(r#""description":"{}""#, description.replace("\"", "\\\"")));
-                    }
-                    if let Some(ref external_docs) = tag.external_docs {
-                        let mut docs_parts = vec![format!(r#""url":"{}""#, external_docs.url)];
-                        if let Some(ref desc) = external_docs.description {
-                            docs_parts.push(format!(r#""description":"{}""#, desc.replace("\"", "\\\"")));
-                        }
-                        tag_obj.push(format!(r#""externalDocs":{{{}}}"#, docs_parts.join(",")));
-                    }
-                    format!("{{{}}}", tag_obj.join(","))
-                })
-                .collect();
-            json.push_str(&tag_entries.join(","));
-            json.push(']');
+            spec.tags = Some(self.build_tags());
+        }
+        for merged in &self.merged_specs {
+            let mut merged = merged.clone();
+            if let Some(tags) = tags {
+                Self::retain_tagged_operations(&mut merged, tags);
+            }
+            Self::merge_into_spec(&mut spec, merged, self.merged_specs_override);
+        }
+        if self.dedupe_inline_schemas {
+            Self::hoist_repeated_inline_schemas(&mut spec);
+        }
+        spec
+    }
+
+    /// Drop the operations of `spec` that carry none of `tags`, and the paths left empty
+    fn retain_tagged_operations(spec: &mut openapi::OpenAPI, tags: &[&str]) {
+        for item in spec.paths.values_mut() {
+            let slots = [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.patch,
+                &mut item.delete,
+                &mut item.head,
+                &mut item.options,
+                &mut item.trace,
+            ];
+            for slot in slots {
+                if slot.as_ref().is_some_and(|operation| !operation.tags.iter().any(|tag| tags.contains(&tag.as_str()))) {
+                    *slot = None;
+                }
+            }
         }
+        spec.paths.retain(|_, item| item.operations().next().is_some());
+    }
+
+    /// Drop component schemas that nothing outside `components.schemas` refers to,
+    /// directly or through other schemas
+    fn retain_referenced_schemas(spec: &mut openapi::OpenAPI) {
+        let Some(schemas) = spec.components.as_mut().map(|components| std::mem::take(&mut components.schemas)) else {
+            return;
+        };
 
-        // Merge collected schemas into the main router's used_schemas
-        for schema in all_used_schemas {
-            self.used_schemas.insert(schema);
+        let mut pending = Vec::new();
+        Self::collect_schema_references(&serde_json::to_value(&*spec).unwrap_or_default(), &mut pending);
+        let mut referenced = std::collections::HashSet::new();
+        while let Some(name) = pending.pop() {
+            if let Some(schema) = schemas.get(&name).filter(|_| !referenced.contains(&name)) {
+                Self::collect_schema_references(&serde_json::to_value(schema).unwrap_or_default(), &mut pending);
+                referenced.insert(name);
+            }
         }
 
-        // Recursively collect all transitively referenced schemas
-        self.collect_transitive_schema_dependencies();
+        if let Some(components) = spec.components.as_mut() {
+            components.schemas = schemas.into_iter().filter(|(name, _)| referenced.contains(name)).collect();
+        }
+    }
 
-        // Add components section with only used schemas
-        let mut used_components_schemas: HashMap<String, String> = HashMap::new();
-        for schema_reg in inventory::iter::<SchemaRegistration>() {
-            let schema_name = schema_reg.type_name.to_string();
-            if self.used_schemas.contains(&schema_name) {
-                used_components_schemas.insert(
-                    schema_name,
-                    schema_reg.schema_json.to_string()
-                );
-            }
-        }
-
-        // Check if any endpoint uses authentication (has Authorized parameter)
-        let has_auth_endpoints = self.routes.iter().any(|route| {
-            // Find the handler documentation for this route
-            inventory::iter::<HandlerDocumentation>()
-                .find(|doc| doc.function_name == route.function_name)
-                .map_or(false, |doc| {
-                    // Check if this endpoint requires auth (has the special marker)
-                    doc.parameters.contains("__REQUIRES_AUTH__")
-                })
+    /// Add the paths, components and tags of `other` to `spec`, keeping what
+    /// `spec` already defines unless `other_wins`
+    fn merge_into_spec(spec: &mut openapi::OpenAPI, other: openapi::OpenAPI, other_wins: bool) {
+        fn merge_map<T>(base: &mut BTreeMap<String, T>, other: BTreeMap<String, T>, other_wins: bool) {
+            for (name, value) in other {
+                if other_wins || !base.contains_key(&name) {
+                    base.insert(name, value);
+                }
+            }
+        }
+
+        for (path, other_item) in other.paths {
+            let item = spec.paths.entry(path).or_default();
+            let slots = [
+                (&mut item.get, other_item.get),
+                (&mut item.post, other_item.post),
+                (&mut item.put, other_item.put),
+                (&mut item.patch, other_item.patch),
+                (&mut item.delete, other_item.delete),
+                (&mut item.head, other_item.head),
+                (&mut item.options, other_item.options),
+                (&mut item.trace, other_item.trace),
+            ];
+            for (slot, operation) in slots {
+                if operation.is_some() && (other_wins || slot.is_none()) {
+                    *slot = operation;
+                }
+            }
+        }
+
+        if let Some(other_components) = other.components {
+            let components = spec.components.get_or_insert_with(|| openapi::Components {
+                schemas: BTreeMap::new(),
+                parameters: None,
+                responses: None,
+                security_schemes: None,
+            });
+            merge_map(&mut components.schemas, other_components.schemas, other_wins);
+            if let Some(parameters) = other_components.parameters {
+                merge_map(components.parameters.get_or_insert_with(BTreeMap::new), parameters, other_wins);
+            }
+            if let Some(responses) = other_components.responses {
+                merge_map(components.responses.get_or_insert_with(BTreeMap::new), responses, other_wins);
+            }
+            if let Some(schemes) = other_components.security_schemes {
+                merge_map(components.security_schemes.get_or_insert_with(BTreeMap::new), schemes, other_wins);
+            }
+        }
+
+        for tag in other.tags.into_iter().flatten() {
+            let tags = spec.tags.get_or_insert_with(Vec::new);
+            match tags.iter_mut().find(|existing| existing.name == tag.name) {
+                Some(existing) if other_wins => *existing = tag,
+                Some(_) => {}
+                None => tags.push(tag),
+            }
+        }
+    }
+
+    /// Replace inline object schemas that occur more than once with a `$ref`
+    /// to a single copy in `components.schemas`
+    fn hoist_repeated_inline_schemas(spec: &mut openapi::OpenAPI) {
+        // Structurally identical schemas serialize identically, since properties are BTreeMaps
+        let mut occurrences: BTreeMap<String, (usize, String)> = BTreeMap::new();
+        for (name, schema) in Self::inline_media_schemas(&mut spec.paths) {
+            let key = serde_json::to_string(schema).unwrap_or_default();
+            occurrences.entry(key).or_insert((0, name)).0 += 1;
+        }
+
+        let components = spec.components.get_or_insert_with(|| openapi::Components {
+            schemas: BTreeMap::new(),
+            parameters: None,
+            responses: None,
+            security_schemes: None,
         });
+        let mut hoisted = BTreeMap::new();
+        for (key, (count, name)) in occurrences {
+            if count < 2 {
+                continue;
+            }
+            let name = (1..)
+                .map(|n| if n == 1 { name.clone() } else { format!("{name}{n}") })
+                .find(|candidate| !components.schemas.contains_key(candidate))
+                .unwrap_or(name);
+            if let Ok(schema) = serde_json::from_str(&key) {
+                components.schemas.insert(name.clone(), schema);
+                hoisted.insert(key, name);
+            }
+        }
+
+        for (_, schema) in Self::inline_media_schemas(&mut spec.paths) {
+            let key = serde_json::to_string(schema).unwrap_or_default();
+            if let Some(name) = hoisted.get(&key) {
+                *schema = Self::schema_ref(name);
+            }
+        }
+    }
+
+    /// Inline object schemas of every request and response body, each with the
+    /// component name it would be hoisted under
+    fn inline_media_schemas(
+        paths: &mut BTreeMap<String, openapi::PathItem>,
+    ) -> Vec<(String, &mut openapi::ReferenceOr<openapi::Schema>)> {
+        let is_inline_object = |schema: &openapi::ReferenceOr<openapi::Schema>| {
+            matches!(schema, openapi::ReferenceOr::Item(item)
+                if item.reference.is_none() && item.properties.as_ref().is_some_and(|properties| !properties.is_empty()))
+        };
+
+        let mut schemas = Vec::new();
+        for operation in paths.values_mut().flat_map(openapi::PathItem::operations_mut) {
+            let base = operation.operation_id.as_deref().map_or_else(|| "Inline".to_string(), Self::pascal_case);
+            let request_media = operation.request_body.iter_mut().flat_map(|body| body.content.values_mut());
+            for media in request_media {
+                schemas.extend(media.schema.as_mut().filter(|schema| is_inline_object(schema)).map(|schema| (format!("{base}Request"), schema)));
+            }
+            let response_media = operation.responses.values_mut().flat_map(|response| match response {
+                openapi::ReferenceOr::Item(response) => response.content.iter_mut().flat_map(|content| content.values_mut()).collect(),
+                openapi::ReferenceOr::Reference { .. } => Vec::new(),
+            });
+            for media in response_media {
+                schemas.extend(media.schema.as_mut().filter(|schema| is_inline_object(schema)).map(|schema| (format!("{base}Response"), schema)));
+            }
+        }
+        schemas
+    }
+
+    /// "create_user" or "users::create_user" as "CreateUser"
+    fn pascal_case(operation_id: &str) -> String {
+        operation_id
+            .rsplit("::")
+            .next()
+            .unwrap_or(operation_id)
+            .split('_')
+            .filter_map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+            })
+            .collect()
+    }
+
+    /// Check the routes and their documentation for problems in the generated spec
+    ///
+    /// Reports an empty title or version, duplicate operationIds, routes
+    /// registered twice, `$ref`s to unregistered schemas and handler tags that
+    /// were never declared. Nothing is printed; the caller decides what to do
+    /// with the warnings.
+    pub fn validate(&self) -> Vec<SpecWarning> {
+        let handler_docs = self.collect_handler_docs();
+        let mut warnings = Vec::new();
+
+        let info = &self.openapi.info;
+        for (field, value) in [("title", &info.title), ("version", &info.version)] {
+            if value.trim().is_empty() {
+                warnings.push(SpecWarning::EmptyInfoField { field: field.to_string() });
+            }
+        }
+
+        let mut operations: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        let mut routes_seen = std::collections::BTreeSet::new();
+        let visible_routes: Vec<&RouteInfo> = self.routes.iter().filter(|route| !self.is_hidden(route, &handler_docs)).collect();
+        for route in &visible_routes {
+            if !routes_seen.insert((route.method.as_str(), route.path.as_str())) {
+                warnings.push(SpecWarning::DuplicateRoute {
+                    method: route.method.clone(),
+                    path: route.path.clone(),
+                });
+            }
+            if handler_docs.contains_key(self.handler_key(route)) {
+                operations.entry(self.handler_key(route)).or_default().push(format!("{} {}", route.method, route.path));
+            }
+        }
+        // Registrations skipped at `route()` time for a method and path already taken
+        for (method, path) in &self.duplicate_routes {
+            let warning = SpecWarning::DuplicateRoute { method: method.clone(), path: path.clone() };
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+        for (operation_id, routes) in operations {
+            if routes.len() > 1 {
+                warnings.push(SpecWarning::DuplicateOperationId {
+                    operation_id: operation_id.to_string(),
+                    routes,
+                });
+            }
+        }
+
+        // Schema usage is tracked in a local set so validating leaves the router's untouched
+        let mut used_schemas = std::collections::HashSet::new();
+        let registered_schemas = Self::registered_schema_names();
+        let declared_tags: std::collections::BTreeSet<&str> = self.openapi.tags.iter().map(|tag| tag.name.as_str()).collect();
+        let mut undeclared = std::collections::BTreeSet::new();
+        for route in &visible_routes {
+            let Some(doc) = self.route_doc(route, &handler_docs) else {
+                continue;
+            };
+            Self::collect_schemas_for_handler(&mut used_schemas, &registered_schemas, &doc);
+
+            for tag in self.handler_tags(&doc) {
+                if !declared_tags.contains(tag.as_str()) && undeclared.insert((tag.clone(), self.handler_key(route))) {
+                    warnings.push(SpecWarning::UndeclaredTag {
+                        tag,
+                        operation: self.handler_key(route).to_string(),
+                    });
+                }
+            }
+        }
+        for (schema, referenced_by) in Self::walk_schema_references(&mut used_schemas, &self.schema_reference_graph()) {
+            warnings.push(SpecWarning::UnresolvedSchemaRef { schema, referenced_by });
+        }
+
+        warnings
+    }
+
+    /// Collect all registered handler documentation keyed the same way as `handler_key`
+    fn collect_handler_docs(&self) -> HashMap<String, &'static HandlerDocumentation> {
+        inventory::iter::<HandlerDocumentation>()
+            .map(|doc| {
+                let key = if self.full_handler_paths {
+                    format!("{}::{}", doc.module_path, doc.function_name)
+                } else {
+                    doc.function_name.to_string()
+                };
+                (key, doc)
+            })
+            .collect()
+    }
+
+    /// Key used to look up a route's documentation, which also becomes its operationId
+    fn handler_key<'a>(&self, route: &'a RouteInfo) -> &'a str {
+        if self.full_handler_paths {
+            &route.handler_path
+        } else {
+            &route.function_name
+        }
+    }
+
+    /// A route's documentation: what `route_documented()` attached, else its handler's registration
+    fn route_doc<'a>(
+        &self,
+        route: &'a RouteInfo,
+        handler_docs: &HashMap<String, &'static HandlerDocumentation>,
+    ) -> Option<HandlerDocumentation<&'a str>> {
+        match &route.doc {
+            Some(doc) => Some(doc.as_str()),
+            None => handler_docs.get(self.handler_key(route)).map(|doc| **doc),
+        }
+    }
+
+    /// Record the request body and response schemas referenced by a handler
+    fn collect_schemas_for_handler(
+        used_schemas: &mut std::collections::HashSet<String>,
+        registered_schemas: &std::collections::BTreeSet<String>,
+        doc: &HandlerDocumentation<&str>,
+    ) {
+        if !doc.request_body.is_empty() && doc.request_body != "[]" {
+            let _ = Self::build_request_body(used_schemas, registered_schemas, doc.request_body);
+        }
+        if !doc.responses.is_empty() && doc.responses != "[]" {
+            let _ = Self::build_responses(used_schemas, registered_schemas, doc.responses);
+        }
+        if !doc.callbacks.is_empty() && doc.callbacks != "[]" {
+            let callbacks = serde_json::to_value(Self::parse_callbacks(doc.callbacks, doc.function_name)).unwrap_or_default();
+            let mut refs = Vec::new();
+            Self::collect_schema_references(&callbacks, &mut refs);
+            used_schemas.extend(refs);
+        }
+    }
+
+    fn build_info(&self) -> openapi::Info {
+        let info = &self.openapi.info;
+        openapi::Info {
+            title: info.title.clone(),
+            version: info.version.clone(),
+            description: info.description.clone(),
+            terms_of_service: info.terms_of_service.clone(),
+            contact: info.contact.as_ref().map(|contact| openapi::Contact {
+                name: contact.name.clone(),
+                url: contact.url.clone(),
+                email: contact.email.clone(),
+            }),
+            license: info.license.as_ref().map(|license| openapi::License {
+                name: license.name.clone(),
+                url: license.url.clone(),
+            }),
+            extensions: info.extensions.clone(),
+        }
+    }
+
+    fn build_tags(&self) -> Vec<openapi::Tag> {
+        self.openapi.tags.iter()
+            .map(|tag| openapi::Tag {
+                name: tag.name.clone(),
+                description: tag.description.clone(),
+                external_docs: tag.external_docs.as_ref().map(|docs| openapi::ExternalDocs {
+                    description: docs.description.clone(),
+                    url: docs.url.clone(),
+                }),
+            })
+            .collect()
+    }
+
+    /// Whether a route is left out of the spec, via `#[api_handler(skip)]` or `exclude_path()`
+    fn is_hidden(&self, route: &RouteInfo, handler_docs: &HashMap<String, &'static HandlerDocumentation>) -> bool {
+        let skipped = self.route_doc(route, handler_docs).is_some_and(|doc| doc.hidden);
+        let openapi_path = self.convert_path_to_openapi(&route.path);
+        skipped || self.excluded_paths.iter().any(|pattern| {
+            glob_matches(pattern, &route.path) || glob_matches(pattern, &openapi_path)
+        })
+    }
+
+    /// Whether a visible route belongs in a spec limited to `tags`; `None` keeps every one
+    fn in_spec(&self, route: &RouteInfo, handler_docs: &HashMap<String, &'static HandlerDocumentation>, tags: Option<&[&str]>) -> bool {
+        !self.is_hidden(route, handler_docs)
+            && tags.is_none_or(|tags| {
+                self.route_doc(route, handler_docs)
+                    .is_some_and(|doc| self.handler_tags(&doc).iter().any(|tag| tags.contains(&tag.as_str())))
+            })
+    }
+
+    /// Included routes grouped by path, in path order so the spec comes out the same every run
+    fn group_routes_by_path(
+        &self,
+        handler_docs: &HashMap<String, &'static HandlerDocumentation>,
+        tags: Option<&[&str]>,
+    ) -> BTreeMap<String, Vec<&RouteInfo>> {
+        let mut path_methods: BTreeMap<String, Vec<&RouteInfo>> = BTreeMap::new();
+        for route in self.routes.iter().filter(|route| self.in_spec(route, handler_docs, tags)) {
+            path_methods.entry(route.path.clone()).or_default().push(route);
+        }
+        path_methods
+    }
+
+    fn build_paths(
+        &self,
+        handler_docs: &HashMap<String, &'static HandlerDocumentation>,
+        registered_schemas: &std::collections::BTreeSet<String>,
+        tags: Option<&[&str]>,
+    ) -> BTreeMap<String, openapi::PathItem> {
+        self.group_routes_by_path(handler_docs, tags)
+            .into_iter()
+            .map(|(path, routes)| {
+                // Convert Axum path format (:param) to OpenAPI format ({param})
+                let openapi_path = self.convert_path_to_openapi(&path);
+                (openapi_path, self.build_path(&routes, handler_docs, registered_schemas))
+            })
+            .collect()
+    }
+
+    fn build_path(
+        &self,
+        routes: &[&RouteInfo],
+        handler_docs: &HashMap<String, &'static HandlerDocumentation>,
+        registered_schemas: &std::collections::BTreeSet<String>,
+    ) -> openapi::PathItem {
+        let mut path_item = openapi::PathItem::default();
+
+        for route in routes {
+            let doc = self.route_doc(route, handler_docs);
+            let operation = Some(self.build_method(route, doc.as_ref(), registered_schemas));
+
+            match route.method.as_str() {
+                "GET" => path_item.get = operation,
+                "POST" => path_item.post = operation,
+                "PUT" => path_item.put = operation,
+                "PATCH" => path_item.patch = operation,
+                "DELETE" => path_item.delete = operation,
+                "HEAD" => path_item.head = operation,
+                "OPTIONS" => path_item.options = operation,
+                "TRACE" => path_item.trace = operation,
+                other => eprintln!("Warning: Unsupported HTTP method {} for {}", other, route.path),
+            }
+        }
+
+        path_item
+    }
+
+    fn build_method(
+        &self,
+        route: &RouteInfo,
+        doc: Option<&HandlerDocumentation<&str>>,
+        registered_schemas: &std::collections::BTreeSet<String>,
+    ) -> openapi::Operation {
+        let (summary, description) = if let Some(doc) = doc {
+            (doc.summary.to_string(), doc.description.to_string())
+        } else {
+            (
+                route.summary.clone().unwrap_or_else(|| format!("{} {}", route.method, route.path)),
+                "No description available".to_string()
+            )
+        };
+
+        let mut operation = openapi::Operation {
+            summary: Some(summary).filter(|summary| !summary.is_empty()),
+            description: Some(description).filter(|description| !description.is_empty()),
+            external_docs: doc.and_then(|doc| Self::parse_external_docs(doc.external_docs)),
+            operation_id: doc.map(|_| self.handler_key(route).to_string()),
+            handler_function: None,
+            tags: Vec::new(),
+            parameters: Vec::new(),
+            request_body: None,
+            responses: Self::default_responses().into_iter().map(|(code, response)| (code, openapi::ReferenceOr::Item(response))).collect(),
+            callbacks: None,
+            security: None,
+            deprecated: doc.is_some_and(|doc| doc.deprecated),
+            extensions: BTreeMap::new(),
+        };
+
+        let Some(doc) = doc else {
+            return operation;
+        };
+
+        // Vendor extensions from the "# Extensions" doc section
+        if !doc.extensions.is_empty() && doc.extensions != "[]" {
+            operation.extensions = Self::parse_extensions(doc.extensions, doc.function_name);
+        }
 
-        if !used_components_schemas.is_empty() || has_auth_endpoints {
-            json.push_str(r#","components":{"#);
+        // Add tags if present
+        operation.tags = self.handler_tags(doc);
 
-            let mut components_parts = Vec::new();
+        // Add parameters in proper OpenAPI format, then any shared ones the handler refers to
+        if !doc.parameters.is_empty() && doc.parameters != "[]" {
+            let mut parameters = self.parse_parameters_to_openapi(doc.parameters);
+            Self::apply_path_parameters(&mut parameters, &route.path, doc.parameters);
+            operation.parameters = parameters.into_iter().map(openapi::ReferenceOr::Item).collect();
+            operation.parameters.extend(self.shared_parameter_refs(doc));
+        }
 
-            // Add schemas section if we have schemas
-            if !used_components_schemas.is_empty() {
-                let schema_entries: Vec<String> = used_components_schemas.iter()
-                    .map(|(name, schema)| format!(r#""{name}": {schema}"#))
-                    .collect();
-                components_parts.push(format!(r#""schemas":{{{}}}"#, schema_entries.join(",")));
+        // Add security requirements that differ from the document-wide default
+        operation.security = self.operation_security(doc);
+
+        // Schema usage was already tracked in the first pass, so what these record is discarded
+        let mut referenced_schemas = std::collections::HashSet::new();
+
+        // Add request body in proper OpenAPI format
+        if !doc.request_body.is_empty() && doc.request_body != "[]" {
+            operation.request_body = Some(Self::build_request_body(&mut referenced_schemas, registered_schemas, doc.request_body));
+            // A body on GET, HEAD or DELETE has no defined meaning, so clients can't be
+            // expected to send one unless the handler says otherwise
+            let declared_required = doc.request_body.contains("\"Required: true\"");
+            if let Some(body) = operation.request_body.as_mut().filter(|_| !declared_required) {
+                if matches!(route.method.as_str(), "GET" | "HEAD" | "DELETE") {
+                    body.required = false;
+                }
             }
+        }
+
+        // Add responses in proper OpenAPI format
+        if !doc.responses.is_empty() && doc.responses != "[]" {
+            let responses = Self::build_responses(&mut referenced_schemas, registered_schemas, doc.responses);
+            operation.responses = responses.into_iter().map(|(code, response)| (code, openapi::ReferenceOr::Item(response))).collect();
+            operation.responses.extend(self.shared_response_refs(doc));
+        }
+
+        // Callbacks from `#[callback]` attributes
+        if !doc.callbacks.is_empty() && doc.callbacks != "[]" {
+            operation.callbacks = Some(Self::parse_callbacks(doc.callbacks, doc.function_name)).filter(|callbacks| !callbacks.is_empty());
+        }
+
+        operation
+    }
+
+    /// `$ref`s for response entries written as "401: $ref Unauthorized"
+    fn shared_response_refs(&self, doc: &HandlerDocumentation<&str>) -> Vec<(String, openapi::ReferenceOr<openapi::Response>)> {
+        let response_strings: Vec<String> = serde_json::from_str(doc.responses).unwrap_or_default();
+        response_strings
+            .iter()
+            .filter_map(|entry| {
+                let (code, description) = entry.split_once(':')?;
+                let name = description.trim().strip_prefix("$ref ")?.trim();
+                if !self.shared_responses.contains_key(name) {
+                    eprintln!("Warning: {} refers to shared response '{name}', which was never registered with shared_response()", doc.function_name);
+                    return None;
+                }
+                Some((code.trim().to_string(), openapi::ReferenceOr::new_ref(format!("#/components/responses/{name}"))))
+            })
+            .collect()
+    }
+
+    /// Whether `key` can key a responses map: a three-digit status code, a
+    /// status class such as `4XX`, or `default`
+    fn is_response_key(key: &str) -> bool {
+        let is_class = matches!(key.as_bytes(), [b'1'..=b'5', b'X', b'X']);
+        key == "default" || is_class || (key.len() == 3 && key.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    /// Standard reason phrase for a status code, e.g. "Not Found" for `404`,
+    /// or "Response" for keys without one
+    fn reason_phrase(code: &str) -> &'static str {
+        code.parse::<u16>().ok()
+            .and_then(|code| http::StatusCode::from_u16(code).ok())
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Response")
+    }
+
+    /// A security requirement needing one scheme, with no scopes
+    fn security_requirement(scheme: &str) -> Vec<BTreeMap<String, Vec<String>>> {
+        vec![BTreeMap::from([(scheme.to_string(), Vec::new())])]
+    }
+
+    /// Schemes a handler declares itself: its `# Security` section or
+    /// `#[api_handler(public)]`, else `sessionAuth` for an `Authorized` extractor
+    fn handler_security(doc: &HandlerDocumentation<&str>) -> Option<Vec<BTreeMap<String, Vec<String>>>> {
+        if !doc.security.is_empty() {
+            let schemes: Vec<String> = serde_json::from_str(doc.security).unwrap_or_default();
+            return Some(schemes.iter().flat_map(|scheme| Self::security_requirement(scheme)).collect());
+        }
+        doc.parameters.contains("__REQUIRES_AUTH__").then(|| Self::security_requirement("sessionAuth"))
+    }
+
+    /// The operation's `security`, left out when the document-wide default already covers it
+    fn operation_security(&self, doc: &HandlerDocumentation<&str>) -> Option<Vec<BTreeMap<String, Vec<String>>>> {
+        let security = Self::handler_security(doc)?;
+        let default = self.default_security.as_deref().map(Self::security_requirement);
+        match default {
+            Some(default) if default == security => None,
+            // Without a default, an empty requirement says nothing
+            None if security.is_empty() => None,
+            _ => Some(security),
+        }
+    }
+
+    fn default_responses() -> BTreeMap<String, openapi::Response> {
+        BTreeMap::from([(
+            "200".to_string(),
+            openapi::Response {
+                description: "Successful response".to_string(),
+                headers: None,
+                links: None,
+                content: None,
+            },
+        )])
+    }
 
-            // Add securitySchemes section if we have auth endpoints
-            if has_auth_endpoints {
-                let security_schemes = r#""securitySchemes":{"sessionAuth":{"type":"apiKey","in":"header","name":"x-session-secret","description":"API session token for authentication"}}"#;
-                components_parts.push(security_schemes.to_string());
+    /// Deserialize the schema JSON a type registered through `#[derive(OpenApiSchema)]`
+    fn parse_registered_schema<T: ::serde::de::DeserializeOwned>(schema_json: &str, type_name: &str) -> Option<T> {
+        match serde_json::from_str(schema_json) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("Warning: Failed to parse schema for {type_name}: {e}");
+                None
             }
+        }
+    }
+
+    /// Registered schemas that are referenced by at least one route
+    fn filter_used_schemas(&self) -> BTreeMap<String, &'static str> {
+        inventory::iter::<SchemaRegistration>()
+            .filter(|reg| self.used_schemas.contains(reg.type_name))
+            .map(|reg| (reg.type_name.to_string(), reg.schema_json))
+            .collect()
+    }
 
-            json.push_str(&components_parts.join(","));
-            json.push('}');
+    fn build_components(
+        &self,
+        handler_docs: &HashMap<String, &'static HandlerDocumentation>,
+        tags: Option<&[&str]>,
+    ) -> Option<openapi::Components> {
+        let schemas: BTreeMap<String, openapi::ReferenceOr<openapi::Schema>> = self.filter_used_schemas()
+            .into_iter()
+            .map(|(name, schema_json)| {
+                let schema = Self::parse_registered_schema(schema_json, &name)
+                    .unwrap_or_else(|| openapi::ReferenceOr::new_item(openapi::Schema::default()));
+                (name, schema)
+            })
+            .collect();
+
+        // Check if any endpoint uses session authentication (has Authorized parameter)
+        let has_auth_endpoints = self.default_security.as_deref() == Some("sessionAuth")
+            || self.routes.iter().filter(|route| self.in_spec(route, handler_docs, tags)).any(|route| {
+                handler_docs
+                    .get(self.handler_key(route))
+                    .and_then(|doc| Self::handler_security(doc))
+                    .is_some_and(|security| security.iter().any(|requirement| requirement.contains_key("sessionAuth")))
+            });
+
+        if schemas.is_empty() && !has_auth_endpoints && self.shared_parameters.is_empty() && self.shared_responses.is_empty() {
+            return None;
         }
 
-        json.push('}');
-        json
+        let security_schemes = has_auth_endpoints.then(|| {
+            BTreeMap::from([(
+                "sessionAuth".to_string(),
+                openapi::SecurityScheme::api_key("x-session-secret", "header")
+                    .with_description("API session token for authentication"),
+            )])
+        });
+
+        let parameters = (!self.shared_parameters.is_empty()).then(|| {
+            self.shared_parameters
+                .iter()
+                .map(|(name, parameter)| (name.clone(), openapi::ReferenceOr::new_item(parameter.clone())))
+                .collect()
+        });
+
+        let responses = (!self.shared_responses.is_empty()).then(|| {
+            self.shared_responses
+                .iter()
+                .map(|(name, response)| (name.clone(), openapi::ReferenceOr::new_item(response.clone())))
+                .collect()
+        });
+
+        Some(openapi::Components {
+            schemas,
+            parameters,
+            responses,
+            security_schemes,
+        })
+    }
+
+    /// Names of every schema registered with `#[derive(OpenApiSchema)]` or `#[api_error]`, sorted
+    pub fn registered_schemas(&self) -> Vec<String> {
+        Self::registered_schema_names().into_iter().collect()
+    }
+
+    /// Names of the registered schemas the spec refers to, directly or transitively, sorted
+    ///
+    /// Analyzes the endpoints the same way `get_unused_schemas()` does, so the two
+    /// together always add up to `registered_schemas()`.
+    pub fn used_schemas(&mut self) -> Vec<String> {
+        // Cached, so this only rebuilds after the router changed
+        self.build_spec();
+
+        self.registered_schemas()
+            .into_iter()
+            .filter(|schema_name| self.used_schemas.contains(schema_name))
+            .collect()
     }
 
     /// Get a list of unused schemas (schemas that are registered but not referenced in any endpoint)
     pub fn get_unused_schemas(&mut self) -> Vec<String> {
-        // If used_schemas is empty, we need to populate it by analyzing the endpoints
-        if self.used_schemas.is_empty() {
-            // Generate OpenAPI spec to populate used_schemas (but don't use the result)
-            let _ = self.openapi_json();
-        }
+        // Build (or reuse) the spec so used_schemas matches the current routes
+        self.build_spec();
 
         let mut unused_schemas = Vec::new();
         for schema_reg in inventory::iter::<SchemaRegistration>() {
@@ -719,53 +1997,81 @@ where
     }
 
     /// Recursively collect all schemas that are transitively referenced by the current used_schemas
-    fn collect_transitive_schema_dependencies(&mut self) {
-        let mut found_new_dependencies = true;
-
-        while found_new_dependencies {
-            found_new_dependencies = false;
-            let current_used: Vec<String> = self.used_schemas.iter().cloned().collect();
-
-            for schema_name in &current_used {
-                // Find the schema registration for this schema
-                if let Some(schema_reg) = inventory::iter::<SchemaRegistration>()
-                    .find(|reg| reg.type_name == schema_name) {
-
-                    let schema_json = schema_reg.schema_json;
-
-                    // Find all $ref references in this schema JSON
-                    let refs = self.extract_schema_references(schema_json);
-                    for ref_schema in refs {
-                        if !self.used_schemas.contains(&ref_schema) {
-                            // Check if this referenced schema actually exists
-                            if inventory::iter::<SchemaRegistration>()
-                                .any(|reg| reg.type_name == ref_schema) {
-                                self.used_schemas.insert(ref_schema);
-                                found_new_dependencies = true;
-                            }
-                        }
-                    }
+    ///
+    /// Returns the referenced schema names that aren't registered, each mapped to
+    /// the first schema found referencing it.
+    fn collect_transitive_schema_dependencies(&mut self) -> BTreeMap<String, String> {
+        let graph = self.schema_reference_graph();
+        Self::walk_schema_references(&mut self.used_schemas, &graph)
+    }
+
+    /// Every registered schema mapped to the schema names its JSON refers to
+    fn schema_reference_graph(&self) -> HashMap<&'static str, Vec<String>> {
+        inventory::iter::<SchemaRegistration>()
+            .map(|reg| (reg.type_name, self.extract_schema_references(reg.schema_json)))
+            .collect()
+    }
+
+    /// Add every schema reachable from `used_schemas` through `graph`
+    ///
+    /// Each schema is expanded once, so the walk is linear in the number of
+    /// references and self-referential or mutually recursive schemas end it
+    /// like any other. References to names missing from `graph` are returned
+    /// instead, each mapped to the first schema found referencing it.
+    fn walk_schema_references(
+        used_schemas: &mut std::collections::HashSet<String>,
+        graph: &HashMap<&'static str, Vec<String>>,
+    ) -> BTreeMap<String, String> {
+        let mut pending: std::collections::VecDeque<String> = used_schemas.iter().cloned().collect();
+        let mut unresolved = BTreeMap::new();
+
+        while let Some(schema_name) = pending.pop_front() {
+            let Some(references) = graph.get(schema_name.as_str()) else {
+                continue;
+            };
+            for reference in references {
+                if !graph.contains_key(reference.as_str()) {
+                    unresolved.entry(reference.clone()).or_insert_with(|| schema_name.clone());
+                } else if used_schemas.insert(reference.clone()) {
+                    pending.push_back(reference.clone());
                 }
             }
         }
+
+        unresolved
     }
 
     /// Extract all schema names referenced via $ref from a JSON schema string
+    ///
+    /// The schema is parsed and walked, so formatting and nesting depth don't matter.
     fn extract_schema_references(&self, schema_json: &str) -> Vec<String> {
         let mut refs = Vec::new();
-        let ref_pattern = "\"$ref\":\"#/components/schemas/";
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(schema_json) {
+            Self::collect_schema_references(&value, &mut refs);
+        }
+        refs
+    }
 
-        let mut start_pos = 0;
-        while let Some(ref_start) = schema_json[start_pos..].find(ref_pattern) {
-            let absolute_start = start_pos + ref_start + ref_pattern.len();
-            if let Some(ref_end) = schema_json[absolute_start..].find('"') {
-                let schema_name = &schema_json[absolute_start..absolute_start + ref_end];
-                refs.push(schema_name.to_string());
+    fn collect_schema_references(value: &serde_json::Value, refs: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, item) in map {
+                    let schema_name = item
+                        .as_str()
+                        .and_then(|reference| reference.strip_prefix("#/components/schemas/"));
+                    match schema_name {
+                        Some(name) if key == "$ref" => refs.push(name.to_string()),
+                        _ => Self::collect_schema_references(item, refs),
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    Self::collect_schema_references(item, refs);
+                }
             }
-            start_pos = absolute_start;
+            _ => {}
         }
-
-        refs
     }
 
     /// Get unused schemas without triggering OpenAPI generation (for testing)
@@ -793,156 +2099,576 @@ where
         }
     }
 
-    fn parse_parameters_to_openapi(&self, params_str: &str) -> String {
+    fn parse_parameters_to_openapi(&self, params_str: &str) -> Vec<openapi::Parameter> {
         // Parse parameter strings like ["id (path): The unique identifier..."]
         // into proper OpenAPI parameter objects
         if params_str == "[]" || params_str.is_empty() {
-            return "[]".to_string();
+            return Vec::new();
         }
 
-        // Use proper JSON parsing instead of string manipulation
-        let param_strings: Result<Vec<String>, _> = serde_json::from_str(params_str);
+        // Each parameter is one element of the JSON string array the macro emits
+        let param_strings: Vec<String> = match serde_json::from_str(params_str) {
+            Ok(strings) => strings,
+            Err(err) => {
+                eprintln!("Warning: could not parse parameters {params_str}: {err}");
+                return Vec::new();
+            }
+        };
 
-        let params: Vec<String> = match param_strings {
-            Ok(strings) => {
-                strings.into_iter().filter_map(|param| {
-                    // Filter out the special auth marker
-                    if param == "__REQUIRES_AUTH__" {
-                        return None;
-                    }
+        // Query<T> extractors are expanded after the documented parameters
+        let mut query_types = Vec::new();
+        let mut params: Vec<openapi::Parameter> = param_strings.into_iter().filter_map(|param| {
+            // Filter out the special auth marker
+            if param == "__REQUIRES_AUTH__" {
+                return None;
+            }
+            if let Some(query_type) = param.strip_prefix("QueryType: ") {
+                query_types.push(query_type.trim().to_string());
+                return None;
+            }
+            // Path<T> schemas are matched against the route in build_method,
+            // and shared parameters are referenced there too
+            if param.starts_with("PathSchema: ") || param.starts_with("ParameterRef: ") {
+                return None;
+            }
 
-                    Some(param)
-                }).map(|param| {
-                    if let Some(colon_pos) = param.find(':') {
-                        let left = param[..colon_pos].trim();
-                        let description = param[colon_pos + 1..].trim();
-
-                        // Parse "name (in)" format
-                        if let Some(paren_start) = left.find('(') {
-                            if let Some(paren_end) = left.find(')') {
-                                let name = left[..paren_start].trim();
-                                let param_in = left[paren_start + 1..paren_end].trim();
-
-                                // Parse description for examples and defaults
-                                // Format: "Description [example: value, default: value]"
-                                let (clean_description, example, default) = Self::parse_description_with_metadata(description);
-
-                                let mut param_obj = format!(
-                                    r#"{{"name": "{}", "in": "{}", "description": "{}", "required": {}, "schema": {{"type": "string"}}"#,
-                                    name,
-                                    param_in,
-                                    clean_description.replace("\"", "\\\""),
-                                    if param_in == "path" { "true" } else { "false" }
-                                );
-
-                                // Add example to schema if present
-                                if let Some(example_value) = example {
-                                    param_obj = param_obj.replace(
-                                        r#""schema": {"type": "string"}"#,
-                                        &format!(r#""schema": {{"type": "string", "example": "{}"}}"#, example_value.replace("\"", "\\\""))
-                                    );
-                                }
-
-                                // Add default to schema if present (only for query/header params)
-                                if let Some(default_value) = default {
-                                    if param_in != "path" {
-                                        param_obj = param_obj.replace(
-                                            r#""type": "string""#,
-                                            &format!(r#""type": "string", "default": "{}""#, default_value.replace("\"", "\\\""))
-                                        );
-                                    }
-                                }
-
-                                param_obj.push('}');
-                                return param_obj;
-                            }
+            Some(param)
+        }).map(|param| {
+            if let Some(colon_pos) = param.find(':') {
+                let left = param[..colon_pos].trim();
+                let description = param[colon_pos + 1..].trim();
+
+                // Parse "name (in)" format
+                if let Some(paren_start) = left.find('(') {
+                    if let Some(paren_end) = left.find(')') {
+                        let name = left[..paren_start].trim();
+                        let mut param_in = left[paren_start + 1..paren_end].trim().to_ascii_lowercase();
+                        if !PARAMETER_LOCATIONS.contains(&param_in.as_str()) {
+                            eprintln!(
+                                "Warning: parameter '{name}' has unsupported location '{param_in}'; expected one of {}. Documenting it as a query parameter",
+                                PARAMETER_LOCATIONS.join(", ")
+                            );
+                            param_in = "query".to_string();
                         }
-                    }
 
-                    // Fallback for malformed parameter
-                    format!(r#"{{"name": "unknown", "in": "query", "description": "{}", "schema": {{"type": "string"}}}}"#,
-                           param.replace("\"", "\\\""))
-                }).collect()
-            },
-            Err(_) => {
-                // Fallback to old parsing method if JSON parsing fails
-                params_str
-                    .trim_start_matches('[')
-                    .trim_end_matches(']')
-                    .split("\", \"")
-                    .map(|param| {
-                        let param = param.trim_matches('"');
-                        format!(r#"{{"name": "unknown", "in": "query", "description": "{}", "schema": {{"type": "string"}}}}"#,
-                               param.replace("\"", "\\\""))
-                    })
-                    .collect()
-            }
-        };
+                        // Parse description for examples, defaults, serialization style and deprecation
+                        // Format: "Description [example: value, default: value, style: form, deprecated]"
+                        let metadata = Self::parse_description_with_metadata(description);
 
-        format!("[{}]", params.join(","))
-    }
+                        let mut schema = openapi::Schema {
+                            schema_type: Some("string".to_string()),
+                            ..Default::default()
+                        };
 
-    fn convert_path_to_openapi(&self, axum_path: &str) -> String {
-        // Convert Axum path format (:param) to OpenAPI format ({param})
-        axum_path.split('/').map(|segment| {
-            if let Some(stripped) = segment.strip_prefix(':') {
-                format!("{{{stripped}}}")
-            } else {
-                segment.to_string()
-            }
-        }).collect::<Vec<_>>().join("/")
-    }
+                        // Add example to both the schema and the parameter if present
+                        let example = metadata.example.map(serde_json::Value::String);
+                        schema.example = example.clone();
 
-    fn parse_request_body_to_openapi(&mut self, request_body_str: &str) -> String {
-        if request_body_str == "[]" || request_body_str.is_empty() {
-            return r#"{"required": true, "content": {"application/json": {"schema": {"type": "object"}}}}"#.to_string();
+                        // Add default to schema if present (only for query/header/cookie params)
+                        if param_in != "path" {
+                            schema.default = metadata.default.map(serde_json::Value::String);
+                        }
+
+                        return openapi::Parameter {
+                            name: name.to_string(),
+                            location: param_in.clone(),
+                            description: Some(metadata.description),
+                            required: param_in == "path",
+                            deprecated: metadata.deprecated,
+                            style: metadata.style,
+                            explode: metadata.explode,
+                            schema: openapi::ReferenceOr::new_item(schema),
+                            example,
+                        };
+                    }
+                }
+            }
+
+            // Fallback for malformed parameter
+            Self::unknown_parameter(&param)
+        }).collect();
+
+        for query_type in query_types {
+            for param in Self::query_parameters_from_schema(&query_type) {
+                // A parameter documented by hand takes precedence over the struct field,
+                // keeping its metadata but taking the field's type
+                match params.iter_mut().find(|existing| existing.name == param.name && existing.location == "query") {
+                    Some(existing) => {
+                        if let (openapi::ReferenceOr::Item(documented), openapi::ReferenceOr::Item(mut typed)) = (&existing.schema, param.schema) {
+                            typed.example = documented.example.clone();
+                            typed.default = documented.default.clone();
+                            existing.schema = openapi::ReferenceOr::new_item(typed);
+                            Self::type_parameter_examples(existing);
+                        }
+                    }
+                    None => params.push(param),
+                }
+            }
         }
 
-        // Check if there's a registered schema type mentioned in the documentation
-        let registered_schemas: std::collections::HashSet<String> = inventory::iter::<SchemaRegistration>()
-            .map(|reg| reg.type_name.to_string())
+        params
+    }
+
+    /// Parse "x-name: value" entries into vendor extensions
+    ///
+    /// Values are read as JSON where possible, so `true` and `{"a": 1}` keep their
+    /// type; anything else becomes a string. Keys without the `x-` prefix are ignored.
+    fn parse_extensions(extensions_str: &str, handler: &str) -> BTreeMap<String, serde_json::Value> {
+        let entries: Vec<String> = serde_json::from_str(extensions_str).unwrap_or_default();
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let (key, value) = entry.split_once(':')?;
+                let key = key.trim();
+                if !key.starts_with("x-") {
+                    eprintln!("Warning: ignoring extension '{key}' on {handler}; extension names must start with 'x-'");
+                    return None;
+                }
+                let value = value.trim();
+                let value = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                Some((key.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Callback objects from a handler's "name METHOD expression: description" entries
+    ///
+    /// Each callback gets a single operation whose body, when a "Schema: name Type"
+    /// entry names a registered schema, refers to it as JSON.
+    fn parse_callbacks(callbacks_str: &str, handler: &str) -> BTreeMap<String, BTreeMap<String, openapi::PathItem>> {
+        let entries: Vec<String> = serde_json::from_str(callbacks_str).unwrap_or_default();
+        let schemas: BTreeMap<&str, &str> = entries
+            .iter()
+            .filter_map(|entry| entry.strip_prefix("Schema: ")?.split_once(' '))
+            .map(|(name, schema)| (name, schema.trim()))
             .collect();
 
-        // Extract request body information from documentation
-        let content: Vec<&str> = request_body_str
-            .trim_start_matches('[')
-            .trim_end_matches(']')
-            .split("\",\"")
-            .map(|s| s.trim_matches('"'))
+        let mut callbacks = BTreeMap::new();
+        for entry in entries.iter().filter(|entry| !entry.starts_with("Schema: ")) {
+            let (line, description) = entry.split_once(": ").unwrap_or((entry, ""));
+            let mut words = line.split_whitespace();
+            let (Some(name), Some(method), Some(expression)) = (words.next(), words.next(), words.next()) else {
+                eprintln!("Warning: ignoring malformed callback '{entry}' on {handler}");
+                continue;
+            };
+
+            let request_body = schemas.get(name).and_then(|&schema| {
+                if !inventory::iter::<SchemaRegistration>().any(|reg| reg.type_name == schema) {
+                    eprintln!("Warning: callback '{name}' on {handler} names schema '{schema}', which is not a registered schema");
+                    return None;
+                }
+                Some(openapi::RequestBody {
+                    description: None,
+                    content: BTreeMap::from([(
+                        "application/json".to_string(),
+                        openapi::MediaType { schema: Some(openapi::ReferenceOr::new_ref(format!("#/components/schemas/{schema}"))) },
+                    )]),
+                    required: true,
+                })
+            });
+            let description = description.trim();
+            let operation = openapi::Operation {
+                summary: None,
+                description: (!description.is_empty()).then(|| description.to_string()),
+                external_docs: None,
+                operation_id: None,
+                handler_function: None,
+                tags: Vec::new(),
+                parameters: Vec::new(),
+                request_body,
+                responses: Self::default_responses().into_iter().map(|(code, response)| (code, openapi::ReferenceOr::Item(response))).collect(),
+                callbacks: None,
+                security: None,
+                deprecated: false,
+                extensions: BTreeMap::new(),
+            };
+
+            let mut path_item = openapi::PathItem::default();
+            let operation = Some(operation);
+            match method {
+                "GET" => path_item.get = operation,
+                "POST" => path_item.post = operation,
+                "PUT" => path_item.put = operation,
+                "PATCH" => path_item.patch = operation,
+                "DELETE" => path_item.delete = operation,
+                "HEAD" => path_item.head = operation,
+                "OPTIONS" => path_item.options = operation,
+                "TRACE" => path_item.trace = operation,
+                other => {
+                    eprintln!("Warning: ignoring callback '{name}' on {handler}; '{other}' is not an HTTP method");
+                    continue;
+                }
+            }
+            callbacks.insert(name.to_string(), BTreeMap::from([(expression.to_string(), path_item)]));
+        }
+        callbacks
+    }
+
+    /// External documentation link from a handler's "URL description" entry
+    fn parse_external_docs(external_docs: &str) -> Option<openapi::ExternalDocs> {
+        let (url, description) = external_docs.trim().split_once(char::is_whitespace).unwrap_or((external_docs.trim(), ""));
+        if url.is_empty() {
+            return None;
+        }
+        let description = description.trim();
+        Some(openapi::ExternalDocs {
+            url: url.to_string(),
+            description: (!description.is_empty()).then(|| description.to_string()),
+        })
+    }
+
+    /// Type the route's path parameters from the handler's `Path<T>` extractor
+    ///
+    /// Tuple extractors are matched to the route's placeholders by position and
+    /// struct extractors by field name, with a field's description moving from
+    /// its schema to the parameter. Documented path parameters keep their
+    /// description and example; undocumented ones are added.
+    fn apply_path_parameters(parameters: &mut Vec<openapi::Parameter>, path: &str, params_str: &str) {
+        let param_strings: Vec<String> = serde_json::from_str(params_str).unwrap_or_default();
+        let schemas: Vec<openapi::ReferenceOr<openapi::Schema>> = param_strings
+            .iter()
+            .filter_map(|param| param.strip_prefix("PathSchema: "))
+            .filter_map(|schema| serde_json::from_str(schema).ok())
             .collect();
+        if schemas.is_empty() {
+            return;
+        }
 
-        // Check for explicit type information first (from our macro enhancement)
-        for line in &content {
-            if let Some(type_name) = line.strip_prefix("Type: ") {
-                // Skip "Type: " prefix
-                if registered_schemas.contains(type_name) {
-                    self.used_schemas.insert(type_name.to_string());
-                    return format!(
-                        "{{\"required\": true, \"description\": \"Request body\", \"content\": {{\"application/json\": {{\"schema\": {{\"$ref\": \"#/components/schemas/{type_name}\"}}}}}}}}"
-                    );
+        // A struct extractor is a single reference to a registered schema
+        let struct_type = match schemas.as_slice() {
+            [schema] => schema.as_ref_str().and_then(|reference| reference.strip_prefix("#/components/schemas/")),
+            _ => None,
+        };
+        let struct_fields = match struct_type {
+            Some(type_name) => {
+                let Some(registration) = inventory::iter::<SchemaRegistration>().find(|reg| reg.type_name == type_name) else {
+                    eprintln!("Warning: path parameters type '{type_name}' is not a registered schema");
+                    return;
+                };
+                Self::parse_registered_schema::<openapi::Schema>(registration.schema_json, type_name)
+                    .and_then(|schema| schema.properties)
+            }
+            None => None,
+        };
+
+        for (index, name) in Self::path_parameter_names(path).into_iter().enumerate() {
+            let schema = match &struct_fields {
+                Some(fields) => fields.get(&name).cloned(),
+                None => schemas.get(index).cloned(),
+            };
+            let Some(mut schema) = schema else {
+                continue;
+            };
+            let field_description = match &mut schema {
+                openapi::ReferenceOr::Item(field) if struct_fields.is_some() => field.description.take(),
+                _ => None,
+            };
+
+            match parameters.iter_mut().find(|param| param.location == "path" && param.name == name) {
+                Some(param) => {
+                    if let (openapi::ReferenceOr::Item(documented), openapi::ReferenceOr::Item(typed)) = (&param.schema, &mut schema) {
+                        typed.example = typed.example.take().or_else(|| documented.example.clone());
+                    }
+                    param.schema = schema;
+                    param.description = param.description.take().or(field_description);
+                    Self::type_parameter_examples(param);
+                }
+                None => parameters.push(openapi::Parameter {
+                    name,
+                    location: "path".to_string(),
+                    description: field_description,
+                    required: true,
+                    deprecated: false,
+                    style: None,
+                    explode: None,
+                    schema,
+                    example: None,
+                }),
+            }
+        }
+    }
+
+    /// `$ref`s to the shared parameters a handler lists as "name (ref)"
+    fn shared_parameter_refs(&self, doc: &HandlerDocumentation<&str>) -> Vec<openapi::ReferenceOr<openapi::Parameter>> {
+        let param_strings: Vec<String> = serde_json::from_str(doc.parameters).unwrap_or_default();
+        param_strings
+            .iter()
+            .filter_map(|param| param.strip_prefix("ParameterRef: "))
+            .filter(|name| {
+                let registered = self.shared_parameters.contains_key(*name);
+                if !registered {
+                    eprintln!("Warning: {} refers to shared parameter '{name}', which was never registered with shared_parameter()", doc.function_name);
+                }
+                registered
+            })
+            .map(|name| openapi::ReferenceOr::new_ref(format!("#/components/parameters/{name}")))
+            .collect()
+    }
+
+    /// Names of the `:param` / `{param}` placeholders in a route, in order
+    fn path_parameter_names(path: &str) -> Vec<String> {
+        path.split('/')
+            .filter_map(|segment| {
+                segment
+                    .strip_prefix(':')
+                    .or_else(|| segment.strip_prefix('{').and_then(|rest| rest.strip_suffix('}')))
+            })
+            .map(|name| name.trim_start_matches('*').to_string())
+            .collect()
+    }
+
+    /// Expand the properties of a registered schema into individual query parameters
+    ///
+    /// Fields listed in the schema's `required` array become required parameters;
+    /// a field's description moves from its schema to the parameter.
+    fn query_parameters_from_schema(type_name: &str) -> Vec<openapi::Parameter> {
+        let Some(registration) = inventory::iter::<SchemaRegistration>().find(|reg| reg.type_name == type_name) else {
+            eprintln!("Warning: query parameters type '{type_name}' is not a registered schema");
+            return Vec::new();
+        };
+        let Some(schema) = Self::parse_registered_schema::<openapi::Schema>(registration.schema_json, type_name) else {
+            return Vec::new();
+        };
+
+        let required = schema.required.unwrap_or_default();
+        schema.properties.unwrap_or_default()
+            .into_iter()
+            .map(|(name, mut property)| {
+                let description = match &mut property {
+                    openapi::ReferenceOr::Item(property) => property.description.take(),
+                    openapi::ReferenceOr::Reference { .. } => None,
+                };
+                openapi::Parameter {
+                    required: required.contains(&name),
+                    deprecated: false,
+                    style: None,
+                    explode: None,
+                    name,
+                    location: "query".to_string(),
+                    description,
+                    schema: property,
+                    example: None,
                 }
+            })
+            .collect()
+    }
+
+    /// Re-type a documented parameter's example and default to its schema's `type`
+    fn type_parameter_examples(param: &mut openapi::Parameter) {
+        let openapi::ReferenceOr::Item(schema) = &mut param.schema else {
+            return;
+        };
+        schema.example = schema.example.take().map(|value| Self::typed_schema_value(schema, value));
+        schema.default = schema.default.take().map(|value| Self::typed_schema_value(schema, value));
+        param.example = param.example.take().map(|value| Self::typed_schema_value(schema, value));
+    }
+
+    /// A documented value as JSON of the type the schema declares
+    ///
+    /// `50` becomes a number for an `integer` or `number` schema and `true` a boolean
+    /// for a `boolean` one; arrays and objects are read as JSON. Anything that doesn't
+    /// fit the declared type, or a schema without one, is kept as it was.
+    fn typed_schema_value(schema: &openapi::Schema, value: serde_json::Value) -> serde_json::Value {
+        let serde_json::Value::String(text) = &value else {
+            return value;
+        };
+        match (schema.schema_type.as_deref(), serde_json::from_str::<serde_json::Value>(text.trim()).ok()) {
+            (Some("integer"), Some(serde_json::Value::Number(number))) if number.is_i64() || number.is_u64() => {
+                serde_json::Value::Number(number)
+            }
+            (Some("number"), Some(typed @ serde_json::Value::Number(_)))
+            | (Some("boolean"), Some(typed @ serde_json::Value::Bool(_)))
+            | (Some("array"), Some(typed @ serde_json::Value::Array(_)))
+            | (Some("object"), Some(typed @ serde_json::Value::Object(_))) => typed,
+            _ => value,
+        }
+    }
+
+    /// Placeholder query parameter for documentation lines that don't match "name (in): description"
+    fn unknown_parameter(description: &str) -> openapi::Parameter {
+        openapi::Parameter {
+            name: "unknown".to_string(),
+            location: "query".to_string(),
+            description: Some(description.to_string()),
+            required: false,
+            deprecated: false,
+            style: None,
+            explode: None,
+            schema: openapi::ReferenceOr::new_item(openapi::Schema {
+                schema_type: Some("string".to_string()),
+                ..Default::default()
+            }),
+            example: None,
+        }
+    }
+
+    /// `application/json` content holding the given schema
+    fn json_content(schema: openapi::ReferenceOr<openapi::Schema>) -> BTreeMap<String, openapi::MediaType> {
+        BTreeMap::from([(
+            "application/json".to_string(),
+            openapi::MediaType { schema: Some(schema) },
+        )])
+    }
+
+    /// Content with one entry per media type; JSON types get the response schema,
+    /// raw bytes a binary string and anything else (CSV, plain text, XML) a string
+    fn content_for_types(
+        content_types: &[String],
+        json_schema: Option<openapi::ReferenceOr<openapi::Schema>>,
+    ) -> BTreeMap<String, openapi::MediaType> {
+        content_types
+            .iter()
+            .map(|content_type| {
+                let schema = if content_type.ends_with("json") {
+                    json_schema.clone().unwrap_or_else(|| openapi::ReferenceOr::new_item(openapi::Schema::default()))
+                } else if Self::is_binary_media_type(content_type) {
+                    Self::binary_schema()
+                } else {
+                    openapi::ReferenceOr::new_item(openapi::Schema {
+                        schema_type: Some("string".to_string()),
+                        ..Default::default()
+                    })
+                };
+                (content_type.clone(), openapi::MediaType { schema: Some(schema) })
+            })
+            .collect()
+    }
+
+    /// Media types whose payload is opaque bytes, like downloads and image uploads
+    fn is_binary_media_type(content_type: &str) -> bool {
+        content_type == "application/octet-stream"
+            || content_type == "application/pdf"
+            || ["image/", "audio/", "video/"].iter().any(|prefix| content_type.starts_with(prefix))
+    }
+
+    /// `{"type": "string", "format": "binary"}`
+    fn binary_schema() -> openapi::ReferenceOr<openapi::Schema> {
+        openapi::ReferenceOr::new_item(openapi::Schema {
+            schema_type: Some("string".to_string()),
+            format: Some("binary".to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Array schema whose items are the given schema
+    fn array_of(items: openapi::ReferenceOr<openapi::Schema>) -> openapi::ReferenceOr<openapi::Schema> {
+        openapi::ReferenceOr::new_item(openapi::Schema {
+            schema_type: Some("array".to_string()),
+            items: Some(Box::new(items)),
+            ..Default::default()
+        })
+    }
+
+    /// `$ref` to a schema under `#/components/schemas`
+    fn schema_ref(schema_name: &str) -> openapi::ReferenceOr<openapi::Schema> {
+        openapi::ReferenceOr::new_ref(format!("#/components/schemas/{schema_name}"))
+    }
+
+    fn convert_path_to_openapi(&self, axum_path: &str) -> String {
+        // Convert Axum path format (:param) to OpenAPI format ({param})
+        axum_path.split('/').map(|segment| {
+            if let Some(stripped) = segment.strip_prefix(':') {
+                format!("{{{stripped}}}")
+            } else {
+                segment.to_string()
+            }
+        }).collect::<Vec<_>>().join("/")
+    }
+
+    /// Parse into this router's own record of used schemas
+    #[cfg(test)]
+    fn parse_request_body_to_openapi(&mut self, request_body_str: &str) -> openapi::RequestBody {
+        Self::build_request_body(&mut self.used_schemas, &Self::registered_schema_names(), request_body_str)
+    }
+
+    /// Names of every schema registered through inventory
+    ///
+    /// Spec generation gathers these once per build and hands them to the parse helpers.
+    fn registered_schema_names() -> std::collections::BTreeSet<String> {
+        #[cfg(test)]
+        REGISTERED_SCHEMA_WALKS.with(|walks| walks.set(walks.get() + 1));
+        inventory::iter::<SchemaRegistration>()
+            .map(|reg| reg.type_name.to_string())
+            .collect()
+    }
+
+    /// Request body for a handler's request body doc lines, recording the
+    /// registered schemas it refers to in `used_schemas`
+    fn build_request_body(
+        used_schemas: &mut std::collections::HashSet<String>,
+        registered_schemas: &std::collections::BTreeSet<String>,
+        request_body_str: &str,
+    ) -> openapi::RequestBody {
+        if request_body_str == "[]" || request_body_str.is_empty() {
+            return openapi::RequestBody {
+                description: None,
+                content: Self::json_content(openapi::ReferenceOr::new_item(openapi::Schema::default())),
+                required: true,
+            };
+        }
+
+        // Extract request body information from documentation, decoding the
+        // JSON string array so escaped characters come through intact
+        let content: Vec<String> = serde_json::from_str(request_body_str).unwrap_or_else(|_| {
+            request_body_str
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split("\",\"")
+                .map(|s| s.trim_matches('"').to_string())
+                .collect()
+        });
+
+        // Media type declared with #[request_body(...)] or a "Content-Type:" doc line,
+        // and optionality from #[request_body(required = false)]
+        let declared_content_type = content.iter()
+            .find_map(|line| line.strip_prefix("ContentType: "))
+            .or_else(|| {
+                content.iter()
+                    .find_map(|line| line.split_once("Content-Type:"))
+                    .map(|(_, declared)| declared.trim())
+                    .filter(|declared| !declared.is_empty())
+            });
+        let required = content.iter().find_map(|line| line.strip_prefix("Required: ")) != Some("false");
+
+        let schema_body = |schema_name: &str| openapi::RequestBody {
+            description: Some("Request body".to_string()),
+            content: BTreeMap::from([(
+                declared_content_type.unwrap_or("application/json").to_string(),
+                openapi::MediaType { schema: Some(Self::schema_ref(schema_name)) },
+            )]),
+            required,
+        };
+
+        // A schema named by #[request_body(schema = "...")] wins over everything else
+        if let Some(schema_name) = content.iter().find_map(|line| line.strip_prefix("Schema: ")) {
+            let schema_name = schema_name.split("::").last().unwrap_or(schema_name).trim();
+            if registered_schemas.contains(schema_name) {
+                used_schemas.insert(schema_name.to_string());
+                return schema_body(schema_name);
             }
+            eprintln!("Warning: request body schema '{schema_name}' is not a registered schema; describing it from the documentation instead");
         }
 
-        // Fallback: Look for type references in the documentation
-        for schema_name in &registered_schemas {
-            if request_body_str.contains(schema_name) {
-                self.used_schemas.insert(schema_name.clone());
-                return format!(
-                    "{{\"required\": true, \"description\": \"Request body\", \"content\": {{\"application/json\": {{\"schema\": {{\"$ref\": \"#/components/schemas/{schema_name}\"}}}}}}}}"
-                );
+        // Otherwise the extractor type from the signature, when it's a registered schema;
+        // prose in the doc section is only ever a description
+        let type_name = content.iter()
+            .find_map(|line| line.strip_prefix("Type: "))
+            .map(|type_name| type_name.split("::").last().unwrap_or(type_name).trim());
+        if let Some(type_name) = type_name {
+            if registered_schemas.contains(type_name) {
+                used_schemas.insert(type_name.to_string());
+                return schema_body(type_name);
             }
+            eprintln!("Warning: request body type '{type_name}' is not a registered schema; describing it from the documentation instead");
         }
 
         let mut description = "Request body".to_string();
-        let mut content_type = "application/json";
-        let mut properties = Vec::new();
+        let content_type = declared_content_type.unwrap_or("application/json");
+        let mut properties = BTreeMap::new();
 
-        for line in content {
-            if line.contains("Content-Type:") {
-                if line.contains("application/json") {
-                    content_type = "application/json";
-                }
+        for line in &content {
+            if line.starts_with("ContentType: ") || line.starts_with("Required: ") || line.contains("Content-Type:") {
+                continue;
             } else if let Some(field_desc) = line.strip_prefix("- ") {
                 // Parse field descriptions like "- name (string): The user's full name"
                 if let Some(colon_pos) = field_desc.find(':') {
@@ -954,52 +2680,144 @@ where
                             let field_name = left[..paren_start].trim();
                             let field_type = left[paren_start + 1..paren_end].trim();
 
-                            properties.push(format!(
-                                r#""{}": {{"type": "{}", "description": "{}"}}"#,
-                                field_name,
-                                field_type,
-                                desc.replace("\"", "\\\"")
-                            ));
+                            let schema_type = Self::normalize_field_type(field_type).unwrap_or_else(|| {
+                                eprintln!("Warning: unknown type '{field_type}' for request body field '{field_name}', using string");
+                                "string"
+                            });
+
+                            properties.insert(
+                                field_name.to_string(),
+                                openapi::ReferenceOr::new_item(openapi::Schema {
+                                    schema_type: Some(schema_type.to_string()),
+                                    format: Self::field_type_format(field_type).map(str::to_string),
+                                    description: Some(desc.to_string()),
+                                    ..Default::default()
+                                }),
+                            );
                         }
                     }
                 }
             } else if !line.is_empty() && !line.contains("Content-Type") {
-                description = line.to_string();
+                description = line.clone();
             }
         }
 
-        let schema = if properties.is_empty() {
-            r#"{"type": "object"}"#.to_string()
+        // A raw upload is the bytes themselves, not an object with fields
+        let schema = if Self::is_binary_media_type(content_type) {
+            Self::binary_schema()
         } else {
-            format!(r#"{{"type": "object", "properties": {{{}}}}}"#, properties.join(","))
+            openapi::ReferenceOr::new_item(openapi::Schema {
+                properties: (!properties.is_empty()).then_some(properties),
+                ..Default::default()
+            })
         };
 
-        format!(
-            r#"{{"required": true, "description": "{}", "content": {{"{}": {{"schema": {}}}}}}}"#,
-            description.replace("\"", "\\\""),
-            content_type,
-            schema
-        )
+        openapi::RequestBody {
+            description: Some(description),
+            content: BTreeMap::from([(
+                content_type.to_string(),
+                openapi::MediaType { schema: Some(schema) },
+            )]),
+            required,
+        }
     }
 
-    fn parse_responses_to_openapi(&mut self, responses_str: &str) -> String {
-        if responses_str == "[]" || responses_str.is_empty() {
-            return r#"{"200": {"description": "Successful response"}}"#.to_string();
+    /// Map a casual field type from handler docs, e.g. "int" or "str", to a JSON Schema type
+    fn normalize_field_type(field_type: &str) -> Option<&'static str> {
+        match field_type.to_ascii_lowercase().as_str() {
+            "int" | "integer" => Some("integer"),
+            "str" | "string" => Some("string"),
+            "bool" | "boolean" => Some("boolean"),
+            "float" | "number" => Some("number"),
+            "array" => Some("array"),
+            "object" => Some("object"),
+            "binary" | "file" => Some("string"),
+            _ => None,
+        }
+    }
+
+    /// The `format` a casual field type implies, e.g. "binary" for a `file` upload part
+    fn field_type_format(field_type: &str) -> Option<&'static str> {
+        match field_type.to_ascii_lowercase().as_str() {
+            "binary" | "file" => Some("binary"),
+            _ => None,
         }
+    }
 
-        // Get list of registered schema types for $ref generation
-        let registered_schemas: std::collections::HashSet<String> = inventory::iter::<SchemaRegistration>()
-            .map(|reg| reg.type_name.to_string())
-            .collect();
+    /// Parse into this router's own record of used schemas
+    #[cfg(test)]
+    fn parse_responses_to_openapi(&mut self, responses_str: &str) -> BTreeMap<String, openapi::Response> {
+        Self::build_responses(&mut self.used_schemas, &Self::registered_schema_names(), responses_str)
+    }
+
+    /// Responses for a handler's response doc lines, recording the
+    /// registered schemas it refers to in `used_schemas`
+    fn build_responses(
+        used_schemas: &mut std::collections::HashSet<String>,
+        registered_schemas: &std::collections::BTreeSet<String>,
+        responses_str: &str,
+    ) -> BTreeMap<String, openapi::Response> {
+        if responses_str == "[]" || responses_str.is_empty() {
+            return Self::default_responses();
+        }
 
         // Use proper JSON parsing to extract response strings
         let response_strings: Result<Vec<String>, _> = serde_json::from_str(responses_str);
 
+        let mut extracted_response_type: Option<String> = None;
         let mut extracted_error_type: Option<String> = None;
+        let mut response_headers: BTreeMap<String, BTreeMap<String, openapi::Header>> = BTreeMap::new();
+        let mut response_links: BTreeMap<String, BTreeMap<String, openapi::Link>> = BTreeMap::new();
+        let mut response_content_types: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut response_schemas: BTreeMap<String, String> = BTreeMap::new();
         let responses: Vec<(String, String)> = match response_strings {
             Ok(strings) => {
                 strings.into_iter().filter_map(|item| {
-                    // Check if this is an ErrorType metadata entry
+                    // Schemas named by a handler's #[response(schema = "...")] attribute
+                    if let Some(schema) = item.strip_prefix("Schema: ") {
+                        if let Some((code, name)) = schema.split_once(' ') {
+                            let name = name.split("::").last().unwrap_or(name).trim();
+                            if registered_schemas.contains(name) {
+                                response_schemas.insert(code.to_string(), name.to_string());
+                            } else {
+                                eprintln!("Warning: response schema '{name}' for status {code} is not a registered schema; ignoring it");
+                            }
+                        }
+                        return None;
+                    }
+
+                    // Media types declared with "Content-Type:" under a response
+                    if let Some(content_types) = item.strip_prefix("ContentType: ") {
+                        if let Some((code, types)) = content_types.split_once(' ') {
+                            response_content_types
+                                .entry(code.to_string())
+                                .or_default()
+                                .extend(types.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()));
+                        }
+                        return None;
+                    }
+
+                    // Headers declared in a response's "headers:" block
+                    if let Some(header) = item.strip_prefix("Header: ") {
+                        if let Some((code, name, header)) = Self::parse_response_header(header) {
+                            response_headers.entry(code).or_default().insert(name, header);
+                        }
+                        return None;
+                    }
+
+                    // Links declared in a response's "links:" block
+                    if let Some(link) = item.strip_prefix("Link: ") {
+                        if let Some((code, name, link)) = Self::parse_response_link(link) {
+                            response_links.entry(code).or_default().insert(name, link);
+                        }
+                        return None;
+                    }
+
+                    // Check if this is a ResponseType or ErrorType metadata entry
+                    if let Some(response_type) = item.strip_prefix("ResponseType: ") {
+                        extracted_response_type = Some(response_type.to_string());
+                        return None; // Don't include metadata in responses
+                    }
                     if let Some(error_type) = item.strip_prefix("ErrorType: ") {
                         extracted_error_type = Some(error_type.to_string());
                         return None; // Don't include metadata in responses
@@ -1010,8 +2828,8 @@ where
                         let status_code = item[..colon_pos].trim();
                         let description = item[colon_pos + 1..].trim();
 
-                        // Only include valid HTTP status codes
-                        if status_code.chars().all(|c| c.is_ascii_digit()) && status_code.len() == 3 {
+                        // Only include valid response keys
+                        if Self::is_response_key(status_code) {
                             return Some((status_code.to_string(), description.to_string()));
                         }
                     }
@@ -1033,8 +2851,8 @@ where
                             let status_code = part[..colon_pos].trim();
                             let description = part[colon_pos + 1..].trim();
 
-                            // Only include valid HTTP status codes
-                            if status_code.chars().all(|c| c.is_ascii_digit()) && status_code.len() == 3 {
+                            // Only include valid response keys
+                            if Self::is_response_key(status_code) {
                                 return Some((status_code.to_string(), description.to_string()));
                             }
                         }
@@ -1045,41 +2863,73 @@ where
         };
 
         if responses.is_empty() {
-            return r#"{"200": {"description": "Successful response"}}"#.to_string();
+            return Self::default_responses();
         }
 
-        let response_objects: Vec<String> = responses.iter().map(|(code, desc)| {
+        // The error enum's variant docs describe its status codes better than the handler doc
+        let error_descriptions: BTreeMap<String, &'static str> = extracted_error_type.as_deref()
+            .map(|error_type| error_type.split("::").last().unwrap_or(error_type).trim())
+            .and_then(|error_type| inventory::iter::<ErrorResponseRegistration>().find(|reg| reg.type_name == error_type))
+            .map(|reg| reg.descriptions.iter().map(|(code, description)| (code.to_string(), *description)).collect())
+            .unwrap_or_default();
+
+        responses.into_iter().map(|(code, desc)| {
+            let desc = match error_descriptions.get(&code) {
+                Some(description) if !code.starts_with('2') => description.to_string(),
+                // OpenAPI requires a description, so a bare "404:" gets the reason phrase
+                _ if desc.is_empty() => Self::reason_phrase(&code).to_string(),
+                _ => desc,
+            };
             // Handle different response types based on status code
-            match code.as_str() {
+            let content = match code.as_str() {
                 "204" => {
                     // 204 No Content should not have a content section
-                    format!(r#""{}": {{"description": "{}"}}"#, code, desc.replace("\"", "\\\""))
+                    None
+                },
+                code if code.starts_with('3') && !response_schemas.contains_key(code) => {
+                    // Redirects point elsewhere through their Location header rather than a body
+                    None
+                },
+                code if response_schemas.contains_key(code) => {
+                    // An explicitly declared schema skips the name matching below
+                    let schema_name = &response_schemas[code];
+                    used_schemas.insert(schema_name.clone());
+                    Some(Self::json_content(Self::schema_ref(schema_name)))
                 },
                 code if code.starts_with('2') => {
                     // Other 2xx responses should have content
-                    let mut schema = r#"{"type":"object","properties":{}}"#.to_string();
-
-                    // Look for registered schema types in the response description or in common response type names
-                    for schema_name in &registered_schemas {
-                        if desc.to_lowercase().contains(&schema_name.to_lowercase()) ||
-                           desc.contains("user") && schema_name.contains("User") ||
-                           desc.contains("greeting") && schema_name.contains("Greet") ||
-                           desc.contains("hello") && schema_name.contains("Hello") {
-                            self.used_schemas.insert(schema_name.clone());
-                            schema = format!("{{\"$ref\": \"#/components/schemas/{schema_name}\"}}");
-                            break;
+                    let mut schema = openapi::ReferenceOr::new_item(openapi::Schema {
+                        properties: Some(BTreeMap::new()),
+                        ..Default::default()
+                    });
+
+                    // Use the registered schema the description names, e.g. "200: Returns UserResponse",
+                    // falling back to the success type from the function signature
+                    let (signature_type, is_list) = match extracted_response_type.as_deref() {
+                        Some(response_type) => match response_type.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+                            Some(item_type) => (Some(item_type), true),
+                            None => (Some(response_type), false),
+                        },
+                        None => (None, false),
+                    };
+                    let signature_schema = signature_type
+                        .map(|type_name| type_name.split("::").last().unwrap_or(type_name).trim())
+                        .and_then(|type_name| registered_schemas.get(type_name));
+
+                    if let Some(schema_name) = Self::find_named_schema(&desc, registered_schemas).or(signature_schema) {
+                        used_schemas.insert(schema_name.clone());
+                        schema = Self::schema_ref(schema_name);
+                        // A Vec<T> signature makes T the item schema, even when the description names T
+                        if is_list && signature_schema == Some(schema_name) {
+                            schema = Self::array_of(schema);
                         }
                     }
 
-                    format!(
-                        r#""{}": {{"description": "{}", "content": {{"application/json": {{"schema": {}}}}}}}"#,
-                        code, desc.replace("\"", "\\\""), schema
-                    )
+                    Some(Self::json_content(schema))
                 },
                 _ => {
                     // 4xx, 5xx and other responses - look for error schemas
-                    let mut has_error_schema = false;
-                    let mut error_schema = String::new();
+                    let mut error_schema = None;
 
                     // First priority: use extracted error type from function signature with mapping
                     if let Some(ref error_type) = extracted_error_type {
@@ -1093,496 +2943,2317 @@ where
                         };
 
                         if registered_schemas.contains(schema_name) {
-                            self.used_schemas.insert(schema_name.to_string());
-                            error_schema = format!("{{\"$ref\": \"#/components/schemas/{schema_name}\"}}");
-                            has_error_schema = true;
+                            error_schema = Some(schema_name.to_string());
                         }
                     }
 
                     // If no extracted error type, try exact schema name match in description
-                    if !has_error_schema {
-                        for schema_name in &registered_schemas {
-                            if schema_name.ends_with("Error") && desc.contains(schema_name) {
-                                self.used_schemas.insert(schema_name.clone());
-                                error_schema = format!("{{\"$ref\": \"#/components/schemas/{schema_name}\"}}");
-                                has_error_schema = true;
-                                break;
-                            }
-                        }
+                    if error_schema.is_none() {
+                        error_schema = registered_schemas
+                            .iter()
+                            .find(|schema_name| schema_name.ends_with("Error") && desc.contains(schema_name.as_str()))
+                            .cloned();
                     }
 
                     // If still no match, try general error matching
-                    if !has_error_schema {
-                        for schema_name in &registered_schemas {
-                            if schema_name.ends_with("Error") && desc.to_lowercase().contains("error") {
-                                self.used_schemas.insert(schema_name.clone());
-                                error_schema = format!("{{\"$ref\": \"#/components/schemas/{schema_name}\"}}");
-                                has_error_schema = true;
-                                break;
-                            }
-                        }
+                    if error_schema.is_none() && desc.to_lowercase().contains("error") {
+                        error_schema = registered_schemas
+                            .iter()
+                            .find(|schema_name| schema_name.ends_with("Error"))
+                            .cloned();
                     }
 
-                    if has_error_schema {
-                        format!(
-                            r#""{}": {{"description": "{}", "content": {{"application/json": {{"schema": {}}}}}}}"#,
-                            code, desc.replace("\"", "\\\""), error_schema
-                        )
-                    } else {
-                        format!(r#""{}": {{"description": "{}"}}"#, code, desc.replace("\"", "\\\""))
-                    }
+                    error_schema.map(|schema_name| {
+                        used_schemas.insert(schema_name.clone());
+                        Self::json_content(Self::schema_ref(&schema_name))
+                    })
+                }
+            };
+
+            // Declared media types replace the default application/json content
+            let content = match response_content_types.remove(&code) {
+                Some(content_types) => {
+                    let json_schema = content
+                        .and_then(|mut content| content.remove("application/json"))
+                        .and_then(|media_type| media_type.schema);
+                    Some(Self::content_for_types(&content_types, json_schema))
                 }
+                None => content,
+            };
+
+            let headers = response_headers.remove(&code);
+            let links = response_links.remove(&code);
+            (code, openapi::Response { description: desc, headers, links, content })
+        }).collect()
+    }
+
+    /// Parse a header entry like "201 Location (string): URL of the new user"
+    /// into its status code, header name and header object
+    fn parse_response_header(entry: &str) -> Option<(String, String, openapi::Header)> {
+        let (code, header) = entry.split_once(' ')?;
+        let (left, description) = header.split_once(':')?;
+
+        let (name, header_type) = match left.find('(').zip(left.find(')')) {
+            Some((paren_start, paren_end)) if paren_start < paren_end => {
+                (left[..paren_start].trim(), left[paren_start + 1..paren_end].trim())
             }
-        }).collect();
+            _ => (left.trim(), "string"),
+        };
+        let schema_type = Self::normalize_field_type(header_type).unwrap_or_else(|| {
+            eprintln!("Warning: unknown type '{header_type}' for response header '{name}', using string");
+            "string"
+        });
+
+        let description = description.trim();
+        Some((
+            code.to_string(),
+            name.to_string(),
+            openapi::Header {
+                description: (!description.is_empty()).then(|| description.to_string()),
+                schema: openapi::ReferenceOr::new_item(openapi::Schema {
+                    schema_type: Some(schema_type.to_string()),
+                    ..Default::default()
+                }),
+            },
+        ))
+    }
+
+    /// Parse a link entry like "201 GetUser (getUser, userId = $response.body#/id): The created user"
+    /// into its status code, link name and link object. Parameter values are read as JSON when they
+    /// parse, so literals stay typed while runtime expressions are kept as strings.
+    fn parse_response_link(entry: &str) -> Option<(String, String, openapi::Link)> {
+        let (code, link) = entry.split_once(' ')?;
+        let paren_start = link.find('(')?;
+        let paren_end = paren_start + link[paren_start..].find(')')?;
+        let name = link[..paren_start].trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut targets = link[paren_start + 1..paren_end].split(',').map(str::trim);
+        let operation_id = targets.next().filter(|id| !id.is_empty())?;
+        let parameters: BTreeMap<String, serde_json::Value> = targets
+            .filter_map(|parameter| {
+                let (key, value) = parameter.split_once('=')?;
+                let value = value.trim();
+                let value = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                Some((key.trim().to_string(), value))
+            })
+            .collect();
+
+        let description = link[paren_end + 1..].trim().trim_start_matches(':').trim();
+        Some((
+            code.to_string(),
+            name.to_string(),
+            openapi::Link {
+                operation_id: Some(operation_id.to_string()),
+                parameters: (!parameters.is_empty()).then_some(parameters),
+                description: (!description.is_empty()).then(|| description.to_string()),
+            },
+        ))
+    }
 
-        format!("{{{}}}", response_objects.join(","))
+    /// Find a registered schema whose exact type name appears as a word in a response description
+    fn find_named_schema<'a>(description: &str, registered_schemas: &'a std::collections::BTreeSet<String>) -> Option<&'a String> {
+        description
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .find_map(|word| registered_schemas.get(word))
     }
 
     /// Parse description text for metadata like examples and defaults
-    /// Format: "Description text [example: value, default: value]"
-    /// Returns: (clean_description, example, default)
-    fn parse_description_with_metadata(description: &str) -> (String, Option<String>, Option<String>) {
+    /// Format: "Description text [example: value, default: value, style: form, explode: true, deprecated]"
+    fn parse_description_with_metadata(description: &str) -> DescriptionMetadata {
         // Look for metadata in square brackets at the end
         if let Some(bracket_start) = description.rfind('[') {
             if let Some(bracket_end) = description[bracket_start..].find(']') {
                 let metadata_str = &description[bracket_start + 1..bracket_start + bracket_end];
-                let clean_description = description[..bracket_start].trim().to_string();
+                let mut metadata = DescriptionMetadata {
+                    description: description[..bracket_start].trim().to_string(),
+                    ..Default::default()
+                };
+
+                // Parse comma-separated metadata: "example: value, default: other"
+                for (key, value) in Self::split_metadata_pairs(metadata_str) {
+                    match key {
+                        "example" => metadata.example = Some(value.to_string()),
+                        "default" => metadata.default = Some(value.to_string()),
+                        "deprecated" => metadata.deprecated = true,
+                        "style" if PARAMETER_STYLES.contains(&value) => metadata.style = Some(value.to_string()),
+                        "style" => eprintln!(
+                            "Warning: ignoring unknown parameter style '{value}'; expected one of {}",
+                            PARAMETER_STYLES.join(", ")
+                        ),
+                        "explode" => match value.parse() {
+                            Ok(explode) => metadata.explode = Some(explode),
+                            Err(_) => eprintln!("Warning: ignoring explode value '{value}'; expected true or false"),
+                        },
+                        _ => {} // Ignore unknown metadata
+                    }
+                }
+
+                return metadata;
+            }
+        }
+
+        // No metadata found, return description as-is
+        DescriptionMetadata {
+            description: description.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Split "key: value, key: value" into pairs, only breaking on commas that
+    /// start a new `key:` or a bare flag like `deprecated`, which pairs with an
+    /// empty value; any other comma stays part of the current value
+    fn split_metadata_pairs(metadata: &str) -> Vec<(&str, &str)> {
+        let is_flag = |segment: &str| segment.split(',').next().is_some_and(|word| word.trim() == "deprecated");
+        let starts_pair = |segment: &str| {
+            is_flag(segment)
+                || segment.split_once(':').is_some_and(|(key, _)| {
+                    let key = key.trim();
+                    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                })
+        };
+
+        // Byte offsets where each pair begins
+        let mut starts = vec![0];
+        for (comma, _) in metadata.match_indices(',') {
+            if starts_pair(&metadata[comma + 1..]) {
+                starts.push(comma + 1);
+            }
+        }
+
+        starts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &start)| {
+                // Each pair runs up to the comma before the next pair
+                let end = starts.get(i + 1).map_or(metadata.len(), |next| next - 1);
+                let segment = &metadata[start..end];
+                if is_flag(segment) {
+                    return Some((segment.trim(), ""));
+                }
+                let (key, value) = segment.split_once(':')?;
+                Some((key.trim(), value.trim()))
+            })
+            .collect()
+    }
+
+    /// A handler's tags, or for an untagged `#[api_handler(tag_from_module)]` handler
+    /// the innermost module of its module path
+    fn handler_tags(&self, doc: &HandlerDocumentation<&str>) -> Vec<String> {
+        let tags: Vec<String> = serde_json::from_str(&self.parse_tags_to_openapi(doc.tags)).unwrap_or_default();
+        if tags.is_empty() && doc.tag_from_module {
+            // A handler at the crate root has no module to take a tag from
+            return doc.module_path.rsplit_once("::").map(|(_, module)| module.to_string()).into_iter().collect();
+        }
+        tags
+    }
+
+    fn parse_tags_to_openapi(&self, tags_str: &str) -> String {
+        if tags_str == "[]" || tags_str.is_empty() {
+            return "[]".to_string();
+        }
+
+        // Parse tag strings like ["user", "admin"] into JSON array
+        let tags: Vec<String> = tags_str
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(|tag| {
+                let clean_tag = tag.trim().trim_matches('"');
+                format!("\"{clean_tag}\"")
+            })
+            .collect();
+
+        format!("[{}]", tags.join(","))
+    }
+
+    pub fn with_openapi_routes(self) -> Self {
+        self.with_spec_routes("/openapi")
+    }
+
+    pub fn with_openapi_routes_prefix(self, prefix: &str) -> Self {
+        // Normalize the prefix
+        let normalized_prefix = if prefix.is_empty() {
+            "/openapi".to_string() // Default prefix when empty
+        } else if prefix.starts_with('/') {
+            prefix.trim_end_matches('/').to_string()
+        } else {
+            format!("/{}", prefix.trim_end_matches('/'))
+        };
+
+        self.with_spec_routes(&normalized_prefix)
+    }
+
+    /// Serve the spec at `{base}.json`, `{base}.yaml` and `{base}`
+    ///
+    /// The bare `base` route picks the format from the `Accept` header and
+    /// falls back to JSON. Every route sends an `ETag` computed here, once,
+    /// and answers a matching `If-None-Match` with 304. CORS headers are only
+    /// sent when `spec_cors()` set an origin.
+    fn with_spec_routes(mut self, base: &str) -> Self {
+        let json = ServedSpec::new("application/json", self.served_openapi_json());
+        let yaml = ServedSpec::new("application/yaml", self.build_spec().to_yaml());
+        let cache_control = match self.spec_max_age {
+            Some(max_age) => format!("public, max-age={max_age}"),
+            None => "no-cache".to_string(),
+        };
+
+        let json_path = format!("{base}.json");
+        let yaml_path = format!("{base}.yaml");
+        let cors_origin = self.spec_cors_origin.clone();
+        // With CORS enabled each spec route also answers the browser's preflight
+        let with_preflight = |method_router: axum::routing::MethodRouter<S>| match cors_origin.clone() {
+            Some(origin) => method_router.options(move || async move { ServedSpec::preflight(&origin) }),
+            None => method_router,
+        };
+
+        let router = self.router
+            .route(&json_path, with_preflight(axum::routing::get({
+                let (json, cache_control, cors_origin) = (json.clone(), cache_control.clone(), cors_origin.clone());
+                move |headers: http::HeaderMap| async move { json.respond(&headers, &cache_control, cors_origin.as_ref()) }
+            })))
+            .route(&yaml_path, with_preflight(axum::routing::get({
+                let (yaml, cache_control, cors_origin) = (yaml.clone(), cache_control.clone(), cors_origin.clone());
+                move |headers: http::HeaderMap| async move { yaml.respond(&headers, &cache_control, cors_origin.as_ref()) }
+            })))
+            .route(base, with_preflight(axum::routing::get({
+                let cors_origin = cors_origin.clone();
+                move |headers: http::HeaderMap| async move {
+                    let accept = headers.get(http::header::ACCEPT).and_then(|value| value.to_str().ok()).unwrap_or_default();
+                    if Self::accepts_yaml(accept) {
+                        yaml.respond(&headers, &cache_control, cors_origin.as_ref())
+                    } else {
+                        json.respond(&headers, &cache_control, cors_origin.as_ref())
+                    }
+                }
+            })));
+
+        self.router = router;
+        self.spec_json_path = Some(json_path);
+        self
+    }
 
-                let mut example = None;
-                let mut default = None;
+    /// Whether an `Accept` header asks for YAML before it asks for JSON
+    fn accepts_yaml(accept: &str) -> bool {
+        accept
+            .split(',')
+            .map(|media_range| media_range.split(';').next().unwrap_or_default().trim().to_ascii_lowercase())
+            .find_map(|media_type| match media_type.as_str() {
+                "application/yaml" | "application/x-yaml" | "text/yaml" => Some(true),
+                "application/json" => Some(false),
+                _ => None,
+            })
+            .unwrap_or(false)
+    }
+
+    /// Serve an interactive Swagger UI page at `path`
+    ///
+    /// The page loads the spec from wherever `with_openapi_routes()` or
+    /// `with_openapi_routes_prefix()` put it, so call one of those first;
+    /// otherwise it points at `/openapi.json`. The page and the `swagger-ui-dist`
+    /// 5.17.14 script and stylesheet are embedded at compile time, and the assets
+    /// are served next to the page, e.g. `/docs/swagger-ui.css`.
+    #[cfg(feature = "swagger-ui")]
+    pub fn with_swagger_ui(self, path: &str) -> Self {
+        self.with_docs_page(path, include_str!("swagger_ui.html"), &[
+            ("swagger-ui-bundle.js", "text/javascript; charset=utf-8", include_bytes!("../assets/swagger-ui/swagger-ui-bundle.js")),
+            ("swagger-ui.css", "text/css; charset=utf-8", include_bytes!("../assets/swagger-ui/swagger-ui.css")),
+        ])
+    }
+
+    /// Serve a ReDoc documentation page at `path`
+    ///
+    /// Like `with_swagger_ui()`, the page reads the spec from the JSON route
+    /// registered by `with_openapi_routes()`/`with_openapi_routes_prefix()`,
+    /// falling back to `/openapi.json`. The ReDoc standalone bundle is embedded
+    /// at compile time and served next to the page, e.g. `/redoc/redoc.standalone.js`.
+    #[cfg(feature = "redoc")]
+    pub fn with_redoc(self, path: &str) -> Self {
+        self.with_docs_page(path, include_str!("redoc.html"), &[
+            ("redoc.standalone.js", "text/javascript; charset=utf-8", include_bytes!("../assets/redoc/redoc.standalone.js")),
+        ])
+    }
+
+    /// Fill in a documentation page template and serve it at `path`
+    ///
+    /// Each `(file name, content type, bytes)` asset is served under `path`,
+    /// which the template refers to as `{{ASSETS}}`.
+    #[cfg(any(feature = "swagger-ui", feature = "redoc"))]
+    fn with_docs_page(mut self, path: &str, template: &str, assets: &[(&str, &'static str, &'static [u8])]) -> Self {
+        let spec_url = self.spec_json_path.as_deref().unwrap_or("/openapi.json");
+        let assets_path = path.trim_end_matches('/');
+        // The spec URL lands inside a script, so it is written as a JSON string
+        // with `<` escaped to keep `</script>` from closing the tag
+        let page = template
+            .replace("{{TITLE}}", &self.openapi.info.title.replace('&', "&amp;").replace('<', "&lt;"))
+            .replace("{{ASSETS}}", &Self::escape_html(assets_path))
+            .replace("{{SPEC_URL}}", &serde_json::to_string(spec_url).unwrap_or_default().replace('<', "\\u003c"));
+        self.router = self.router.route(path, axum::routing::get(move || async move {
+            axum::response::Html(page)
+        }));
+        for &(name, content_type, bytes) in assets {
+            self.router = self.router.route(&format!("{assets_path}/{name}"), axum::routing::get(move || async move {
+                ([(http::header::CONTENT_TYPE, content_type)], bytes)
+            }));
+        }
+        self
+    }
+
+    /// Serve a plain HTML page at `path` listing every documented endpoint
+    ///
+    /// Each row shows the method, path and summary of a route registered so far,
+    /// so call this after the routes it should list. The page links to the JSON
+    /// spec the same way `with_swagger_ui()` does and needs no external assets.
+    pub fn with_openapi_index(mut self, path: &str) -> Self {
+        const METHOD_ORDER: [&str; 8] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "TRACE"];
+
+        let handler_docs = self.collect_handler_docs();
+        let mut rows = String::new();
+        for (route_path, mut routes) in self.group_routes_by_path(&handler_docs, None) {
+            routes.sort_by_key(|route| METHOD_ORDER.iter().position(|method| *method == route.method));
+            let openapi_path = Self::escape_html(&self.convert_path_to_openapi(&route_path));
+            for route in routes {
+                let summary = self.route_doc(route, &handler_docs).map(|doc| doc.summary).unwrap_or_default();
+                rows.push_str(&format!(
+                    "<tr><td class=\"method\">{}</td><td><code>{openapi_path}</code></td><td>{}</td></tr>\n",
+                    route.method,
+                    Self::escape_html(summary)
+                ));
+            }
+        }
+
+        let title = Self::escape_html(&self.openapi.info.title);
+        let spec_url = Self::escape_html(self.spec_json_path.as_deref().unwrap_or("/openapi.json"));
+        let page = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>body{{font-family:sans-serif;margin:2rem}}td{{padding:.25rem 1rem .25rem 0}}.method{{font-weight:bold}}</style>\n\
+             </head>\n<body>\n<h1>{title} <small>{}</small></h1>\n<p><a href=\"{spec_url}\">OpenAPI spec</a></p>\n\
+             <table>\n<tr><th>Method</th><th>Path</th><th>Summary</th></tr>\n{rows}</table>\n</body>\n</html>\n",
+            Self::escape_html(&self.openapi.info.version)
+        );
+        self.router = self.router.route(path, axum::routing::get(move || async move {
+            axum::response::Html(page)
+        }));
+        self
+    }
+
+    /// Escape text for use in HTML element content and quoted attributes
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    /// Merge another ApiRouter into this one
+    /// Both routers must have the same state type S
+    ///
+    /// A path defined in both routers keeps the operations of both, e.g. `GET /users`
+    /// from one and `POST /users` from the other. A method defined on the same path
+    /// in both is logged as a warning before Axum rejects the overlap.
+    pub fn merge(mut self, other: ApiRouter<S>) -> Self {
+        self.spec_cache = None;
+
+        for (existing, incoming) in self.colliding_operations(&other) {
+            eprintln!(
+                "Warning: {} {} is handled by both {} and {} in merged routers",
+                existing.method, existing.path, existing.function_name, incoming.function_name
+            );
+        }
+
+        // Merge the underlying axum routers
+        self.router = self.router.merge(other.router);
+
+        // Merge routes; operations are built per method, so a shared path keeps both sets
+        self.routes.extend(other.routes);
+        self.duplicate_routes.extend(other.duplicate_routes);
+
+        // Merge used schemas
+        self.used_schemas.extend(other.used_schemas);
+
+        // Merge path exclusions
+        self.excluded_paths.extend(other.excluded_paths);
+        self.merged_specs.extend(other.merged_specs);
+
+        // Merge OpenAPI paths, keeping entries this router already has
+        for path in other.openapi.paths.into_keys() {
+            self.openapi.paths.entry(path).or_insert(PathItem);
+        }
+
+        // Merge servers (avoid duplicates)
+        for server in other.openapi.servers {
+            if !self.openapi.servers.iter().any(|s| s.url == server.url) {
+                self.openapi.servers.push(server);
+            }
+        }
+
+        // Merge tags (avoid duplicates)
+        for tag in other.openapi.tags {
+            if !self.openapi.tags.iter().any(|t| t.name == tag.name) {
+                self.openapi.tags.push(tag);
+            }
+        }
+
+        // This router's default security and shared parameters win
+        self.default_security = self.default_security.or(other.default_security);
+        for (name, parameter) in other.shared_parameters {
+            self.shared_parameters.entry(name).or_insert(parameter);
+        }
+        for (name, response) in other.shared_responses {
+            self.shared_responses.entry(name).or_insert(response);
+        }
+
+        self
+    }
+
+    /// Routes in `other` whose method and path this router already handles
+    fn colliding_operations<'a>(&'a self, other: &'a ApiRouter<S>) -> Vec<(&'a RouteInfo, &'a RouteInfo)> {
+        other
+            .routes
+            .iter()
+            .filter_map(|incoming| {
+                self.routes
+                    .iter()
+                    .find(|existing| existing.path == incoming.path && existing.method == incoming.method)
+                    .map(|existing| (existing, incoming))
+            })
+            .collect()
+    }
+
+    /// Mount another ApiRouter under a path prefix, like Axum's `Router::nest`
+    ///
+    /// Every route, spec path and path exclusion of `other` is prefixed before
+    /// it is merged, so `nest("/v1", users)` documents `/users/{id}` as
+    /// `/v1/users/{id}`. Schemas, servers and tags merge as in `merge()`.
+    ///
+    /// Panics like `Router::nest` if the prefix is empty or `/`.
+    pub fn nest(mut self, prefix: &str, mut other: ApiRouter<S>) -> Self {
+        let prefix = prefix.trim_end_matches('/');
+        let nested = |path: &str| if path == "/" { prefix.to_string() } else { format!("{prefix}{path}") };
+
+        for route in &mut other.routes {
+            let mounted = nested(&route.path);
+            if route.summary.as_deref() == Some(&format!("{} {}", route.method, route.path)) {
+                route.summary = Some(format!("{} {}", route.method, mounted));
+            }
+            route.path = mounted;
+        }
+        other.openapi.paths = std::mem::take(&mut other.openapi.paths)
+            .into_iter()
+            .map(|(path, item)| (nested(&path), item))
+            .collect();
+        for pattern in &mut other.excluded_paths {
+            *pattern = nested(pattern);
+        }
+        for (_, path) in &mut other.duplicate_routes {
+            *path = nested(path);
+        }
+
+        // Axum does the routing; the emptied router merges as a no-op below
+        let router = std::mem::take(&mut other.router);
+        self.router = self.router.nest(prefix, router);
+        self.merge(other)
+    }
+
+    // Use into_router().with_state(your_state) for state management
+    pub fn into_router(self) -> Router<S> {
+        self.router
+    }
+}
+
+// Macro to create API router
+#[macro_export]
+macro_rules! api_router {
+    ($title:expr, $version:expr) => {
+        $crate::ApiRouter::new($title, $version)
+    };
+}
+
+// Macro to generate standalone routing functions
+macro_rules! tracked_routing_fn {
+    ($fn_name:ident, $method_upper:expr, $axum_fn:path) => {
+        pub fn $fn_name<H, T, S>(handler: H) -> TrackedMethodRouter<S>
+        where
+            H: axum::handler::Handler<T, S>,
+            T: 'static,
+            S: Clone + Send + Sync + 'static,
+        {
+            TrackedMethodRouter {
+                handlers: vec![($method_upper, extract_handler_path::<H>(), $axum_fn(handler))],
+            }
+        }
+    };
+}
+
+// Custom routing helpers that preserve handler metadata
+tracked_routing_fn!(get, "GET", axum::routing::get);
+tracked_routing_fn!(post, "POST", axum::routing::post);
+tracked_routing_fn!(put, "PUT", axum::routing::put);
+tracked_routing_fn!(delete, "DELETE", axum::routing::delete);
+tracked_routing_fn!(patch, "PATCH", axum::routing::patch);
+tracked_routing_fn!(head, "HEAD", axum::routing::head);
+tracked_routing_fn!(options, "OPTIONS", axum::routing::options);
+tracked_routing_fn!(trace, "TRACE", axum::routing::trace);
+
+// Re-export inventory for macros
+pub use inventory;
+
+// Re-export serde_json for macros
+pub use serde_json;
+
+// Re-export proc macros
+pub use machined_openapi_gen_macros::{api_handler, OpenApiSchema, api_error};
+
+// Mock serde for compatibility
+pub mod serde {
+    pub trait Serialize {}
+    pub trait Deserialize<'de> {}
+
+    // Blanket implementations for all types
+    impl<T> Serialize for T {}
+    impl<'de, T> Deserialize<'de> for T {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test schema registrations
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "UserData",
+            schema_json: r#"{"type": "object", "properties": {"name": {"type": "string"}, "email": {"type": "string"}}, "required": ["name", "email"]}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "CreateUserRequest",
+            schema_json: r#"{"type": "object", "properties": {"name": {"type": "string"}, "email": {"type": "string"}, "age": {"type": "number"}}, "required": ["name", "email", "age"]}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "UpdateUserRequest",
+            schema_json: r#"{"type": "object", "properties": {"name": {"type": "string"}, "email": {"type": "string"}}, "required": ["name", "email"]}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "GreetResponse",
+            schema_json: r#"{"type": "object", "properties": {"message": {"type": "string"}, "style": {"type": "string"}}, "required": ["message", "style"]}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "DeleteUserError",
+            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "GreetError",
+            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "UserResponse",
+            schema_json: r#"{"type": "object", "properties": {"id": {"type": "integer"}, "name": {"type": "string"}, "email": {"type": "string"}}, "required": ["id", "name", "email"]}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "GetUserError",
+            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+        }
+    }
+
+    inventory::submit! {
+        SchemaRegistration {
+            type_name: "CreateUserError",
+            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+        }
+    }
+
+    #[test]
+    fn test_api_router_creation() {
+        let router = ApiRouter::new("Test API", "1.0.0");
+        let spec = router.openapi_spec();
+
+        assert_eq!(spec.info.title, "Test API");
+        assert_eq!(spec.info.version, "1.0.0");
+    }
+
+    #[test]
+    fn test_api_router_macro() {
+        let router = api_router!("Test API", "2.0.0");
+        let spec = router.openapi_spec();
+
+        assert_eq!(spec.info.title, "Test API");
+        assert_eq!(spec.info.version, "2.0.0");
+    }
+
+    #[test]
+    fn test_api_description() {
+        let router = api_router!("Test API", "1.0.0")
+            .description("Test API for testing");
+
+        let spec = router.openapi_spec();
+        assert_eq!(spec.info.description, Some("Test API for testing".to_string()));
+    }
+
+    #[test]
+    fn test_terms_of_service() {
+        let router = api_router!("Test API", "1.0.0")
+            .terms_of_service("https://example.com/terms");
+
+        let spec = router.openapi_spec();
+        assert_eq!(spec.info.terms_of_service, Some("https://example.com/terms".to_string()));
+    }
+
+    #[test]
+    fn test_contact_info() {
+        let router = api_router!("Test API", "1.0.0")
+            .contact(Some("Test Team"), Some("https://example.com"), Some("test@example.com"));
+
+        let spec = router.openapi_spec();
+        assert!(spec.info.contact.is_some());
+
+        let contact = spec.info.contact.as_ref().unwrap();
+        assert_eq!(contact.name, Some("Test Team".to_string()));
+        assert_eq!(contact.url, Some("https://example.com".to_string()));
+        assert_eq!(contact.email, Some("test@example.com".to_string()));
+    }
+
+    #[test]
+    fn test_contact_email_only() {
+        let router = api_router!("Test API", "1.0.0")
+            .contact_email("test@example.com");
+
+        let spec = router.openapi_spec();
+        assert!(spec.info.contact.is_some());
+
+        let contact = spec.info.contact.as_ref().unwrap();
+        assert_eq!(contact.email, Some("test@example.com".to_string()));
+        assert_eq!(contact.name, None);
+        assert_eq!(contact.url, None);
+    }
+
+    #[test]
+    fn test_license() {
+        let router = api_router!("Test API", "1.0.0")
+            .license("MIT", Some("https://opensource.org/licenses/MIT"));
+
+        let spec = router.openapi_spec();
+        assert!(spec.info.license.is_some());
+
+        let license = spec.info.license.as_ref().unwrap();
+        assert_eq!(license.name, "MIT");
+        assert_eq!(license.url, Some("https://opensource.org/licenses/MIT".to_string()));
+    }
+
+    #[test]
+    fn test_tag_addition() {
+        let router = api_router!("Test API", "1.0.0")
+            .tag("users", Some("User operations"))
+            .tag("admin", None);
+
+        let spec = router.openapi_spec();
+        assert_eq!(spec.tags.len(), 2);
+
+        assert_eq!(spec.tags[0].name, "users");
+        assert_eq!(spec.tags[0].description, Some("User operations".to_string()));
+
+        assert_eq!(spec.tags[1].name, "admin");
+        assert_eq!(spec.tags[1].description, None);
+    }
+
+    #[test]
+    fn test_tag_with_external_docs() {
+        let router = api_router!("Test API", "1.0.0")
+            .tag_with_docs(
+                "users",
+                Some("User operations"),
+                Some("Learn more"),
+                "https://example.com/docs"
+            );
+
+        let spec = router.openapi_spec();
+        assert_eq!(spec.tags.len(), 1);
+
+        let tag = &spec.tags[0];
+        assert_eq!(tag.name, "users");
+        assert_eq!(tag.description, Some("User operations".to_string()));
+        assert!(tag.external_docs.is_some());
+
+        let docs = tag.external_docs.as_ref().unwrap();
+        assert_eq!(docs.description, Some("Learn more".to_string()));
+        assert_eq!(docs.url, "https://example.com/docs");
+    }
+
+    #[test]
+    fn test_convert_path_to_openapi() {
+        let router = api_router!("Test API", "1.0.0");
+
+        assert_eq!(router.convert_path_to_openapi("/users/:id"), "/users/{id}");
+        assert_eq!(router.convert_path_to_openapi("/users/:id/posts/:post_id"), "/users/{id}/posts/{post_id}");
+        assert_eq!(router.convert_path_to_openapi("/static"), "/static");
+        assert_eq!(router.convert_path_to_openapi("/"), "/");
+    }
+
+    #[test]
+    fn test_parse_parameters_to_openapi() {
+        let router = api_router!("Test API", "1.0.0");
+
+        // Test empty parameters
+        assert!(router.parse_parameters_to_openapi("[]").is_empty());
+
+        // Test path parameter
+        let params = r#"["id (path): The user ID"]"#;
+        let result = router.parse_parameters_to_openapi(params);
+        assert_eq!(result[0].name, "id");
+        assert_eq!(result[0].location, "path");
+        assert!(result[0].required);
+
+        // Test query parameter
+        let params = r#"["filter (query): Filter results"]"#;
+        let result = router.parse_parameters_to_openapi(params);
+        assert_eq!(result[0].name, "filter");
+        assert_eq!(result[0].location, "query");
+        assert!(!result[0].required);
+    }
+
+    #[test]
+    fn test_cookie_parameter() {
+        let router = api_router!("Test API", "1.0.0");
+        let params = router.parse_parameters_to_openapi(r#"["session_id (cookie): Session identifier"]"#);
+
+        assert_eq!(params[0].name, "session_id");
+        assert_eq!(params[0].location, "cookie");
+        assert!(!params[0].required);
+        assert_eq!(params[0].description.as_deref(), Some("Session identifier"));
+    }
+
+    #[test]
+    fn test_invalid_parameter_location_falls_back_to_query() {
+        let router = api_router!("Test API", "1.0.0");
+        let params = router.parse_parameters_to_openapi(r#"["token (body): Not a parameter location", "Trace (Header): Trace id"]"#);
+
+        assert_eq!(params[0].name, "token");
+        assert_eq!(params[0].location, "query");
+        // Locations are matched case-insensitively
+        assert_eq!(params[1].location, "header");
+    }
+
+    #[test]
+    fn test_parse_responses_to_openapi() {
+        let mut router = api_router!("Test API", "1.0.0");
+
+        // Test empty responses
+        let result = serde_json::to_value(router.parse_responses_to_openapi("[]")).unwrap();
+        assert_eq!(result["200"], serde_json::json!({"description": "Successful response"}));
+
+        // Test simple responses
+        let responses = r#"["200: Success", "404: Not found"]"#;
+        let result = serde_json::to_value(router.parse_responses_to_openapi(responses)).unwrap();
+
+        // Check that the result contains the expected response codes and descriptions
+        assert_eq!(result["200"]["description"], "Success");
+        assert!(result["200"]["content"]["application/json"].is_object()); // 200 responses have content
+        assert_eq!(result["404"], serde_json::json!({"description": "Not found"}));
+    }
+
+    #[test]
+    fn test_parse_tags_to_openapi() {
+        let router = api_router!("Test API", "1.0.0");
+
+        // Test empty tags
+        assert_eq!(router.parse_tags_to_openapi("[]"), "[]");
+        assert_eq!(router.parse_tags_to_openapi(""), "[]");
+
+        // Test single tag
+        let result = router.parse_tags_to_openapi(r#"["users"]"#);
+        assert_eq!(result, r#"["users"]"#);
+
+        // Test multiple tags
+        let result = router.parse_tags_to_openapi(r#"["users", "admin"]"#);
+        assert_eq!(result, r#"["users","admin"]"#);
+    }
+
+    #[test]
+    fn test_handler_tags_from_module() {
+        let router = api_router!("Test API", "1.0.0");
+        let doc = |module_path: &'static str, tags: &'static str| HandlerDocumentation {
+            function_name: "list_products",
+            module_path,
+            hidden: false,
+            deprecated: false,
+            summary: "List products",
+            description: "Handler tagged from its module",
+            parameters: "[]",
+            responses: "[]",
+            request_body: "[]",
+            tags,
+            tag_from_module: true,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        };
+
+        assert_eq!(router.handler_tags(&doc("shop::api::catalog", "[]")), ["catalog"]);
+        // Explicit tags win over the module name
+        assert_eq!(router.handler_tags(&doc("shop::catalog", r#"["products"]"#)), ["products"]);
+        // The crate root is no module to take a tag from
+        assert!(router.handler_tags(&doc("shop", "[]")).is_empty());
+        let untagged = HandlerDocumentation { tag_from_module: false, ..doc("shop::catalog", "[]") };
+        assert!(router.handler_tags(&untagged).is_empty());
+    }
+
+    #[test]
+    fn test_openapi_json_structure() {
+        let mut router = api_router!("Test API", "1.0.0")
+            .description("Test Description")
+            .tag("test", Some("Test operations"));
+
+        let json = router.openapi_json();
+
+        // Basic structure checks
+        assert!(json.contains(r#""openapi":"3.0.0""#));
+        assert!(json.contains(r#""title":"Test API""#));
+        assert!(json.contains(r#""version":"1.0.0""#));
+        assert!(json.contains(r#""description":"Test Description""#));
+        assert!(json.contains(r#""paths":{"#));
+        assert!(json.contains(r#""tags":["#));
+    }
+
+    #[test]
+    fn test_servers_in_openapi_json() {
+        let mut router = api_router!("Test API", "1.0.0")
+            .server("https://api.example.com", Some("Production"))
+            .server("https://staging.example.com", None);
+
+        let json = router.openapi_json();
+        let spec: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(spec["servers"][0]["url"], "https://api.example.com");
+        assert_eq!(spec["servers"][0]["description"], "Production");
+        assert_eq!(spec["servers"][1]["url"], "https://staging.example.com");
+        assert!(spec["servers"][1].get("description").is_none());
+
+        // No servers configured means no servers key at all
+        let mut router = api_router!("Test API", "1.0.0");
+        assert!(!router.openapi_json().contains("servers"));
+    }
+
+    #[test]
+    fn test_merge_spec_with_unique_and_conflicting_paths() {
+        let handwritten: openapi::OpenAPI = serde_json::from_str(r##"{
+            "openapi": "3.0.0",
+            "info": {"title": "Legacy proxy", "version": "0.1.0"},
+            "paths": {
+                "/legacy/ping": {"get": {"summary": "Legacy ping", "responses": {"200": {"description": "pong"}}}},
+                "/users/{id}": {
+                    "get": {"summary": "Hand-written get", "responses": {"200": {"description": "A user"}}},
+                    "patch": {"summary": "Hand-written patch", "responses": {"204": {"description": "Updated"}}}
+                }
+            },
+            "components": {
+                "schemas": {"LegacyStatus": {"type": "string"}, "UserResponse": {"type": "string"}},
+                "securitySchemes": {"legacyKey": {"type": "apiKey", "name": "x-legacy-key", "in": "header"}}
+            },
+            "tags": [{"name": "legacy", "description": "Proxied endpoints"}]
+        }"##).unwrap();
+
+        let mut router = deterministic_router();
+        router.merge_spec(handwritten.clone());
+        let spec = router.build_openapi();
+
+        // A path only the hand-written spec has is added as is
+        assert_eq!(spec.paths["/legacy/ping"].get.as_ref().unwrap().summary.as_deref(), Some("Legacy ping"));
+        // On a shared path, the generated GET is kept and the hand-written PATCH fills in
+        let users = &spec.paths["/users/{id}"];
+        assert_eq!(users.get.as_ref().unwrap().summary.as_deref(), Some("Deterministic output"));
+        assert_eq!(users.patch.as_ref().unwrap().summary.as_deref(), Some("Hand-written patch"));
+        assert!(users.delete.is_some());
+
+        let components = spec.components.as_ref().unwrap();
+        assert!(components.schemas.contains_key("LegacyStatus"));
+        assert!(components.schemas["UserResponse"].as_item().unwrap().properties.is_some());
+        assert!(components.security_schemes.as_ref().unwrap().contains_key("legacyKey"));
+        let tags: Vec<&str> = spec.tags.as_ref().unwrap().iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(tags, ["users", "admin", "legacy"]);
+
+        // With the override flag the hand-written definitions win conflicts
+        let mut router = deterministic_router().merged_specs_override(true);
+        router.merge_spec(handwritten);
+        let spec = router.build_openapi();
+        assert_eq!(spec.paths["/users/{id}"].get.as_ref().unwrap().summary.as_deref(), Some("Hand-written get"));
+        assert_eq!(spec.components.as_ref().unwrap().schemas["UserResponse"].as_item().unwrap().schema_type.as_deref(), Some("string"));
+    }
+
+    #[test]
+    fn test_openapi_version() {
+        let mut router = api_router!("Test API", "1.0.0");
+        assert!(router.openapi_json().starts_with(r#"{"openapi":"3.0.0""#));
+
+        let mut router = router.openapi_version("3.0.3");
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+        assert_eq!(spec["openapi"], "3.0.3");
+        assert!(router.openapi_json_filtered(&["users"]).contains(r#""openapi":"3.0.3""#));
+
+        // Versions outside 3.0.x are rejected and the previous one kept
+        for unsupported in ["3.1.0", "2.0", "4.0.0", "3.0.", "3.0.x"] {
+            let mut rejected = api_router!("Test API", "1.0.0").openapi_version("3.0.2").openapi_version(unsupported);
+            assert_eq!(rejected.build_spec().openapi, "3.0.2", "{unsupported} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_server_with_variables() {
+        let mut router = api_router!("Test API", "1.0.0").server_with_variables(
+            "https://{tenant}.api.example.com/{version}",
+            [
+                ("tenant", openapi::ServerVariable { default: "demo".to_string(), enum_values: None, description: Some("Customer subdomain".to_string()) }),
+                ("version", openapi::ServerVariable { default: "v2".to_string(), enum_values: Some(vec!["v1".to_string(), "v2".to_string()]), description: None }),
+            ],
+        );
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+
+        let server = &spec["servers"][0];
+        assert_eq!(server["url"], "https://{tenant}.api.example.com/{version}");
+        assert_eq!(server["variables"]["tenant"]["default"], "demo");
+        assert_eq!(server["variables"]["tenant"]["description"], "Customer subdomain");
+        assert_eq!(server["variables"]["version"]["enum"], serde_json::json!(["v1", "v2"]));
+    }
+
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "deterministic_output_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Deterministic output",
+            description: "Handler used to check stable serialization",
+            parameters: r#"["id (path): Resource ID", "__REQUIRES_AUTH__"]"#,
+            responses: r#"["200: Returns UserResponse", "404: Not found GetUserError", "400: Bad input GreetError", "500: Internal server error occurred"]"#,
+            request_body: r#"["Type: CreateUserRequest"]"#,
+            tags: r#"["users"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        }
+    }
+
+    fn deterministic_router() -> ApiRouter {
+        let mut router = api_router!("Test API", "1.0.0")
+            .tag("users", Some("User operations"))
+            .tag("admin", None);
+        for (path, method) in [("/users/:id", "GET"), ("/accounts/:id", "PUT"), ("/users/:id", "DELETE"), ("/zones/:id", "POST")] {
+            router.routes.push(RouteInfo {
+                path: path.to_string(),
+                method: method.to_string(),
+                function_name: "deterministic_output_handler".to_string(),
+                handler_path: "deterministic_output_handler".to_string(),
+                summary: None,
+                description: None,
+                doc: None,
+            });
+        }
+        router
+    }
+
+    #[test]
+    fn test_request_body_optional_on_methods_without_body_semantics() {
+        let spec: serde_json::Value = serde_json::from_str(&deterministic_router().openapi_json()).unwrap();
+
+        assert_eq!(spec["paths"]["/users/{id}"]["get"]["requestBody"]["required"], false);
+        assert_eq!(spec["paths"]["/users/{id}"]["delete"]["requestBody"]["required"], false);
+        assert_eq!(spec["paths"]["/accounts/{id}"]["put"]["requestBody"]["required"], true);
+        assert_eq!(spec["paths"]["/zones/{id}"]["post"]["requestBody"]["required"], true);
+    }
+
+    #[test]
+    fn test_openapi_json_is_deterministic() {
+        let first = deterministic_router().openapi_json();
+        let second = deterministic_router().openapi_json();
+
+        assert_eq!(first, second);
+
+        // Paths and schemas are sorted, tags keep insertion order
+        assert!(first.find(r#""/accounts/{id}""#) < first.find(r#""/users/{id}""#));
+        assert!(first.find(r#""CreateUserRequest":"#) < first.find(r#""UserResponse":"#));
+        assert!(first.find(r#""name":"users""#) < first.find(r#""name":"admin""#));
+    }
+
+    #[test]
+    fn test_operations_match_schema_tracking_pass() {
+        let mut router = deterministic_router();
+        let handler_docs = router.collect_handler_docs();
+        let doc = *handler_docs.get("deterministic_output_handler").unwrap();
+        // The PUT route, whose body keeps the required flag the docs give it
+        let operation = router.build_method(&router.routes[1], Some(doc), &ApiRouter::<()>::registered_schema_names());
+
+        // Building an operation builds the same bodies the schema-tracking pass does
+        let request_body = router.parse_request_body_to_openapi(doc.request_body);
+        let responses = router.parse_responses_to_openapi(doc.responses);
+        assert_eq!(operation.request_body, Some(request_body));
+        assert_eq!(
+            operation.responses,
+            responses.into_iter().map(|(code, response)| (code, openapi::ReferenceOr::Item(response))).collect()
+        );
+        for schema in ["CreateUserRequest", "UserResponse", "GetUserError"] {
+            assert!(router.used_schemas.contains(schema), "{schema} should be tracked");
+        }
+    }
+
+    #[test]
+    fn test_filtered_spec_reuses_gathered_handler_docs() {
+        let mut router = deterministic_router();
+        let full = router.openapi_json();
+        let used_schemas = router.used_schemas.clone();
+
+        // Building from docs gathered up front gives the same spec and schema usage
+        let handler_docs = router.collect_handler_docs();
+        let mut rebuilt = deterministic_router();
+        assert_eq!(rebuilt.build_openapi_with_docs(&handler_docs, None).to_json_compact().unwrap(), full);
+        assert_eq!(rebuilt.used_schemas, used_schemas);
+
+        // The filtered build reads the same gathered docs; every route carries the "users" tag
+        let filtered: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["users"])).unwrap();
+        let full: serde_json::Value = serde_json::from_str(&full).unwrap();
+        assert_eq!(filtered["paths"], full["paths"]);
+        assert_eq!(filtered["components"], full["components"]);
+    }
+
+    #[test]
+    fn test_registered_schemas_gathered_once_per_build() {
+        let mut router = deterministic_router();
+        let walks = || REGISTERED_SCHEMA_WALKS.with(|walks| walks.get());
+
+        let before = walks();
+        let json = router.openapi_json();
+        // Four routes each parse a request body and responses twice, yet inventory is walked once
+        assert_eq!(walks() - before, 1);
+
+        let mut rebuilt = deterministic_router();
+        assert_eq!(rebuilt.openapi_json(), json);
+    }
+
+    #[test]
+    fn test_paths_sorted_and_methods_in_canonical_order() {
+        let mut router = api_router!("Test API", "1.0.0");
+        for (path, method) in [
+            ("/zeta", "GET"),
+            ("/items", "OPTIONS"),
+            ("/items", "DELETE"),
+            ("/alpha", "POST"),
+            ("/items", "HEAD"),
+            ("/items", "PATCH"),
+            ("/items", "PUT"),
+            ("/items", "POST"),
+            ("/items", "GET"),
+        ] {
+            router.routes.push(route_info(path, method, &format!("{}_items", method.to_lowercase())));
+        }
+        let json = router.openapi_json();
+
+        let position = |needle: &str| json.find(needle).unwrap_or_else(|| panic!("{needle} missing"));
+        let paths = [r#""/alpha":"#, r#""/items":"#, r#""/zeta":"#].map(position);
+        assert!(paths.is_sorted());
+
+        let items = &json[position(r#""/items":"#)..position(r#""/zeta":"#)];
+        let methods = ["get", "post", "put", "patch", "delete", "head", "options"]
+            .map(|method| items.find(&format!(r#""{method}":"#)).unwrap());
+        assert!(methods.is_sorted(), "methods out of order in {items}");
+    }
+
+    #[test]
+    fn test_pretty_json_output() {
+        let mut router = deterministic_router();
+        let compact = router.openapi_json();
+        let pretty = router.openapi_json_pretty();
+
+        assert!(!compact.contains('\n'));
+        assert!(pretty.contains("\n  \"info\": {\n    \"title\": \"Test API\""));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap()
+        );
+
+        fn served_spec(router: ApiRouter) -> String {
+            let response = call_route(router.with_openapi_routes().into_router(), "/openapi.json");
+            assert_eq!(response.headers()["content-type"], "application/json");
+            let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        }
+        assert_eq!(served_spec(deterministic_router()), compact);
+        assert_eq!(served_spec(deterministic_router().pretty_json(true)), pretty);
+    }
+
+    #[test]
+    fn test_spec_route_negotiates_format_from_accept() {
+        let router = deterministic_router().with_openapi_routes().into_router();
+        let fetch = |uri: &str, accept: Option<&str>| {
+            let mut request = http::Request::builder().uri(uri);
+            if let Some(accept) = accept {
+                request = request.header(http::header::ACCEPT, accept);
+            }
+            let request = request.body(axum::body::Body::empty()).unwrap();
+            let response = ready(tower::ServiceExt::oneshot(router.clone(), request)).unwrap();
+            let content_type = response.headers()["content-type"].to_str().unwrap().to_string();
+            let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+            (content_type, String::from_utf8(body.to_vec()).unwrap())
+        };
+
+        let (content_type, json) = fetch("/openapi", Some("application/json"));
+        assert_eq!(content_type, "application/json");
+        assert_eq!(json, fetch("/openapi.json", None).1);
+
+        for accept in ["application/yaml", "text/yaml", "text/yaml;q=0.9, application/json;q=0.8"] {
+            let (content_type, yaml) = fetch("/openapi", Some(accept));
+            assert_eq!(content_type, "application/yaml");
+            assert_eq!(yaml, fetch("/openapi.yaml", None).1);
+        }
+
+        // No Accept header, or one naming neither format, gets JSON
+        assert_eq!(fetch("/openapi", None).0, "application/json");
+        assert_eq!(fetch("/openapi", Some("text/html, */*")).0, "application/json");
+
+        let router = api_router!("Test API", "1.0.0").with_openapi_routes_prefix("/api/spec").into_router();
+        let request = http::Request::builder()
+            .uri("/api/spec")
+            .header(http::header::ACCEPT, "application/yaml")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = ready(tower::ServiceExt::oneshot(router, request)).unwrap();
+        assert_eq!(response.headers()["content-type"], "application/yaml");
+    }
+
+    #[test]
+    fn test_spec_route_etag_and_if_none_match() {
+        let router = deterministic_router().with_openapi_routes().into_router();
+        let fetch = |uri: &str, if_none_match: Option<&str>| {
+            let mut request = http::Request::builder().uri(uri);
+            if let Some(tag) = if_none_match {
+                request = request.header(http::header::IF_NONE_MATCH, tag);
+            }
+            ready(tower::ServiceExt::oneshot(router.clone(), request.body(axum::body::Body::empty()).unwrap())).unwrap()
+        };
+
+        let response = fetch("/openapi.json", None);
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["cache-control"], "no-cache");
+        let etag = response.headers()["etag"].to_str().unwrap().to_string();
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+
+        // The tag is computed once per spec, so it is the same on every request
+        assert_eq!(fetch("/openapi.json", None).headers()["etag"], etag.as_str());
+        assert_ne!(fetch("/openapi.yaml", None).headers()["etag"], etag.as_str());
+
+        let response = fetch("/openapi.json", Some(&etag));
+        assert_eq!(response.status(), http::StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()["etag"], etag.as_str());
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        assert!(body.is_empty());
+        let weak_in_list = format!(r#""stale", W/{etag}"#);
+        assert_eq!(fetch("/openapi.json", Some(&weak_in_list)).status(), http::StatusCode::NOT_MODIFIED);
+
+        let response = fetch("/openapi.json", Some(r#""0000000000000000""#));
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        assert_eq!(String::from_utf8(body.to_vec()).unwrap(), deterministic_router().openapi_json());
+
+        let router = api_router!("Test API", "1.0.0").spec_max_age(3600).with_openapi_routes().into_router();
+        let response = call_route(router, "/openapi.yaml");
+        assert_eq!(response.headers()["cache-control"], "public, max-age=3600");
+    }
+
+    #[test]
+    fn test_spec_cors_headers_only_on_spec_routes() {
+        let router = api_router!("Test API", "1.0.0")
+            .spec_cors("*")
+            .with_openapi_routes()
+            .route("/debug", get(internal_debug_handler))
+            .into_router();
+
+        let response = call_route(router.clone(), "/openapi.json");
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["access-control-allow-origin"], "*");
+        assert_eq!(response.headers()["access-control-expose-headers"], "ETag");
+        assert_eq!(call_route(router.clone(), "/openapi.yaml").headers()["access-control-allow-origin"], "*");
+
+        let preflight = http::Request::builder()
+            .method(http::Method::OPTIONS)
+            .uri("/openapi.json")
+            .header(http::header::ORIGIN, "https://editor.example.com")
+            .header(http::header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = ready(tower::ServiceExt::oneshot(router.clone(), preflight)).unwrap();
+        assert_eq!(response.status(), http::StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()["access-control-allow-methods"], "GET, OPTIONS");
+        assert_eq!(response.headers()["access-control-allow-headers"], "Accept, If-None-Match");
+
+        // Handler routes are left alone
+        let response = call_route(router, "/debug");
+        assert!(response.headers().get("access-control-allow-origin").is_none());
+
+        // Without spec_cors() the spec carries no CORS headers
+        let router = api_router!("Test API", "1.0.0").with_openapi_routes().into_router();
+        assert!(call_route(router, "/openapi.json").headers().get("access-control-allow-origin").is_none());
+
+        let router = api_router!("Test API", "1.0.0").spec_cors("https://editor.example.com").with_openapi_routes().into_router();
+        assert_eq!(call_route(router, "/openapi").headers()["access-control-allow-origin"], "https://editor.example.com");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_spec_routes_gzip_for_accepting_clients() {
+        use std::io::Read;
+
+        let router = deterministic_router().with_openapi_routes().into_router();
+        let fetch = |accept_encoding: &str| {
+            let request = http::Request::builder()
+                .uri("/openapi.json")
+                .header(http::header::ACCEPT_ENCODING, accept_encoding)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let response = ready(tower::ServiceExt::oneshot(router.clone(), request)).unwrap();
+            let headers = response.headers().clone();
+            let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+            (headers, body)
+        };
+        let expected = deterministic_router().openapi_json();
+
+        let (headers, body) = fetch("br, gzip;q=0.8");
+        assert_eq!(headers["content-encoding"], "gzip");
+        assert_eq!(headers["content-type"], "application/json");
+        assert_eq!(headers["vary"], "Accept-Encoding");
+        let mut decompressed = String::new();
+        flate2::read::GzDecoder::new(&body[..]).read_to_string(&mut decompressed).unwrap();
+        assert_eq!(decompressed, expected);
+        let gzip_etag = headers["etag"].clone();
+
+        let (headers, body) = fetch("gzip;q=0, identity");
+        assert!(headers.get("content-encoding").is_none());
+        assert_eq!(headers["vary"], "Accept-Encoding");
+        assert_eq!(String::from_utf8(body.to_vec()).unwrap(), expected);
+        assert_ne!(headers["etag"], gzip_etag);
+    }
+
+    #[test]
+    fn test_to_markdown_lists_operations_with_parameters() {
+        let markdown = deterministic_router().to_markdown();
+
+        assert!(markdown.starts_with("# Test API 1.0.0\n"));
+        assert!(markdown.contains("\n## users\n\nUser operations\n"));
+        assert_eq!(markdown.matches("### Deterministic output\n").count(), 4);
+        assert!(markdown.contains("`GET /users/{id}`"));
+        assert!(markdown.contains("`DELETE /users/{id}`"));
+        assert!(markdown.contains("\nHandler used to check stable serialization\n"));
+
+        assert!(markdown.contains("| Name | In | Type | Required | Description |\n|------|----|------|----------|-------------|\n"));
+        assert!(markdown.contains("| `id` | path | string | yes | Resource ID |"));
+        assert!(markdown.contains("| 200 | Returns UserResponse | `UserResponse` |"));
+
+        // Every operation carries the users tag, so no other section is written
+        assert!(!markdown.contains("## admin"));
+        assert!(!markdown.contains("## Other operations"));
+    }
+
+    #[cfg(feature = "typescript")]
+    #[test]
+    fn test_to_typescript_covers_registered_schemas() {
+        let mut router = deterministic_router();
+        let declarations = router.to_typescript();
+
+        let schemas = router.build_spec().components.as_ref().unwrap().schemas.clone();
+        assert!(!schemas.is_empty());
+        for name in schemas.keys() {
+            assert!(
+                declarations.contains(&format!("export interface {name} ")) || declarations.contains(&format!("export type {name} = ")),
+                "{name} missing from {declarations}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_openapi_matches_json_output() {
+        let mut router = deterministic_router();
+        let spec = router.build_openapi();
+        let json: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+
+        assert_eq!(serde_json::to_value(&spec.paths).unwrap(), json["paths"]);
+        assert_eq!(serde_json::to_value(&spec.components).unwrap(), json["components"]);
+        assert!(spec.paths["/users/{id}"].get.is_some());
+        assert!(spec.components.as_ref().unwrap().schemas.contains_key("UserResponse"));
+
+        // The returned document is a copy, editing it leaves the router's spec alone
+        let mut edited = router.build_openapi();
+        edited.paths.clear();
+        assert_eq!(router.build_spec().paths.len(), spec.paths.len());
+    }
+
+    #[test]
+    fn test_build_spec_is_cached_until_router_changes() {
+        let mut router = deterministic_router();
+        router.build_spec();
+
+        // Tamper with the cached spec to prove it is reused rather than rebuilt
+        router.spec_cache.as_mut().unwrap().info.title = "Cached".to_string();
+        assert!(router.openapi_json().contains(r#""title":"Cached""#));
+        assert_eq!(router.build_spec().info.title, "Cached");
+
+        // Any builder that changes the router drops the cache
+        let mut router = router.tag("extra", None);
+        assert!(router.spec_cache.is_none());
+        assert!(router.openapi_json().contains(r#""title":"Test API""#));
+
+        router.build_spec();
+        let mut router = router.server("https://api.example.com", None);
+        assert!(router.spec_cache.is_none());
+        assert!(router.openapi_json().contains("https://api.example.com"));
+
+        router.build_spec();
+        let router = router.merge(api_router!("Other", "1.0.0"));
+        assert!(router.spec_cache.is_none());
+
+        // Schema usage follows routes added after the first query
+        let mut router = deterministic_router();
+        assert!(!router.used_schemas().is_empty());
+        assert!(router.get_unused_schemas().contains(&"UserData".to_string()));
+        let mut catalog = api_router!("Catalog", "1.0.0");
+        catalog.routes.push(route_info("/catalog", "GET", "public_catalog_handler"));
+        let mut router = router.merge(catalog);
+        assert!(!router.get_unused_schemas().contains(&"UserData".to_string()));
+        assert!(router.used_schemas().contains(&"UserData".to_string()));
+    }
+
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "public_catalog_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Public catalog",
+            description: "Handler tagged for the public audience",
+            parameters: "[]",
+            responses: r#"["200: Returns UserData"]"#,
+            request_body: "[]",
+            tags: r#"["public"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        }
+    }
+
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "admin_tree_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Admin tree",
+            description: "Handler tagged for administrators only",
+            parameters: "[]",
+            responses: r#"["200: Returns UserData", "400: Bad input CreateUserError"]"#,
+            request_body: r#"["Type: MutualLeft"]"#,
+            tags: r#"["admin"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        }
+    }
+
+    fn audience_router() -> ApiRouter {
+        let mut router = api_router!("Test API", "1.0.0")
+            .tag("public", None)
+            .tag("admin", None);
+        router.routes.push(route_info("/catalog", "GET", "public_catalog_handler"));
+        router.routes.push(route_info("/admin/tree", "POST", "admin_tree_handler"));
+        router
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_keeps_only_matching_operations() {
+        let mut router = audience_router();
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["public"])).unwrap();
+
+        let paths: Vec<&String> = spec["paths"].as_object().unwrap().keys().collect();
+        assert_eq!(paths, ["/catalog"]);
+
+        // Schemas only the admin operation used are pruned, shared ones stay
+        let schemas: Vec<&String> = spec["components"]["schemas"].as_object().unwrap().keys().collect();
+        assert_eq!(schemas, ["UserData"]);
+
+        let tags: Vec<&serde_json::Value> = spec["tags"].as_array().unwrap().iter().map(|tag| &tag["name"]).collect();
+        assert_eq!(tags, ["public"]);
+
+        // The full spec is unaffected
+        let full = router.openapi_json();
+        assert!(full.contains("/admin/tree"));
+        assert!(full.contains(r#""MutualRight""#));
+    }
+
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "public_catalog_pages_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Public catalog pages",
+            description: "Handler using shared components",
+            parameters: r#"["ParameterRef: page"]"#,
+            responses: r#"["200: Returns UserData", "401: $ref Unauthorized"]"#,
+            request_body: "[]",
+            tags: r#"["public"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        }
+    }
+
+    /// The audience router plus a public route that uses shared components
+    fn audience_router_with_shared_components() -> ApiRouter {
+        let page = openapi::Parameter {
+            name: "page".to_string(),
+            location: "query".to_string(),
+            description: Some("Page number".to_string()),
+            required: false,
+            deprecated: false,
+            style: None,
+            explode: None,
+            schema: openapi::ReferenceOr::new_item(openapi::Schema { schema_type: Some("integer".to_string()), ..Default::default() }),
+            example: None,
+        };
+        let unauthorized = openapi::Response {
+            description: "Missing or expired session".to_string(),
+            headers: None,
+            links: None,
+            content: None,
+        };
+        let mut router = audience_router()
+            .shared_parameter("page", page)
+            .shared_response("Unauthorized", unauthorized);
+        router.routes.push(route_info("/catalog/pages", "GET", "public_catalog_pages_handler"));
+        router
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_keeps_shared_parameters() {
+        let mut router = audience_router_with_shared_components();
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["public"])).unwrap();
+
+        assert_eq!(
+            spec["paths"]["/catalog/pages"]["get"]["parameters"],
+            serde_json::json!([{ "$ref": "#/components/parameters/page" }])
+        );
+        assert_eq!(spec["components"]["parameters"]["page"]["in"], "query");
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_keeps_shared_responses() {
+        let mut router = audience_router_with_shared_components();
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["public"])).unwrap();
+
+        assert_eq!(
+            spec["paths"]["/catalog/pages"]["get"]["responses"]["401"],
+            serde_json::json!({ "$ref": "#/components/responses/Unauthorized" })
+        );
+        assert_eq!(spec["components"]["responses"]["Unauthorized"]["description"], "Missing or expired session");
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_keeps_default_security() {
+        let mut router = audience_router().default_security("sessionAuth");
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["public"])).unwrap();
+
+        assert_eq!(spec["security"], serde_json::json!([{ "sessionAuth": [] }]));
+        assert!(spec["components"]["securitySchemes"]["sessionAuth"].is_object());
+    }
+
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "public_note_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Leave a note",
+            description: "Handler with a body documented field by field",
+            parameters: "[]",
+            responses: r#"["204: Saved"]"#,
+            request_body: r#"["- note (string): Free text"]"#,
+            tags: r#"["public"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        }
+    }
+
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "public_memo_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Leave a memo",
+            description: "Handler with the same inline body as public_note_handler",
+            parameters: "[]",
+            responses: r#"["204: Saved"]"#,
+            request_body: r#"["- note (string): Free text"]"#,
+            tags: r#"["public"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
+        }
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_dedupes_inline_schemas() {
+        let mut router = audience_router().dedupe_inline_schemas(true);
+        router.routes.push(route_info("/notes", "POST", "public_note_handler"));
+        router.routes.push(route_info("/memos", "POST", "public_memo_handler"));
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["public"])).unwrap();
+
+        let body_schema = |path: &str| spec["paths"][path]["post"]["requestBody"]["content"]["application/json"]["schema"].clone();
+        let hoisted = body_schema("/notes");
+        assert!(hoisted.get("$ref").is_some(), "the shared body should be hoisted: {hoisted}");
+        assert_eq!(body_schema("/memos"), hoisted);
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_keeps_tagged_merged_operations() {
+        let handwritten: openapi::OpenAPI = serde_json::from_str(r##"{
+            "openapi": "3.0.0",
+            "info": {"title": "Legacy proxy", "version": "0.1.0"},
+            "paths": {
+                "/legacy/catalog": {"get": {"tags": ["public"], "summary": "Legacy catalog", "responses": {"200": {
+                    "description": "Items",
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/LegacyItem"}}}
+                }}}},
+                "/legacy/admin": {"get": {"tags": ["admin"], "summary": "Legacy admin", "responses": {"200": {
+                    "description": "Stats",
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/LegacyStats"}}}
+                }}}},
+                "/legacy/mixed": {
+                    "get": {"tags": ["public"], "summary": "Mixed read", "responses": {"200": {"description": "Read"}}},
+                    "delete": {"summary": "Untagged delete", "responses": {"204": {"description": "Deleted"}}}
+                }
+            },
+            "components": {"schemas": {
+                "LegacyItem": {"type": "object", "properties": {"tag": {"$ref": "#/components/schemas/LegacyTag"}}},
+                "LegacyTag": {"type": "string"},
+                "LegacyStats": {"type": "object"}
+            }}
+        }"##).unwrap();
+
+        let mut router = audience_router();
+        router.merge_spec(handwritten);
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["public"])).unwrap();
+
+        let paths: Vec<&String> = spec["paths"].as_object().unwrap().keys().collect();
+        assert_eq!(paths, ["/catalog", "/legacy/catalog", "/legacy/mixed"]);
+        assert!(spec["paths"]["/legacy/mixed"].get("delete").is_none());
+
+        // Merged schemas are pruned like generated ones, following references
+        let schemas = &spec["components"]["schemas"];
+        assert!(schemas["LegacyItem"].is_object());
+        assert!(schemas["LegacyTag"].is_object());
+        assert!(schemas.get("LegacyStats").is_none());
+    }
+
+    #[test]
+    fn test_openapi_json_filtered_leaves_full_spec_state() {
+        let mut router = audience_router();
+        let full = router.openapi_json();
+        let used = router.used_schemas();
+
+        router.openapi_json_filtered(&["admin"]);
+        assert_eq!(router.used_schemas(), used);
+        assert_eq!(router.openapi_json(), full);
+    }
 
-                // Parse comma-separated metadata: "example: value, default: other"
-                for part in metadata_str.split(',') {
-                    let part = part.trim();
-                    if let Some(colon_pos) = part.find(':') {
-                        let key = part[..colon_pos].trim();
-                        let value = part[colon_pos + 1..].trim();
-
-                        match key {
-                            "example" => example = Some(value.to_string()),
-                            "default" => default = Some(value.to_string()),
-                            _ => {} // Ignore unknown metadata
-                        }
-                    }
-                }
+    #[test]
+    fn test_openapi_json_filtered_without_matches() {
+        let mut router = audience_router();
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json_filtered(&["partner"])).unwrap();
 
-                return (clean_description, example, default);
-            }
-        }
+        assert!(spec["paths"].as_object().unwrap().is_empty());
+        assert!(spec.get("components").is_none());
+        assert!(spec.get("tags").is_none());
+    }
 
-        // No metadata found, return description as-is
-        (description.to_string(), None, None)
+    async fn internal_debug_handler() -> &'static str {
+        "debug"
     }
 
-    fn parse_tags_to_openapi(&self, tags_str: &str) -> String {
-        if tags_str == "[]" || tags_str.is_empty() {
-            return "[]".to_string();
+    inventory::submit! {
+        HandlerDocumentation {
+            function_name: "internal_debug_handler",
+            module_path: module_path!(),
+            hidden: true,
+            deprecated: false,
+            summary: "Internal debug endpoint",
+            description: "Handler hidden from the spec",
+            parameters: "[]",
+            responses: r#"["200: Returns UserResponse"]"#,
+            request_body: "[]",
+            tags: r#"["debug"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
         }
+    }
 
-        // Parse tag strings like ["user", "admin"] into JSON array
-        let tags: Vec<String> = tags_str
-            .trim_start_matches('[')
-            .trim_end_matches(']')
-            .split(',')
-            .map(|tag| {
-                let clean_tag = tag.trim().trim_matches('"');
-                format!("\"{clean_tag}\"")
-            })
-            .collect();
+    #[test]
+    fn test_skipped_handler_is_routable_but_not_documented() {
+        let mut router = api_router!("Test API", "1.0.0").route("/debug", get(internal_debug_handler));
 
-        format!("[{}]", tags.join(","))
+        let json = router.openapi_json();
+        assert!(!json.contains("/debug"));
+        assert!(!json.contains("UserResponse"));
+        assert!(router.validate().is_empty());
+
+        let response = call_route(router.into_router(), "/debug");
+        assert_eq!(response.status(), http::StatusCode::OK);
     }
 
-    pub fn with_openapi_routes(mut self) -> Self {
-        let json_spec = self.openapi_json();
-        let yaml_spec = self.openapi.to_yaml();
-        let router = self.router
-            .route("/openapi.json", axum::routing::get(move || async move {
-                axum::Json(json_spec)
-            }))
-            .route("/openapi.yaml", axum::routing::get(move || async move {
-                ([("content-type", "application/yaml")], yaml_spec)
-            }));
+    /// Poll a future that is expected to finish without waiting on I/O
+    fn ready<F: std::future::Future>(future: F) -> F::Output {
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        match std::pin::pin!(future).poll(&mut context) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("future should complete without waiting"),
+        }
+    }
 
-        Self { router, openapi: self.openapi, routes: self.routes, used_schemas: self.used_schemas }
+    fn call_route(router: Router, uri: &str) -> axum::response::Response {
+        let request = http::Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap();
+        ready(tower::ServiceExt::oneshot(router, request)).unwrap()
     }
 
-    pub fn with_openapi_routes_prefix(mut self, prefix: &str) -> Self {
-        let json_spec = self.openapi_json();
-        let yaml_spec = self.openapi.to_yaml();
+    #[test]
+    fn test_reason_phrase() {
+        assert_eq!(ApiRouter::<()>::reason_phrase("200"), "OK");
+        assert_eq!(ApiRouter::<()>::reason_phrase("201"), "Created");
+        assert_eq!(ApiRouter::<()>::reason_phrase("404"), "Not Found");
+        assert_eq!(ApiRouter::<()>::reason_phrase("503"), "Service Unavailable");
+        assert_eq!(ApiRouter::<()>::reason_phrase("299"), "Response");
+        assert_eq!(ApiRouter::<()>::reason_phrase("default"), "Response");
+    }
 
-        // Normalize the prefix
-        let normalized_prefix = if prefix.is_empty() {
-            "/openapi".to_string() // Default prefix when empty
-        } else if prefix.starts_with('/') {
-            prefix.trim_end_matches('/').to_string()
-        } else {
-            format!("/{}", prefix.trim_end_matches('/'))
-        };
+    #[test]
+    fn test_pascal_case_operation_ids() {
+        assert_eq!(ApiRouter::<()>::pascal_case("create_user"), "CreateUser");
+        assert_eq!(ApiRouter::<()>::pascal_case("users::create_user"), "CreateUser");
+        assert_eq!(ApiRouter::<()>::pascal_case("ping"), "Ping");
+    }
 
-        let json_path = format!("{normalized_prefix}.json");
-        let yaml_path = format!("{normalized_prefix}.yaml");
+    #[test]
+    fn test_parse_external_docs() {
+        let docs = ApiRouter::<()>::parse_external_docs("https://example.com/guides/users  User guide").unwrap();
+        assert_eq!(docs.url, "https://example.com/guides/users");
+        assert_eq!(docs.description.as_deref(), Some("User guide"));
+
+        let docs = ApiRouter::<()>::parse_external_docs("https://example.com/guides/users").unwrap();
+        assert_eq!(docs.description, None);
+        assert_eq!(ApiRouter::<()>::parse_external_docs(""), None);
+    }
 
-        let router = self.router
-            .route(&json_path, axum::routing::get(move || async move {
-                axum::Json(json_spec)
-            }))
-            .route(&yaml_path, axum::routing::get(move || async move {
-                ([("content-type", "application/yaml")], yaml_spec)
-            }));
+    #[test]
+    fn test_bare_status_code_gets_reason_phrase() {
+        let mut router = ApiRouter::<()>::new("Test API", "1.0.0");
+        let responses = router.parse_responses_to_openapi(r#"["201:","404:","299:"]"#);
 
-        Self { router, openapi: self.openapi, routes: self.routes, used_schemas: self.used_schemas }
+        assert_eq!(responses["201"].description, "Created");
+        assert_eq!(responses["404"].description, "Not Found");
+        assert_eq!(responses["299"].description, "Response");
     }
 
-    /// Merge another ApiRouter into this one
-    /// Both routers must have the same state type S
-    pub fn merge(mut self, other: ApiRouter<S>) -> Self {
-        // Merge the underlying axum routers
-        self.router = self.router.merge(other.router);
+    #[test]
+    fn test_is_response_key() {
+        assert!(ApiRouter::<()>::is_response_key("404"));
+        assert!(ApiRouter::<()>::is_response_key("default"));
+        assert!(ApiRouter::<()>::is_response_key("2XX"));
+        assert!(ApiRouter::<()>::is_response_key("5XX"));
+        assert!(!ApiRouter::<()>::is_response_key("6XX"));
+        assert!(!ApiRouter::<()>::is_response_key("4xx"));
+        assert!(!ApiRouter::<()>::is_response_key("Default"));
+        assert!(!ApiRouter::<()>::is_response_key("40"));
+    }
 
-        // Merge routes
-        self.routes.extend(other.routes);
+    #[test]
+    fn test_write_openapi_json_and_yaml() {
+        let mut router = deterministic_router();
+        let dir = std::env::temp_dir().join(format!("machined-openapi-gen-write-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
 
-        // Merge used schemas
-        self.used_schemas.extend(other.used_schemas);
+        let json_path = dir.join("openapi.json");
+        router.write_openapi_json(&json_path).unwrap();
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+        assert_eq!(written, expected);
 
-        // Merge OpenAPI paths
-        self.openapi.paths.extend(other.openapi.paths);
+        let yaml_path = dir.join("openapi.yaml");
+        router.write_openapi_yaml(&yaml_path).unwrap();
+        assert_eq!(std::fs::read_to_string(&yaml_path).unwrap(), router.build_spec().to_yaml());
 
-        // Merge tags (avoid duplicates)
-        for tag in other.openapi.tags {
-            if !self.openapi.tags.iter().any(|t| t.name == tag.name) {
-                self.openapi.tags.push(tag);
-            }
-        }
+        let missing = dir.join("missing").join("openapi.json");
+        assert_eq!(router.write_openapi_json(&missing).unwrap_err().kind(), std::io::ErrorKind::NotFound);
 
-        self
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    // Use into_router().with_state(your_state) for state management
-    pub fn into_router(self) -> Router<S> {
-        self.router
+    #[cfg(feature = "swagger-ui")]
+    #[test]
+    fn test_swagger_ui_points_at_registered_spec() {
+        let router = api_router!("Test API", "1.0.0")
+            .with_openapi_routes_prefix("/api/spec")
+            .with_swagger_ui("/docs")
+            .into_router();
+
+        let response = call_route(router.clone(), "/docs");
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/html; charset=utf-8");
+
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert!(html.contains(r#"url: "/api/spec.json""#));
+        assert!(html.contains("<title>Test API</title>"));
+        assert!(html.contains(r#"<script src="/docs/swagger-ui-bundle.js"></script>"#));
+        assert!(html.contains(r#"href="/docs/swagger-ui.css""#));
+        assert!(!html.contains("https://"));
+
+        // The assets are served from the binary rather than a CDN
+        let response = call_route(router.clone(), "/docs/swagger-ui-bundle.js");
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/javascript; charset=utf-8");
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("SwaggerUIBundle"));
+        assert_eq!(call_route(router.clone(), "/docs/swagger-ui.css").headers()["content-type"], "text/css; charset=utf-8");
+
+        assert_eq!(call_route(router, "/api/spec.json").status(), http::StatusCode::OK);
     }
-}
-
-// Macro to create API router
-#[macro_export]
-macro_rules! api_router {
-    ($title:expr, $version:expr) => {
-        $crate::ApiRouter::new($title, $version)
-    };
-}
 
-// Macro to generate standalone routing functions
-macro_rules! tracked_routing_fn {
-    ($fn_name:ident, $method_upper:expr, $axum_fn:path) => {
-        pub fn $fn_name<H, T, S>(handler: H) -> TrackedMethodRouter<S>
-        where
-            H: axum::handler::Handler<T, S>,
-            T: 'static,
-            S: Clone + Send + Sync + 'static,
-        {
-            TrackedMethodRouter {
-                method_router: $axum_fn(handler),
-                handlers: vec![($method_upper, extract_handler_name::<H>())],
-            }
+    #[cfg(feature = "redoc")]
+    #[test]
+    fn test_redoc_points_at_registered_spec() {
+        fn redoc_html(router: Router) -> String {
+            let response = call_route(router, "/redoc");
+            assert_eq!(response.status(), http::StatusCode::OK);
+            assert_eq!(response.headers()["content-type"], "text/html; charset=utf-8");
+            let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
         }
-    };
-}
-
-// Custom routing helpers that preserve handler metadata
-tracked_routing_fn!(get, "GET", axum::routing::get);
-tracked_routing_fn!(post, "POST", axum::routing::post);
-tracked_routing_fn!(put, "PUT", axum::routing::put);
-tracked_routing_fn!(delete, "DELETE", axum::routing::delete);
-tracked_routing_fn!(patch, "PATCH", axum::routing::patch);
 
-// Re-export inventory for macros
-pub use inventory;
-
-// Re-export serde_json for macros
-pub use serde_json;
+        let default = api_router!("Test API", "1.0.0").with_openapi_routes().with_redoc("/redoc").into_router();
+        let html = redoc_html(default.clone());
+        assert!(html.contains(r#"Redoc.init("/openapi.json""#));
+        assert!(html.contains(r#"<script src="/redoc/redoc.standalone.js"></script>"#));
+        assert!(!html.contains("https://"));
+
+        // The bundle is served from the binary rather than a CDN
+        let response = call_route(default, "/redoc/redoc.standalone.js");
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/javascript; charset=utf-8");
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("Redoc"));
+
+        let prefixed = api_router!("Test API", "1.0.0")
+            .with_openapi_routes_prefix("/api/spec")
+            .with_redoc("/redoc");
+        let html = redoc_html(prefixed.into_router());
+        assert!(html.contains(r#"Redoc.init("/api/spec.json""#));
+        assert!(html.contains("<title>Test API</title>"));
+    }
 
-// Re-export proc macros
-pub use machined_openapi_gen_macros::{api_handler, OpenApiSchema, api_error};
+    #[test]
+    fn test_openapi_index_lists_every_route() {
+        let mut router = api_router!("Test <API>", "1.0.0")
+            .with_openapi_routes_prefix("/api/spec")
+            .exclude_path("/internal/*");
+        for (path, method) in [("/users/:id", "DELETE"), ("/users/:id", "GET"), ("/health", "HEAD"), ("/internal/metrics", "GET")] {
+            router.routes.push(route_info(path, method, "deterministic_output_handler"));
+        }
+        let router = router.with_openapi_index("/endpoints").into_router();
+
+        let response = call_route(router, "/endpoints");
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/html; charset=utf-8");
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(html.contains("<title>Test &lt;API&gt;</title>"));
+        assert!(html.contains(r#"<a href="/api/spec.json">"#));
+        let rows: Vec<&str> = html.lines().filter(|line| line.starts_with("<tr><td")).collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].contains(r#"<td class="method">HEAD</td><td><code>/health</code></td>"#));
+        assert!(rows[1].contains(r#"<td class="method">GET</td><td><code>/users/{id}</code></td>"#));
+        assert!(rows[2].contains(r#"<td class="method">DELETE</td><td><code>/users/{id}</code></td>"#));
+        assert!(!html.contains("/internal/metrics"));
+    }
 
-// Mock serde for compatibility
-pub mod serde {
-    pub trait Serialize {}
-    pub trait Deserialize<'de> {}
+    #[test]
+    fn test_exclude_path_hides_matching_routes() {
+        let mut router = api_router!("Test API", "1.0.0")
+            .tag("users", None)
+            .exclude_path("/internal/*")
+            .exclude_path("/admin/{id}");
+        for path in ["/internal/metrics", "/internal/users/:id", "/admin/:id", "/users/:id"] {
+            router.routes.push(route_info(path, "GET", "deterministic_output_handler"));
+        }
 
-    // Blanket implementations for all types
-    impl<T> Serialize for T {}
-    impl<'de, T> Deserialize<'de> for T {}
-}
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+        let paths: Vec<&String> = spec["paths"].as_object().unwrap().keys().collect();
+        assert_eq!(paths, ["/users/{id}"]);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("/health", "/health"));
+        assert!(!glob_matches("/health", "/healthz"));
+        assert!(glob_matches("/internal/*", "/internal/metrics/cpu"));
+        assert!(!glob_matches("/internal/*", "/users"));
+        assert!(glob_matches("/*/debug", "/v1/debug"));
+        assert!(glob_matches("*", "/anything"));
+    }
 
-    // Test schema registrations
     inventory::submit! {
         SchemaRegistration {
-            type_name: "UserData",
-            schema_json: r#"{"type": "object", "properties": {"name": {"type": "string"}, "email": {"type": "string"}}, "required": ["name", "email"]}"#,
+            type_name: "RecursiveTreeNode",
+            schema_json: r##"{"type":"object","properties":{"children":{"type":"array","items":{"$ref":"#/components/schemas/RecursiveTreeNode"}}}}"##,
         }
     }
 
     inventory::submit! {
         SchemaRegistration {
-            type_name: "CreateUserRequest",
-            schema_json: r#"{"type": "object", "properties": {"name": {"type": "string"}, "email": {"type": "string"}, "age": {"type": "number"}}, "required": ["name", "email", "age"]}"#,
+            type_name: "MutualLeft",
+            schema_json: r##"{"type":"object","properties":{"right":{"$ref":"#/components/schemas/MutualRight"}}}"##,
         }
     }
 
     inventory::submit! {
         SchemaRegistration {
-            type_name: "UpdateUserRequest",
-            schema_json: r#"{"type": "object", "properties": {"name": {"type": "string"}, "email": {"type": "string"}}, "required": ["name", "email"]}"#,
+            type_name: "MutualRight",
+            schema_json: r##"{"type":"object","properties":{"left":{"$ref":"#/components/schemas/MutualLeft"},"ghost":{"$ref":"#/components/schemas/UnregisteredGhost"}}}"##,
         }
     }
 
-    inventory::submit! {
-        SchemaRegistration {
-            type_name: "GreetResponse",
-            schema_json: r#"{"type": "object", "properties": {"message": {"type": "string"}, "style": {"type": "string"}}, "required": ["message", "style"]}"#,
-        }
+    #[test]
+    fn test_transitive_dependencies_with_self_reference() {
+        let mut router = api_router!("Test API", "1.0.0");
+        router.used_schemas.insert("RecursiveTreeNode".to_string());
+
+        let unresolved = router.collect_transitive_schema_dependencies();
+
+        assert!(unresolved.is_empty());
+        assert_eq!(router.used_schemas.len(), 1);
+        assert!(router.used_schemas.contains("RecursiveTreeNode"));
     }
 
-    inventory::submit! {
-        SchemaRegistration {
-            type_name: "DeleteUserError",
-            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
-        }
+    #[test]
+    fn test_transitive_dependencies_with_mutual_recursion() {
+        let mut router = api_router!("Test API", "1.0.0");
+        router.used_schemas.insert("MutualLeft".to_string());
+
+        let unresolved = router.collect_transitive_schema_dependencies();
+
+        let mut used: Vec<&String> = router.used_schemas.iter().collect();
+        used.sort();
+        assert_eq!(used, ["MutualLeft", "MutualRight"]);
+
+        // The dangling reference is reported, not added
+        assert_eq!(unresolved.into_iter().collect::<Vec<_>>(), [("UnregisteredGhost".to_string(), "MutualRight".to_string())]);
     }
 
+    // ChainA refers to ChainB, and so on down to ChainJ
+    macro_rules! chain_schema {
+        ($name:literal, $next:literal) => {
+            inventory::submit! {
+                SchemaRegistration {
+                    type_name: $name,
+                    schema_json: concat!(r##"{"type":"object","properties":{"next":{"$ref":"#/components/schemas/"##, $next, r#""}}}"#),
+                }
+            }
+        };
+    }
+    chain_schema!("ChainA", "ChainB");
+    chain_schema!("ChainB", "ChainC");
+    chain_schema!("ChainC", "ChainD");
+    chain_schema!("ChainD", "ChainE");
+    chain_schema!("ChainE", "ChainF");
+    chain_schema!("ChainF", "ChainG");
+    chain_schema!("ChainG", "ChainH");
+    chain_schema!("ChainH", "ChainI");
+    chain_schema!("ChainI", "ChainJ");
     inventory::submit! {
         SchemaRegistration {
-            type_name: "GreetError",
-            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+            type_name: "ChainJ",
+            schema_json: r#"{"type":"object","properties":{"end":{"type":"boolean"}}}"#,
         }
     }
 
-    inventory::submit! {
-        SchemaRegistration {
-            type_name: "UserResponse",
-            schema_json: r#"{"type": "object", "properties": {"id": {"type": "integer"}, "name": {"type": "string"}, "email": {"type": "string"}}, "required": ["id", "name", "email"]}"#,
-        }
+    #[test]
+    fn test_transitive_dependencies_along_a_chain() {
+        let router = api_router!("Test API", "1.0.0");
+        let graph = router.schema_reference_graph();
+        assert_eq!(graph["ChainA"], ["ChainB"]);
+        assert!(graph["ChainJ"].is_empty());
+
+        // One graph serves any number of walks
+        let mut from_head = std::collections::HashSet::from(["ChainA".to_string()]);
+        assert!(ApiRouter::<()>::walk_schema_references(&mut from_head, &graph).is_empty());
+        let mut collected: Vec<&str> = from_head.iter().map(String::as_str).collect();
+        collected.sort();
+        assert_eq!(collected, ["ChainA", "ChainB", "ChainC", "ChainD", "ChainE", "ChainF", "ChainG", "ChainH", "ChainI", "ChainJ"]);
+
+        let mut from_middle = std::collections::HashSet::from(["ChainH".to_string()]);
+        ApiRouter::<()>::walk_schema_references(&mut from_middle, &graph);
+        assert_eq!(from_middle.len(), 3);
+        assert!(!from_middle.contains("ChainG"));
     }
 
     inventory::submit! {
-        SchemaRegistration {
-            type_name: "GetUserError",
-            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+        HandlerDocumentation {
+            function_name: "validate_lint_handler",
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
+            summary: "Lint target",
+            description: "Handler used to exercise validate()",
+            parameters: "[]",
+            responses: r#"["200: Success"]"#,
+            request_body: r#"["Type: MutualRight"]"#,
+            tags: r#"["users", "undeclared"]"#,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
         }
     }
 
-    inventory::submit! {
-        SchemaRegistration {
-            type_name: "CreateUserError",
-            schema_json: r#"{"type": "object", "properties": {"error": {"type": "object"}}}"#,
+    fn route_info(path: &str, method: &str, function_name: &str) -> RouteInfo {
+        RouteInfo {
+            path: path.to_string(),
+            method: method.to_string(),
+            function_name: function_name.to_string(),
+            handler_path: function_name.to_string(),
+            summary: None,
+            description: None,
+            doc: None,
         }
     }
 
     #[test]
-    fn test_api_router_creation() {
-        let router = ApiRouter::new("Test API", "1.0.0");
-        let spec = router.openapi_spec();
+    fn test_validate_clean_router_has_no_warnings() {
+        let mut router = api_router!("Test API", "1.0.0").tag("users", None);
+        router.routes.push(route_info("/users/:id", "GET", "deterministic_output_handler"));
 
-        assert_eq!(spec.info.title, "Test API");
-        assert_eq!(spec.info.version, "1.0.0");
+        assert!(router.validate().is_empty());
     }
 
     #[test]
-    fn test_api_router_macro() {
-        let router = api_router!("Test API", "2.0.0");
-        let spec = router.openapi_spec();
-
-        assert_eq!(spec.info.title, "Test API");
-        assert_eq!(spec.info.version, "2.0.0");
+    fn test_validate_reports_duplicate_operation_ids() {
+        let mut router = api_router!("Test API", "1.0.0").tag("users", None);
+        router.routes.push(route_info("/users/:id", "GET", "deterministic_output_handler"));
+        router.routes.push(route_info("/accounts/:id", "GET", "deterministic_output_handler"));
+
+        assert_eq!(router.validate(), [SpecWarning::DuplicateOperationId {
+            operation_id: "deterministic_output_handler".to_string(),
+            routes: vec!["GET /users/:id".to_string(), "GET /accounts/:id".to_string()],
+        }]);
     }
 
     #[test]
-    fn test_api_description() {
-        let router = api_router!("Test API", "1.0.0")
-            .description("Test API for testing");
+    fn test_validate_reports_empty_title() {
+        let router = api_router!("", "1.0.0");
+        let warnings = router.validate();
 
-        let spec = router.openapi_spec();
-        assert_eq!(spec.info.description, Some("Test API for testing".to_string()));
+        assert_eq!(warnings, [SpecWarning::EmptyInfoField { field: "title".to_string() }]);
+        assert_eq!(warnings[0].to_string(), "info.title is empty; OpenAPI requires a non-empty title");
     }
 
     #[test]
-    fn test_terms_of_service() {
-        let router = api_router!("Test API", "1.0.0")
-            .terms_of_service("https://example.com/terms");
+    fn test_validate_reports_empty_version() {
+        // Whitespace is as good as empty to a validator
+        let router = api_router!("Test API", "  ");
+        assert_eq!(router.validate(), [SpecWarning::EmptyInfoField { field: "version".to_string() }]);
 
-        let spec = router.openapi_spec();
-        assert_eq!(spec.info.terms_of_service, Some("https://example.com/terms".to_string()));
+        assert!(api_router!("Test API", "1.0.0").validate().is_empty());
     }
 
     #[test]
-    fn test_contact_info() {
-        let router = api_router!("Test API", "1.0.0")
-            .contact(Some("Test Team"), Some("https://example.com"), Some("test@example.com"));
-
-        let spec = router.openapi_spec();
-        assert!(spec.info.contact.is_some());
+    fn test_validate_reports_duplicate_routes() {
+        let mut router = api_router!("Test API", "1.0.0");
+        router.routes.push(route_info("/health", "GET", "undocumented_a"));
+        router.routes.push(route_info("/health", "GET", "undocumented_b"));
+        router.routes.push(route_info("/health", "POST", "undocumented_c"));
 
-        let contact = spec.info.contact.as_ref().unwrap();
-        assert_eq!(contact.name, Some("Test Team".to_string()));
-        assert_eq!(contact.url, Some("https://example.com".to_string()));
-        assert_eq!(contact.email, Some("test@example.com".to_string()));
+        assert_eq!(router.validate(), [SpecWarning::DuplicateRoute {
+            method: "GET".to_string(),
+            path: "/health".to_string(),
+        }]);
     }
 
     #[test]
-    fn test_contact_email_only() {
-        let router = api_router!("Test API", "1.0.0")
-            .contact_email("test@example.com");
-
-        let spec = router.openapi_spec();
-        assert!(spec.info.contact.is_some());
-
-        let contact = spec.info.contact.as_ref().unwrap();
-        assert_eq!(contact.email, Some("test@example.com".to_string()));
-        assert_eq!(contact.name, None);
-        assert_eq!(contact.url, None);
+    fn test_validate_reports_unresolved_refs_and_undeclared_tags() {
+        let mut router = api_router!("Test API", "1.0.0").tag("users", None);
+        router.routes.push(route_info("/lint", "POST", "validate_lint_handler"));
+
+        let warnings = router.validate();
+
+        assert!(warnings.contains(&SpecWarning::UndeclaredTag {
+            tag: "undeclared".to_string(),
+            operation: "validate_lint_handler".to_string(),
+        }));
+        assert!(warnings.contains(&SpecWarning::UnresolvedSchemaRef {
+            schema: "UnregisteredGhost".to_string(),
+            referenced_by: "MutualRight".to_string(),
+        }));
+        assert_eq!(warnings.len(), 2);
+
+        // Validating doesn't record schema usage on the router itself
+        assert!(router.used_schemas.is_empty());
+        assert_eq!(
+            warnings[0].to_string(),
+            "tag 'undeclared' used by 'validate_lint_handler' is not declared"
+        );
     }
 
     #[test]
-    fn test_license() {
-        let router = api_router!("Test API", "1.0.0")
-            .license("MIT", Some("https://opensource.org/licenses/MIT"));
+    fn test_extract_schema_references_ignores_formatting() {
+        let router = api_router!("Test API", "1.0.0");
 
-        let spec = router.openapi_spec();
-        assert!(spec.info.license.is_some());
+        let compact = r##"{"type":"object","properties":{"user":{"$ref":"#/components/schemas/UserData"}}}"##;
+        assert_eq!(router.extract_schema_references(compact), vec!["UserData".to_string()]);
 
-        let license = spec.info.license.as_ref().unwrap();
-        assert_eq!(license.name, "MIT");
-        assert_eq!(license.url, Some("https://opensource.org/licenses/MIT".to_string()));
+        let spaced = r##"{"type": "object", "properties": {"user": {"$ref": "#/components/schemas/UserData"}}}"##;
+        assert_eq!(router.extract_schema_references(spaced), vec!["UserData".to_string()]);
+
+        let pretty = serde_json::to_string_pretty(&serde_json::from_str::<serde_json::Value>(compact).unwrap()).unwrap();
+        assert_eq!(router.extract_schema_references(&pretty), vec!["UserData".to_string()]);
     }
 
     #[test]
-    fn test_tag_addition() {
-        let router = api_router!("Test API", "1.0.0")
-            .tag("users", Some("User operations"))
-            .tag("admin", None);
+    fn test_extract_schema_references_nested_in_items() {
+        let router = api_router!("Test API", "1.0.0");
 
-        let spec = router.openapi_spec();
-        assert_eq!(spec.tags.len(), 2);
+        let schema = r##"{
+            "type": "object",
+            "properties": {
+                "users": {"type": "array", "items": {"$ref": "#/components/schemas/UserResponse"}},
+                "choice": {"oneOf": [{"$ref": "#/components/schemas/GreetResponse"}, {"type": "string"}]}
+            }
+        }"##;
 
-        assert_eq!(spec.tags[0].name, "users");
-        assert_eq!(spec.tags[0].description, Some("User operations".to_string()));
+        let mut refs = router.extract_schema_references(schema);
+        refs.sort();
+        assert_eq!(refs, vec!["GreetResponse".to_string(), "UserResponse".to_string()]);
 
-        assert_eq!(spec.tags[1].name, "admin");
-        assert_eq!(spec.tags[1].description, None);
+        // Invalid JSON yields no references instead of panicking
+        assert!(router.extract_schema_references("{not json").is_empty());
     }
 
     #[test]
-    fn test_tag_with_external_docs() {
-        let router = api_router!("Test API", "1.0.0")
-            .tag_with_docs(
-                "users",
-                Some("User operations"),
-                Some("Learn more"),
-                "https://example.com/docs"
+    fn test_merge_servers_skips_duplicates() {
+        let router = api_router!("Test API", "1.0.0")
+            .server("https://api.example.com", Some("Production"))
+            .merge(
+                api_router!("Other", "1.0.0")
+                    .server("https://api.example.com", Some("Duplicate"))
+                    .server("https://staging.example.com", None),
             );
 
-        let spec = router.openapi_spec();
-        assert_eq!(spec.tags.len(), 1);
+        let servers = &router.openapi_spec().servers;
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].description, Some("Production".to_string()));
+        assert_eq!(servers[1].url, "https://staging.example.com");
+    }
 
-        let tag = &spec.tags[0];
-        assert_eq!(tag.name, "users");
-        assert_eq!(tag.description, Some("User operations".to_string()));
-        assert!(tag.external_docs.is_some());
+    #[test]
+    fn test_merge_unions_methods_on_shared_path() {
+        async fn list_users() -> &'static str {
+            "list"
+        }
+        async fn create_user() -> &'static str {
+            "create"
+        }
 
-        let docs = tag.external_docs.as_ref().unwrap();
-        assert_eq!(docs.description, Some("Learn more".to_string()));
-        assert_eq!(docs.url, "https://example.com/docs");
+        let readers = api_router!("Readers", "1.0.0").route("/users", get(list_users));
+        let writers = api_router!("Writers", "1.0.0").route("/users", post(create_user));
+        assert!(readers.colliding_operations(&writers).is_empty());
+
+        let mut router = readers.merge(writers);
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+        let users = spec["paths"]["/users"].as_object().unwrap();
+        assert_eq!(users.keys().collect::<Vec<_>>(), ["get", "post"]);
+        assert_eq!(call_route(router.into_router(), "/users").status(), 200);
     }
 
     #[test]
-    fn test_convert_path_to_openapi() {
-        let router = api_router!("Test API", "1.0.0");
+    fn test_route_registered_twice_keeps_first_handler_and_warns() {
+        async fn list_users() -> &'static str {
+            "list"
+        }
+        async fn list_people() -> &'static str {
+            "people"
+        }
 
-        assert_eq!(router.convert_path_to_openapi("/users/:id"), "/users/{id}");
-        assert_eq!(router.convert_path_to_openapi("/users/:id/posts/:post_id"), "/users/{id}/posts/{post_id}");
-        assert_eq!(router.convert_path_to_openapi("/static"), "/static");
-        assert_eq!(router.convert_path_to_openapi("/"), "/");
+        let router = api_router!("Test API", "1.0.0")
+            .route("/users", get(list_users))
+            .route("/users", post(list_people).get(list_people));
+
+        // The duplicate GET is skipped, the new POST is still routed
+        let routes: Vec<(&str, &str)> = router.routes().map(|route| (route.method.as_str(), route.function_name.as_str())).collect();
+        assert_eq!(routes, [("GET", "list_users"), ("POST", "list_people")]);
+        assert_eq!(router.validate(), [SpecWarning::DuplicateRoute {
+            method: "GET".to_string(),
+            path: "/users".to_string(),
+        }]);
+
+        let response = call_route(router.into_router(), "/users");
+        let body = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        assert_eq!(&body[..], b"list");
     }
 
     #[test]
-    fn test_parse_parameters_to_openapi() {
-        let router = api_router!("Test API", "1.0.0");
-
-        // Test empty parameters
-        assert_eq!(router.parse_parameters_to_openapi("[]"), "[]");
+    fn test_nested_duplicate_route_reported_at_mounted_path() {
+        async fn list_users() -> &'static str {
+            "list"
+        }
 
-        // Test path parameter
-        let params = r#"["id (path): The user ID"]"#;
-        let result = router.parse_parameters_to_openapi(params);
-        assert!(result.contains(r#""name": "id""#));
-        assert!(result.contains(r#""in": "path""#));
-        assert!(result.contains(r#""required": true"#));
+        let users = api_router!("Users", "1.0.0")
+            .get("/users", list_users)
+            .get("/users", list_users);
+        let router = api_router!("Test API", "1.0.0").nest("/api", users);
 
-        // Test query parameter
-        let params = r#"["filter (query): Filter results"]"#;
-        let result = router.parse_parameters_to_openapi(params);
-        assert!(result.contains(r#""name": "filter""#));
-        assert!(result.contains(r#""in": "query""#));
-        assert!(result.contains(r#""required": false"#));
+        assert_eq!(router.validate(), [SpecWarning::DuplicateRoute {
+            method: "GET".to_string(),
+            path: "/api/users".to_string(),
+        }]);
     }
 
     #[test]
-    fn test_parse_responses_to_openapi() {
-        let mut router = api_router!("Test API", "1.0.0");
+    #[should_panic(expected = "GET /users is routed twice, to list_users and to list_people")]
+    fn test_route_registered_twice_names_both_handlers() {
+        async fn list_users() -> &'static str {
+            "list"
+        }
+        async fn list_people() -> &'static str {
+            "people"
+        }
 
-        // Test empty responses
-        let result = router.parse_responses_to_openapi("[]");
-        assert!(result.contains(r#""200": {"description": "Successful response"}"#));
+        let _ = api_router!("Test API", "1.0.0")
+            .panic_on_duplicate_routes(true)
+            .route("/users", get(list_users))
+            .route("/users", post(list_people).get(list_people));
+    }
 
-        // Test simple responses
-        let responses = r#"["200: Success", "404: Not found"]"#;
-        let result = router.parse_responses_to_openapi(responses);
+    #[test]
+    #[should_panic(expected = "POST /users is routed twice")]
+    fn test_method_helper_registered_twice_panics() {
+        async fn create_user() -> &'static str {
+            "create"
+        }
 
-        // Check that the result contains the expected response codes and descriptions
-        assert!(result.contains(r#""200":"#), "Result should contain '\"200\":' but was: {result}");
-        assert!(result.contains(r#""description": "Success"#));
-        assert!(result.contains(r#""application/json""#)); // 200 responses have content
-        assert!(result.contains(r#""404": {"description": "Not found"}"#));
+        let _ = api_router!("Test API", "1.0.0")
+            .panic_on_duplicate_routes(true)
+            .post("/users", create_user)
+            .post("/users", create_user);
     }
 
     #[test]
-    fn test_parse_tags_to_openapi() {
-        let router = api_router!("Test API", "1.0.0");
+    fn test_routes_iterates_tracked_routes() {
+        async fn list_users() -> &'static str {
+            "list"
+        }
+        async fn create_user() -> &'static str {
+            "create"
+        }
 
-        // Test empty tags
-        assert_eq!(router.parse_tags_to_openapi("[]"), "[]");
-        assert_eq!(router.parse_tags_to_openapi(""), "[]");
+        let router = api_router!("Test API", "1.0.0")
+            .route("/users", get(list_users).post(create_user))
+            .delete("/users/{id}", create_user);
 
-        // Test single tag
-        let result = router.parse_tags_to_openapi(r#"["users"]"#);
-        assert_eq!(result, r#"["users"]"#);
+        let table: Vec<(&str, &str, &str)> = router
+            .routes()
+            .map(|route| (route.method.as_str(), route.path.as_str(), route.function_name.as_str()))
+            .collect();
+        assert_eq!(table, [("GET", "/users", "list_users"), ("POST", "/users", "create_user"), ("DELETE", "/users/{id}", "create_user")]);
+        assert!(router.spec_cache.is_none());
+    }
 
-        // Test multiple tags
-        let result = router.parse_tags_to_openapi(r#"["users", "admin"]"#);
-        assert_eq!(result, r#"["users","admin"]"#);
+    #[test]
+    fn test_colliding_operations_reports_duplicate_methods() {
+        async fn list_users() -> &'static str {
+            "list"
+        }
+        async fn list_people() -> &'static str {
+            "people"
+        }
+
+        let first = api_router!("First", "1.0.0").route("/users", get(list_users));
+        let second = api_router!("Second", "1.0.0").route("/users", get(list_people).post(list_people));
+        let collisions = first.colliding_operations(&second);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].0.function_name, "list_users");
+        assert_eq!(collisions[0].1.function_name, "list_people");
+        assert_eq!(collisions[0].1.method, "GET");
     }
 
     #[test]
-    fn test_openapi_json_structure() {
-        let mut router = api_router!("Test API", "1.0.0")
-            .description("Test Description")
-            .tag("test", Some("Test operations"));
+    fn test_nest_prefixes_routes_and_spec_paths() {
+        async fn handler() -> &'static str {
+            "ok"
+        }
 
-        let json = router.openapi_json();
+        let v1 = api_router!("V1", "1.0.0")
+            .route("/", get(handler))
+            .route("/users", get(handler))
+            .route("/users/{id}", get(handler).delete(handler))
+            .exclude_path("/internal/*")
+            .route("/internal/stats", get(handler))
+            .tag("users", Some("User operations"));
+        let mut router = api_router!("Test API", "1.0.0").route("/health", get(handler)).nest("/v1/", v1);
+
+        let spec: serde_json::Value = serde_json::from_str(&router.openapi_json()).unwrap();
+        let paths: Vec<&String> = spec["paths"].as_object().unwrap().keys().collect();
+        assert_eq!(paths, ["/health", "/v1", "/v1/users", "/v1/users/{id}"]);
+        assert!(spec["paths"]["/v1/users/{id}"]["delete"].is_object());
+        assert_eq!(spec["paths"]["/v1/users"]["get"]["summary"], "GET /v1/users");
+        assert_eq!(spec["tags"][0]["name"], "users");
+
+        let app = router.into_router();
+        assert_eq!(call_route(app.clone(), "/v1/users/7").status(), 200);
+        assert_eq!(call_route(app.clone(), "/v1/internal/stats").status(), 200);
+        assert_eq!(call_route(app, "/users").status(), 404);
+    }
 
-        // Basic structure checks
-        assert!(json.contains(r#""openapi":"3.0.0""#));
-        assert!(json.contains(r#""title":"Test API""#));
-        assert!(json.contains(r#""version":"1.0.0""#));
-        assert!(json.contains(r#""description":"Test Description""#));
-        assert!(json.contains(r#""paths":{"#));
-        assert!(json.contains(r#""tags":["#));
+    /// The `$ref` of a response's `application/json` schema
+    fn json_schema_ref<'a>(responses: &'a BTreeMap<String, openapi::Response>, code: &str) -> Option<&'a str> {
+        responses[code].content.as_ref()?.get("application/json")?.schema.as_ref()?.as_ref_str()
     }
 
     #[test]
@@ -1593,8 +5264,7 @@ mod tests {
         let responses = r#"["200: Returns a personalized GreetResponse message"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
-        assert!(result.contains("GreetResponse"));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/GreetResponse\""));
+        assert_eq!(json_schema_ref(&result, "200"), Some("#/components/schemas/GreetResponse"));
     }
 
     #[test]
@@ -1605,9 +5275,8 @@ mod tests {
         let responses = r#"["404: User not found DeleteUserError", "403: Insufficient permissions DeleteUserError"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
-
-        assert!(result.contains("DeleteUserError"));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/DeleteUserError\""));
+        assert_eq!(json_schema_ref(&result, "404"), Some("#/components/schemas/DeleteUserError"));
+        assert_eq!(json_schema_ref(&result, "403"), Some("#/components/schemas/DeleteUserError"));
     }
 
     #[test]
@@ -1618,9 +5287,124 @@ mod tests {
         let responses = r#"["200: Successfully retrieved UserResponse information", "201: User successfully created UserResponse"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
+        assert_eq!(json_schema_ref(&result, "200"), Some("#/components/schemas/UserResponse"));
+        assert_eq!(json_schema_ref(&result, "201"), Some("#/components/schemas/UserResponse"));
+    }
+
+    #[test]
+    fn test_success_response_keywords_do_not_attach_schema() {
+        let mut router = api_router!("Test", "1.0");
+
+        // "user" and "greeting" used to pull in User*/Greet* schemas by keyword
+        let responses = r#"["200: Returns the user profile", "201: Sends a friendly greeting"]"#;
+        let result = router.parse_responses_to_openapi(responses);
+
+        assert_eq!(json_schema_ref(&result, "200"), None);
+        assert_eq!(json_schema_ref(&result, "201"), None);
+        assert!(router.used_schemas.is_empty());
+    }
+
+    #[test]
+    fn test_success_response_requires_exact_schema_name() {
+        let mut router = api_router!("Test", "1.0");
+
+        // Case-insensitive and partial matches are not schema references
+        let result = router.parse_responses_to_openapi(r#"["200: Returns a userresponse", "202: Queued UserResponses"]"#);
+        assert_eq!(json_schema_ref(&result, "200"), None);
+        assert_eq!(json_schema_ref(&result, "202"), None);
+
+        // The exact type name attaches the schema, even next to punctuation
+        let result = router.parse_responses_to_openapi(r#"["200: Returns (UserResponse)."]"#);
+        assert_eq!(json_schema_ref(&result, "200"), Some("#/components/schemas/UserResponse"));
+        assert!(router.used_schemas.contains("UserResponse"));
+    }
+
+    #[test]
+    fn test_success_response_uses_signature_type() {
+        let mut router = api_router!("Test", "1.0");
+
+        // The description names no schema, so the type from the signature is used
+        let responses = router.parse_responses_to_openapi(r#"["201: User created", "500: Internal server error occurred", "ResponseType: UserResponse"]"#);
+        assert_eq!(json_schema_ref(&responses, "201"), Some("#/components/schemas/UserResponse"));
+        assert!(router.used_schemas.contains("UserResponse"));
+
+        // A schema named in the description still wins
+        let responses = router.parse_responses_to_openapi(r#"["200: Returns GreetResponse", "ResponseType: UserResponse"]"#);
+        assert_eq!(json_schema_ref(&responses, "200"), Some("#/components/schemas/GreetResponse"));
+    }
+
+    #[test]
+    fn test_list_response_uses_array_schema() {
+        let mut router = api_router!("Test", "1.0");
+
+        let responses = router.parse_responses_to_openapi(r#"["200: Returns every UserResponse", "ResponseType: Vec<UserResponse>"]"#);
+        let schema = responses["200"].content.as_ref().unwrap()["application/json"].schema.as_ref().unwrap();
+
+        let array = schema.as_item().expect("list response should be an inline array");
+        assert_eq!(array.schema_type.as_deref(), Some("array"));
+        assert_eq!(array.items.as_ref().unwrap().as_ref_str(), Some("#/components/schemas/UserResponse"));
+        assert!(router.used_schemas.contains("UserResponse"));
+    }
+
+    #[test]
+    fn test_response_headers() {
+        let mut router = api_router!("Test", "1.0");
+
+        let responses = router.parse_responses_to_openapi(
+            r#"["201: User created", "Header: 201 Location (string): URL of the new user", "Header: 201 X-RateLimit-Remaining (int): Requests left", "429: Slow down", "Header: 429 Retry-After: Seconds to wait"]"#,
+        );
+
+        let created = responses["201"].headers.as_ref().expect("201 should have headers");
+        assert_eq!(created["Location"].description.as_deref(), Some("URL of the new user"));
+        assert_eq!(created["Location"].schema.as_item().unwrap().schema_type.as_deref(), Some("string"));
+        assert_eq!(created["X-RateLimit-Remaining"].schema.as_item().unwrap().schema_type.as_deref(), Some("integer"));
+
+        // Untyped headers default to string
+        let retry_after = &responses["429"].headers.as_ref().unwrap()["Retry-After"];
+        assert_eq!(retry_after.schema.as_item().unwrap().schema_type.as_deref(), Some("string"));
+        assert!(!responses.contains_key("Header"));
+    }
+
+    #[test]
+    fn test_response_links() {
+        let mut router = api_router!("Test", "1.0");
+
+        let responses = router.parse_responses_to_openapi(
+            r#"["201: User created", "Link: 201 GetUserById (getUser, userId = $response.body#/id, verbose = true): Fetch the new user", "Link: 201 ListUsers (listUsers)", "400: Bad request"]"#,
+        );
+
+        let links = responses["201"].links.as_ref().expect("201 should have links");
+        let by_id = &links["GetUserById"];
+        assert_eq!(by_id.operation_id.as_deref(), Some("getUser"));
+        assert_eq!(by_id.description.as_deref(), Some("Fetch the new user"));
+        let parameters = by_id.parameters.as_ref().unwrap();
+        assert_eq!(parameters["userId"], "$response.body#/id");
+        assert_eq!(parameters["verbose"], true);
+
+        assert_eq!(links["ListUsers"].operation_id.as_deref(), Some("listUsers"));
+        assert!(links["ListUsers"].parameters.is_none());
+        assert!(links["ListUsers"].description.is_none());
+        assert!(responses["400"].links.is_none());
+        assert!(!responses.contains_key("Link"));
+    }
+
+    #[test]
+    fn test_response_with_multiple_content_types() {
+        let mut router = api_router!("Test", "1.0");
+
+        let responses = router.parse_responses_to_openapi(
+            r#"["200: Returns UserResponse records", "ContentType: 200 application/json, text/csv", "404: Not found"]"#,
+        );
 
-        assert!(result.contains("UserResponse"));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/UserResponse\""));
+        let content = responses["200"].content.as_ref().unwrap();
+        assert_eq!(content.len(), 2);
+        assert_eq!(
+            content["application/json"].schema.as_ref().unwrap().as_ref_str(),
+            Some("#/components/schemas/UserResponse")
+        );
+        let csv = content["text/csv"].schema.as_ref().unwrap().as_item().unwrap();
+        assert_eq!(csv.schema_type.as_deref(), Some("string"));
+        assert!(responses["404"].content.is_none());
     }
 
     #[test]
@@ -1631,12 +5415,9 @@ mod tests {
         let responses = r#"["200: Returns GreetResponse", "400: Invalid request GreetError"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
-
         // Should contain both response and error schema references
-        assert!(result.contains("GreetResponse"));
-        assert!(result.contains("GreetError"));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/GreetResponse\""));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/GreetError\""));
+        assert_eq!(json_schema_ref(&result, "200"), Some("#/components/schemas/GreetResponse"));
+        assert_eq!(json_schema_ref(&result, "400"), Some("#/components/schemas/GreetError"));
     }
 
     #[test]
@@ -1647,9 +5428,8 @@ mod tests {
         let responses = r#"["404: User not found for the given ID GetUserError", "400: Invalid user ID format GetUserError"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
-
-        assert!(result.contains("GetUserError"));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/GetUserError\""));
+        assert_eq!(json_schema_ref(&result, "404"), Some("#/components/schemas/GetUserError"));
+        assert_eq!(json_schema_ref(&result, "400"), Some("#/components/schemas/GetUserError"));
     }
 
     #[test]
@@ -1660,8 +5440,8 @@ mod tests {
         let responses = r#"["400: Invalid input data provided CreateUserError", "500: Internal server error occurred CreateUserError"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
-        assert!(result.contains("CreateUserError"));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/CreateUserError\""));
+        assert_eq!(json_schema_ref(&result, "400"), Some("#/components/schemas/CreateUserError"));
+        assert_eq!(json_schema_ref(&result, "500"), Some("#/components/schemas/CreateUserError"));
     }
 
     #[test]
@@ -1673,18 +5453,18 @@ mod tests {
         let result = router.parse_responses_to_openapi(responses);
 
         // Should contain all error schema references
-        assert!(result.contains("\"$ref\": \"#/components/schemas/GetUserError\""));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/CreateUserError\""));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/DeleteUserError\""));
-        assert!(result.contains("\"$ref\": \"#/components/schemas/GreetError\""));
+        assert_eq!(json_schema_ref(&result, "400"), Some("#/components/schemas/GetUserError"));
+        assert_eq!(json_schema_ref(&result, "401"), Some("#/components/schemas/CreateUserError"));
+        assert_eq!(json_schema_ref(&result, "403"), Some("#/components/schemas/DeleteUserError"));
+        assert_eq!(json_schema_ref(&result, "422"), Some("#/components/schemas/GreetError"));
     }
 
     #[test]
     fn test_unused_schema_detection() {
         let mut router = api_router!("Test", "1.0");
 
-        // Use some schemas first
-        let _ = router.parse_responses_to_openapi(r#"["200: Successfully retrieved UserResponse information", "404: User not found GetUserError"]"#);
+        // Use some schemas first, through a route since usage is read from the spec
+        router.routes.push(route_info("/users/{id}", "GET", "deterministic_output_handler"));
 
         // Now check what's used vs unused
         let all_schemas_count = inventory::iter::<SchemaRegistration>().count();
@@ -1703,6 +5483,23 @@ mod tests {
                 unused.contains(&"UpdateUserRequest".to_string()));
     }
 
+    #[test]
+    fn test_registered_schemas_split_into_used_and_unused() {
+        let mut router = deterministic_router();
+        let registered = router.registered_schemas();
+        let used = router.used_schemas();
+        let unused = router.get_unused_schemas();
+
+        assert!(registered.contains(&"UserResponse".to_string()));
+        assert!(used.contains(&"CreateUserRequest".to_string()));
+        assert!(used.iter().all(|schema| registered.contains(schema)));
+        assert!(used.iter().all(|schema| !unused.contains(schema)));
+
+        let mut combined: Vec<String> = used.into_iter().chain(unused).collect();
+        combined.sort();
+        assert_eq!(combined, registered);
+    }
+
     #[test]
     fn test_openapi_only_includes_used_schemas() {
         let mut router = api_router!("Test", "1.0");
@@ -1785,6 +5582,7 @@ mod handler_tests {
         api_router!("Handler Test API", "1.0.0")
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn simulate_handler_registration(
         _router: &ApiRouter,
         function_name: &'static str,
@@ -1798,12 +5596,20 @@ mod handler_tests {
         // Simulate what the api_handler macro would register
         HandlerDocumentation {
             function_name,
+            module_path: module_path!(),
+            hidden: false,
+            deprecated: false,
             summary,
             description,
             parameters,
             responses,
             request_body,
             tags,
+            tag_from_module: false,
+            extensions: "[]",
+            external_docs: "",
+            security: "",
+            callbacks: "[]",
         }
     }
 
@@ -2046,9 +5852,101 @@ mod handler_tests {
         let json_body = r#"["Type: UserData", "Content-Type: application/json", "- name (string): User name", "- email (string): User email"]"#;
         let result = router.parse_request_body_to_openapi(json_body);
 
-        assert!(result.contains("application/json"));
-        assert!(result.contains("UserData"));
-        assert!(result.contains("required"));
+        assert_eq!(
+            result.content["application/json"].schema,
+            Some(openapi::ReferenceOr::new_ref("#/components/schemas/UserData"))
+        );
+        assert!(result.required);
+    }
+
+    #[test]
+    fn test_request_body_matches_schema_names_exactly() {
+        let mut router = create_test_router();
+
+        // An unregistered type that merely contains a registered name isn't matched to it
+        let body = r#"["Type: UserDataExport", "Export of the user's data", "- name (string): User name"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+        let Some(openapi::ReferenceOr::Item(schema)) = &result.content["application/json"].schema else {
+            panic!("unregistered body type should fall back to an inline schema");
+        };
+        assert!(schema.properties.as_ref().unwrap().contains_key("name"));
+        assert!(!router.used_schemas.contains("UserData"));
+
+        // A module-qualified type matches its registered name
+        let body = r#"["Type: models::UserData"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+        assert_eq!(
+            result.content["application/json"].schema,
+            Some(openapi::ReferenceOr::new_ref("#/components/schemas/UserData"))
+        );
+
+        // A schema named in the documentation's prose is only part of the description
+        let body = r#"["The UpdateUserRequest fields to change", "- name (string): New name"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+        assert_eq!(result.description.as_deref(), Some("The UpdateUserRequest fields to change"));
+        assert!(matches!(result.content["application/json"].schema, Some(openapi::ReferenceOr::Item(_))));
+        assert!(!router.used_schemas.contains("UpdateUserRequest"));
+    }
+
+    #[test]
+    fn test_unregistered_declared_schemas_are_not_referenced() {
+        let mut router = create_test_router();
+
+        // The body falls back to the documented fields
+        let body = r#"["Schema: LoginForm", "- username (string): Account name"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+        let Some(openapi::ReferenceOr::Item(schema)) = &result.content["application/json"].schema else {
+            panic!("unregistered body schema should fall back to an inline schema");
+        };
+        assert!(schema.properties.as_ref().unwrap().contains_key("username"));
+
+        // The response falls back to the schema its description names
+        let result = router.parse_responses_to_openapi(r#"["200: Returns UserResponse", "Schema: 200 LegacyUser", "404: Not found", "Schema: 404 models::Missing"]"#);
+        let ok = result["200"].content.as_ref().unwrap()["application/json"].schema.as_ref().unwrap();
+        assert_eq!(ok.as_ref_str(), Some("#/components/schemas/UserResponse"));
+        assert!(result["404"].content.is_none());
+        assert!(!router.used_schemas.contains("LoginForm"));
+        assert!(!router.used_schemas.contains("LegacyUser"));
+    }
+
+    #[test]
+    fn test_request_body_markers_set_content_type_and_required() {
+        let mut router = create_test_router();
+
+        let body = r#"["Schema: CreateUserRequest", "ContentType: application/x-www-form-urlencoded", "Required: false"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+        assert!(!result.required);
+        assert_eq!(
+            result.content["application/x-www-form-urlencoded"].schema,
+            Some(openapi::ReferenceOr::new_ref("#/components/schemas/CreateUserRequest"))
+        );
+        assert!(router.used_schemas.contains("CreateUserRequest"));
+
+        let body = r#"["Required: false", "- note (string): Free text"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+        assert!(!result.required);
+        assert_eq!(result.description.as_deref(), Some("Request body"));
+    }
+
+    #[test]
+    fn test_multipart_request_body_with_file_part() {
+        let mut router = create_test_router();
+
+        let body = r#"["Content-Type: multipart/form-data", "Avatar upload", "- file (binary): The image to upload", "- caption (string): Alt text for the image"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+
+        assert_eq!(result.content.keys().collect::<Vec<_>>(), ["multipart/form-data"]);
+        let Some(openapi::ReferenceOr::Item(schema)) = &result.content["multipart/form-data"].schema else {
+            panic!("multipart body should have an inline schema");
+        };
+        let properties = schema.properties.as_ref().unwrap();
+        let Some(openapi::ReferenceOr::Item(file)) = properties.get("file") else { panic!("missing file part") };
+        assert_eq!(file.schema_type.as_deref(), Some("string"));
+        assert_eq!(file.format.as_deref(), Some("binary"));
+        let Some(openapi::ReferenceOr::Item(caption)) = properties.get("caption") else { panic!("missing caption part") };
+        assert_eq!(caption.schema_type.as_deref(), Some("string"));
+        assert_eq!(caption.format, None);
+        assert_eq!(result.description.as_deref(), Some("Avatar upload"));
     }
 
     #[test]
@@ -2071,9 +5969,11 @@ mod handler_tests {
         let result = router.parse_responses_to_openapi(responses);
 
         // 204 should not have content
-        assert!(result.contains(r#""204": {"description": "No content"}"#));
+        assert_eq!(serde_json::to_value(&result["204"]).unwrap(), serde_json::json!({"description": "No content"}));
         // 201 and 202 should have content
-        assert!(result.contains(r#""201": {"description": "Created with Location header", "content":"#));
+        assert_eq!(result["201"].description, "Created with Location header");
+        assert!(result["201"].content.is_some());
+        assert!(result["202"].content.is_some());
     }
 
     #[test]
@@ -2085,9 +5985,9 @@ mod handler_tests {
         let result = router.parse_responses_to_openapi(responses);
 
         // Error responses should not have content by default
-        assert!(result.contains(r#""400": {"description": "Validation failed"}"#));
-        assert!(result.contains(r#""409": {"description": "Conflict with existing resource"}"#));
-        assert!(result.contains(r#""422": {"description": "Unprocessable entity"}"#));
+        assert_eq!(serde_json::to_value(&result["400"]).unwrap(), serde_json::json!({"description": "Validation failed"}));
+        assert_eq!(serde_json::to_value(&result["409"]).unwrap(), serde_json::json!({"description": "Conflict with existing resource"}));
+        assert_eq!(serde_json::to_value(&result["422"]).unwrap(), serde_json::json!({"description": "Unprocessable entity"}));
     }
 
     #[test]
@@ -2120,16 +6020,20 @@ mod handler_tests {
             path: "/users".to_string(),
             method: "GET".to_string(),
             function_name: "list_users".to_string(),
+            handler_path: "list_users".to_string(),
             summary: Some("List users".to_string()),
             description: None,
+            doc: None,
         });
 
         router.routes.push(RouteInfo {
             path: "/users/:id".to_string(),
             method: "GET".to_string(),
             function_name: "get_user".to_string(),
+            handler_path: "get_user".to_string(),
             summary: Some("Get user".to_string()),
             description: None,
+            doc: None,
         });
 
         let json = router.openapi_json();
@@ -2148,8 +6052,7 @@ mod handler_tests {
         let responses = r#"["200: Successfully retrieved user information"]"#;
         let result = router.parse_responses_to_openapi(responses);
 
-        // Should detect "user" in description and look for UserResponse schema
-        assert!(result.contains(r#""200": {"description": "Successfully retrieved user information""#));
+        assert_eq!(result["200"].description, "Successfully retrieved user information");
     }
 
     #[test]
@@ -2162,9 +6065,210 @@ mod handler_tests {
         // This should not panic and should use /openapi as default
         let _final_router = router_with_routes.into_router();
     }
+
+    fn build_test_operation(docs: &HandlerDocumentation<&str>) -> openapi::Operation {
+        let route = RouteInfo {
+            path: "/notes".to_string(),
+            method: "POST".to_string(),
+            function_name: docs.function_name.to_string(),
+            handler_path: docs.function_name.to_string(),
+            summary: None,
+            description: None,
+            doc: None,
+        };
+        create_test_router().build_method(&route, Some(docs), &ApiRouter::<()>::registered_schema_names())
+    }
+
+    #[test]
+    fn test_special_characters_survive_parameters() {
+        let docs = simulate_handler_registration(
+            &create_test_router(),
+            "search_notes",
+            "Search notes",
+            "Search notes",
+            r#"["q (query): Say \"hi\" \\ back\nthen 🎉 [example: \"quoted\", default: C:\\tmp]"]"#,
+            r#"["200: Notes found"]"#,
+            "[]",
+            "[]",
+        );
+
+        let operation = build_test_operation(&docs);
+        let param = operation.parameters[0].as_item().unwrap();
+        assert_eq!(param.name, "q");
+        assert_eq!(param.description.as_deref(), Some("Say \"hi\" \\ back\nthen 🎉"));
+
+        let schema = param.schema.as_item().unwrap();
+        assert_eq!(schema.example, Some(serde_json::json!("\"quoted\"")));
+        assert_eq!(schema.default, Some(serde_json::json!("C:\\tmp")));
+    }
+
+    #[test]
+    fn test_special_characters_survive_request_body() {
+        let docs = simulate_handler_registration(
+            &create_test_router(),
+            "create_note",
+            "Create note",
+            "Create note",
+            "[]",
+            r#"["201: Note created"]"#,
+            r#"["A \"note\" payload \\ 📝", "- body (string): Text with\ttabs and\nnewlines"]"#,
+            "[]",
+        );
+
+        let operation = build_test_operation(&docs);
+        let body = operation.request_body.expect("request body should parse");
+        assert_eq!(body.description.as_deref(), Some("A \"note\" payload \\ 📝"));
+
+        let schema = body.content["application/json"].schema.as_ref().unwrap().as_item().unwrap();
+        let field = schema.properties.as_ref().unwrap()["body"].as_item().unwrap();
+        assert_eq!(field.description.as_deref(), Some("Text with\ttabs and\nnewlines"));
+    }
+
+    #[test]
+    fn test_special_characters_survive_responses() {
+        let docs = simulate_handler_registration(
+            &create_test_router(),
+            "delete_note",
+            "Delete note",
+            "Delete note",
+            "[]",
+            r#"["204: Gone \"for good\" \\o/", "404: No note\nfound 🤷"]"#,
+            "[]",
+            "[]",
+        );
+
+        let operation = build_test_operation(&docs);
+        assert_eq!(operation.responses["204"].as_item().unwrap().description, "Gone \"for good\" \\o/");
+        assert_eq!(operation.responses["404"].as_item().unwrap().description, "No note\nfound 🤷");
+        // Parsed rather than replaced by the default 200
+        assert!(!operation.responses.contains_key("200"));
+    }
+
+    #[test]
+    fn test_commas_in_parameter_description() {
+        let docs = simulate_handler_registration(
+            &create_test_router(),
+            "search_contacts",
+            "Search contacts",
+            "Search contacts",
+            r#"["q (query): Filter by name, email, or phone", "sort (query): Sort order, newest first [default: created_at]"]"#,
+            r#"["200: Contacts found"]"#,
+            "[]",
+            "[]",
+        );
+
+        let operation = build_test_operation(&docs);
+        assert_eq!(operation.parameters.len(), 2);
+        let (q, sort) = (operation.parameters[0].as_item().unwrap(), operation.parameters[1].as_item().unwrap());
+        assert_eq!(q.name, "q");
+        assert_eq!(q.description.as_deref(), Some("Filter by name, email, or phone"));
+        assert_eq!(sort.name, "sort");
+        assert_eq!(sort.description.as_deref(), Some("Sort order, newest first"));
+        assert_eq!(
+            sort.schema.as_item().unwrap().default,
+            Some(serde_json::json!("created_at"))
+        );
+    }
+
+    #[test]
+    fn test_commas_inside_metadata_values() {
+        let metadata =
+            ApiRouter::<()>::parse_description_with_metadata("Full name [example: Smith, Jane, default: Doe, John]");
+        assert_eq!(metadata.description, "Full name");
+        assert_eq!(metadata.example.as_deref(), Some("Smith, Jane"));
+        assert_eq!(metadata.default.as_deref(), Some("Doe, John"));
+
+        // Colons inside a value don't start a new pair either
+        let metadata =
+            ApiRouter::<()>::parse_description_with_metadata("Start time [example: 10:30, or later, default: 09:00]");
+        assert_eq!(metadata.example.as_deref(), Some("10:30, or later"));
+        assert_eq!(metadata.default.as_deref(), Some("09:00"));
+    }
+
+    #[test]
+    fn test_deprecated_query_parameter_with_example() {
+        let router = create_test_router();
+        let params = router.parse_parameters_to_openapi(
+            r#"["page_size (query): Results per page [example: 50, deprecated]", "limit (query): Maximum results [deprecated, default: 20]", "cursor (query): Opaque cursor"]"#,
+        );
+
+        assert!(params[0].deprecated);
+        assert_eq!(params[0].description.as_deref(), Some("Results per page"));
+        assert_eq!(params[0].example, Some(serde_json::json!("50")));
+        assert!(params[1].deprecated);
+        assert_eq!(params[1].schema.as_item().unwrap().default, Some(serde_json::json!("20")));
+        assert!(!params[2].deprecated);
+        assert_eq!(params[2].example, None);
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json[0]["deprecated"], true);
+        assert_eq!(json[0]["example"], "50");
+        assert!(json[2].get("deprecated").is_none());
+        assert!(json[2].get("example").is_none());
+    }
+
+    #[test]
+    fn test_parameter_style_and_explode() {
+        let router = create_test_router();
+        let params = router.parse_parameters_to_openapi(
+            r#"["ids (query): Ids to fetch, repeated [style: form, explode: true]", "tags (query): Tags as a|b|c [style: pipeDelimited]", "sort (query): Sort keys [style: commas, explode: maybe]"]"#,
+        );
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json[0]["style"], "form");
+        assert_eq!(json[0]["explode"], true);
+        assert_eq!(json[0]["description"], "Ids to fetch, repeated");
+        assert_eq!(json[1]["style"], "pipeDelimited");
+        assert!(json[1].get("explode").is_none());
+        // Unknown styles and non-boolean explode values are dropped
+        assert!(json[2].get("style").is_none());
+        assert!(json[2].get("explode").is_none());
+    }
+
+    #[test]
+    fn test_invalid_parameter_json_yields_no_parameters() {
+        let router = create_test_router();
+
+        let result = router.parse_parameters_to_openapi(r#"["id (path): unterminated"#);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_request_body_field_type_aliases() {
+        let mut router = create_test_router();
+
+        let body = r#"["Profile update", "- age (int): Age in years", "- name (str): Display name", "- active (bool): Whether enabled", "- score (float): Rating", "- count (Integer): Visits", "- tags (array): Labels"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+
+        let schema = result.content["application/json"].schema.as_ref().unwrap().as_item().unwrap();
+        let properties = schema.properties.as_ref().unwrap();
+        let field_type = |name: &str| properties[name].as_item().unwrap().schema_type.clone().unwrap();
+        assert_eq!(field_type("age"), "integer");
+        assert_eq!(field_type("name"), "string");
+        assert_eq!(field_type("active"), "boolean");
+        assert_eq!(field_type("score"), "number");
+        assert_eq!(field_type("count"), "integer");
+        assert_eq!(field_type("tags"), "array");
+    }
+
+    #[test]
+    fn test_request_body_unknown_field_type_falls_back_to_string() {
+        let mut router = create_test_router();
+
+        assert_eq!(ApiRouter::<()>::normalize_field_type("uuid"), None);
+
+        let body = r#"["Lookup", "- id (uuid): Record identifier"]"#;
+        let result = router.parse_request_body_to_openapi(body);
+
+        let schema = result.content["application/json"].schema.as_ref().unwrap().as_item().unwrap();
+        let id = schema.properties.as_ref().unwrap()["id"].as_item().unwrap();
+        assert_eq!(id.schema_type.as_deref(), Some("string"));
+        assert_eq!(id.description.as_deref(), Some("Record identifier"));
+    }
 }
 
 #[cfg(test)]
+#[allow(unused_imports)]
 mod rustdoc_parsing_tests {
     use super::*;
 
@@ -2176,10 +6280,10 @@ mod rustdoc_parsing_tests {
         let params = r#"["id (path): The unique user identifier", "include_deleted (query): Include soft-deleted records"]"#;
         let result = router.parse_parameters_to_openapi(params);
 
-        assert!(result.contains(r#""name": "id""#));
-        assert!(result.contains(r#""in": "path""#));
-        assert!(result.contains(r#""name": "include_deleted""#));
-        assert!(result.contains(r#""in": "query""#));
+        assert_eq!(result[0].name, "id");
+        assert_eq!(result[0].location, "path");
+        assert_eq!(result[1].name, "include_deleted");
+        assert_eq!(result[1].location, "query");
     }
 
     #[test]
@@ -2190,9 +6294,11 @@ mod rustdoc_parsing_tests {
         let body = r#"["Type: CreateUserRequest", "Content-Type: application/json", "User information for account creation", "- name (string): The user's full name", "- email (string): Valid email address", "- age (number): User's age in years"]"#;
         let result = router.parse_request_body_to_openapi(body);
 
-        assert!(result.contains("CreateUserRequest"));
-        assert!(result.contains("application/json"));
-        assert!(result.contains("required"));
+        assert_eq!(
+            result.content["application/json"].schema,
+            Some(openapi::ReferenceOr::new_ref("#/components/schemas/CreateUserRequest"))
+        );
+        assert!(result.required);
     }
 
     #[test]
@@ -2204,10 +6310,7 @@ mod rustdoc_parsing_tests {
         let result = router.parse_responses_to_openapi(responses);
 
         // Verify each status code is parsed
-        assert!(result.contains(r#""200":"#));
-        assert!(result.contains(r#""201":"#));
-        assert!(result.contains(r#""400":"#));
-        assert!(result.contains(r#""500":"#));
+        assert_eq!(result.keys().collect::<Vec<_>>(), ["200", "201", "400", "500"]);
     }
 
     #[test]
@@ -2219,8 +6322,9 @@ mod rustdoc_parsing_tests {
         let result = router.parse_parameters_to_openapi(params);
 
         // Should handle the valid one
-        assert!(result.contains(r#""name": "valid""#));
+        assert!(result.iter().any(|param| param.name == "valid" && param.location == "query"));
     }
+
 }
 
 #[cfg(test)]
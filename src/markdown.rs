@@ -0,0 +1,170 @@
+//! Render a generated spec as a Markdown reference
+//!
+//! [`to_markdown`] writes a section per tag, in the order the spec declares
+//! them, and a subsection per operation with its parameters and responses as
+//! tables. Operations with several tags appear under each; untagged ones are
+//! collected at the end. The result suits wikis that can't render OpenAPI.
+
+use crate::openapi::{OpenAPI, Operation, Parameter, PathItem, ReferenceOr, Response, Schema};
+use std::collections::BTreeMap;
+
+/// Heading for operations that have no tags
+const UNTAGGED: &str = "Other operations";
+
+/// The whole spec as Markdown, starting with the API title as a level-one heading
+pub fn to_markdown(spec: &OpenAPI) -> String {
+    let mut out = format!("# {} {}\n", spec.info.title, spec.info.version);
+    if let Some(description) = &spec.info.description {
+        out.push_str(&format!("\n{description}\n"));
+    }
+
+    for (tag, operations) in operations_by_tag(spec) {
+        out.push_str(&format!("\n## {tag}\n"));
+        let tag_description = spec.tags.iter().flatten().find(|t| t.name == tag).and_then(|t| t.description.as_deref());
+        if let Some(description) = tag_description {
+            out.push_str(&format!("\n{description}\n"));
+        }
+        for (method, path, operation) in operations {
+            out.push_str(&operation_section(spec, method, path, operation));
+        }
+    }
+    out
+}
+
+/// An operation with the method and path it is served at
+type Located<'a> = (&'static str, &'a str, &'a Operation);
+
+/// Operations grouped by tag: declared tags first, then the others by name, untagged last
+fn operations_by_tag(spec: &OpenAPI) -> Vec<(String, Vec<Located<'_>>)> {
+    let mut by_tag: BTreeMap<&str, Vec<Located<'_>>> = BTreeMap::new();
+    for (path, item) in &spec.paths {
+        for (method, operation) in methods(item) {
+            if operation.tags.is_empty() {
+                by_tag.entry(UNTAGGED).or_default().push((method, path, operation));
+            }
+            for tag in &operation.tags {
+                by_tag.entry(tag).or_default().push((method, path, operation));
+            }
+        }
+    }
+
+    let mut grouped = Vec::new();
+    for tag in spec.tags.iter().flatten() {
+        if let Some(operations) = by_tag.remove(tag.name.as_str()) {
+            grouped.push((tag.name.clone(), operations));
+        }
+    }
+    let untagged = by_tag.remove(UNTAGGED);
+    grouped.extend(by_tag.into_iter().map(|(tag, operations)| (tag.to_string(), operations)));
+    grouped.extend(untagged.map(|operations| (UNTAGGED.to_string(), operations)));
+    grouped
+}
+
+/// The operations on a path with their upper-case method, in serialization order
+fn methods(item: &PathItem) -> impl Iterator<Item = (&'static str, &Operation)> {
+    [
+        ("GET", &item.get),
+        ("POST", &item.post),
+        ("PUT", &item.put),
+        ("PATCH", &item.patch),
+        ("DELETE", &item.delete),
+        ("HEAD", &item.head),
+        ("OPTIONS", &item.options),
+        ("TRACE", &item.trace),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| Some((method, operation.as_ref()?)))
+}
+
+/// `### Summary`, the method and path, the description and the two tables
+fn operation_section(spec: &OpenAPI, method: &str, path: &str, operation: &Operation) -> String {
+    let title = operation.summary.as_deref().unwrap_or(path);
+    let mut out = format!("\n### {title}\n\n`{method} {path}`");
+    if operation.deprecated {
+        out.push_str(" (deprecated)");
+    }
+    out.push('\n');
+    if let Some(description) = operation.description.as_deref().filter(|description| *description != title) {
+        out.push_str(&format!("\n{description}\n"));
+    }
+
+    let parameters: Vec<&Parameter> = operation.parameters.iter().filter_map(|parameter| resolve_parameter(spec, parameter)).collect();
+    if !parameters.is_empty() {
+        out.push_str("\n**Parameters**\n\n| Name | In | Type | Required | Description |\n|------|----|------|----------|-------------|\n");
+        for parameter in parameters {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                parameter.name,
+                parameter.location,
+                cell(&schema_label(&parameter.schema)),
+                if parameter.required { "yes" } else { "no" },
+                cell(parameter.description.as_deref().unwrap_or_default())
+            ));
+        }
+    }
+
+    if !operation.responses.is_empty() {
+        out.push_str("\n**Responses**\n\n| Status | Description | Schema |\n|--------|-------------|--------|\n");
+        for (status, response) in &operation.responses {
+            let (description, schema) = match resolve_response(spec, response) {
+                Some(response) => (response.description.as_str(), response_schema(response)),
+                None => ("", String::new()),
+            };
+            out.push_str(&format!("| {status} | {} | {} |\n", cell(description), cell(&schema)));
+        }
+    }
+    out
+}
+
+/// A parameter, following a `$ref` into `components.parameters`
+fn resolve_parameter<'a>(spec: &'a OpenAPI, parameter: &'a ReferenceOr<Parameter>) -> Option<&'a Parameter> {
+    match parameter {
+        ReferenceOr::Item(parameter) => Some(parameter),
+        ReferenceOr::Reference { reference } => {
+            let name = reference.strip_prefix("#/components/parameters/")?;
+            spec.components.as_ref()?.parameters.as_ref()?.get(name)?.as_item()
+        }
+    }
+}
+
+/// A response, following a `$ref` into `components.responses`
+fn resolve_response<'a>(spec: &'a OpenAPI, response: &'a ReferenceOr<Response>) -> Option<&'a Response> {
+    match response {
+        ReferenceOr::Item(response) => Some(response),
+        ReferenceOr::Reference { reference } => {
+            let name = reference.strip_prefix("#/components/responses/")?;
+            spec.components.as_ref()?.responses.as_ref()?.get(name)?.as_item()
+        }
+    }
+}
+
+/// The schema of a response's first media type, e.g. "`User`" or "array of `User`"
+fn response_schema(response: &Response) -> String {
+    response
+        .content
+        .iter()
+        .flat_map(|content| content.values())
+        .find_map(|media_type| media_type.schema.as_ref())
+        .map(schema_label)
+        .unwrap_or_default()
+}
+
+/// Short description of a schema: a referenced name in backticks, or its type
+fn schema_label(schema: &ReferenceOr<Schema>) -> String {
+    match schema {
+        ReferenceOr::Reference { reference } => format!("`{}`", reference.rsplit('/').next().unwrap_or(reference)),
+        ReferenceOr::Item(schema) => match (schema.schema_type.as_deref(), &schema.items) {
+            (Some("array"), Some(items)) => format!("array of {}", schema_label(items)),
+            (Some(schema_type), _) => match &schema.format {
+                Some(format) => format!("{schema_type} ({format})"),
+                None => schema_type.to_string(),
+            },
+            (None, _) => String::new(),
+        },
+    }
+}
+
+/// Text made safe for a table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
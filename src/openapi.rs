@@ -15,6 +15,7 @@
 //! - [`License`] - License information for the API
 //! - [`ExternalDocs`] - External documentation references
 //! - [`Tag`] - API tags with optional external documentation
+//! - [`Server`] - Server URLs the API is reachable at
 //! - [`PathItem`] - Path definitions with all HTTP methods
 //! - [`Operation`] - Operation/endpoint definitions
 //! - [`Parameter`] - Request parameters (path, query, header, cookie)
@@ -34,6 +35,7 @@
 //! 2. **camelCase fields**: Uses `#[serde(rename_all = "camelCase")]` where needed
 //!    - `Info::terms_of_service` → `"termsOfService"`
 //!    - `Tag::external_docs` → `"externalDocs"`
+//!    - `Operation::external_docs` → `"externalDocs"`
 //!    - `Operation::request_body` → `"requestBody"`
 //!    - `SecurityScheme::bearer_format` → `"bearerFormat"`
 //! 3. **Optional field handling**: Uses `#[serde(skip_serializing_if = "Option::is_none")]`
@@ -49,7 +51,7 @@
 //!
 //! ```rust
 //! use machined_openapi_gen::openapi::*;
-//! use std::collections::HashMap;
+//! use std::collections::BTreeMap;
 //!
 //! let openapi = OpenAPI {
 //!     openapi: "3.0.0".to_string(),
@@ -67,9 +69,16 @@
 //!             name: "MIT".to_string(),
 //!             url: Some("https://opensource.org/licenses/MIT".to_string()),
 //!         }),
+//!         extensions: BTreeMap::new(),
 //!     },
-//!     paths: HashMap::new(),
+//!     servers: vec![Server {
+//!         url: "https://api.example.com".to_string(),
+//!         description: Some("Production".to_string()),
+//!         variables: None,
+//!     }],
+//!     paths: BTreeMap::new(),
 //!     components: None,
+//!     security: None,
 //!     tags: None,
 //! };
 //!
@@ -81,7 +90,7 @@
 //!
 //! - ✅ Step 1: Type system foundation (COMPLETE)
 //! - ⏳ Step 2: Migrate helper functions to return types
-//! - ⏳ Step 3: Update main `openapi_json()` method
+//! - ✅ Step 3: Update main `openapi_json()` method
 //! - ⏳ Step 4: Add integration tests
 //! - ⏳ Step 5: YAML generation support
 //!
@@ -96,7 +105,7 @@
 //! Run tests with: `cargo test openapi::`
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// A type that can be either a reference to a component or an inline definition.
 /// This is used throughout OpenAPI for schemas, parameters, responses, etc.
@@ -152,9 +161,14 @@ impl<T> ReferenceOr<T> {
 pub struct OpenAPI {
     pub openapi: String,
     pub info: Info,
-    pub paths: HashMap<String, PathItem>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub servers: Vec<Server>,
+    pub paths: BTreeMap<String, PathItem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
+    /// Security requirements for every operation that doesn't declare its own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<BTreeMap<String, Vec<String>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<Tag>>,
 }
@@ -168,6 +182,27 @@ pub struct Tag {
     pub external_docs: Option<ExternalDocs>,
 }
 
+/// Server hosting the API, e.g. a production or staging base URL
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Server {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, ServerVariable>>,
+}
+
+/// Substitution value for a `{name}` placeholder in a server URL
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServerVariable {
+    pub default: String,
+    /// The only values the variable may take, `default` among them
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
 /// External documentation reference
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExternalDocs {
@@ -187,9 +222,12 @@ impl OpenAPI {
                 terms_of_service: None,
                 contact: None,
                 license: None,
+                extensions: BTreeMap::new(),
             },
-            paths: HashMap::new(),
+            servers: Vec::new(),
+            paths: BTreeMap::new(),
             components: None,
+            security: None,
             tags: None,
         }
     }
@@ -201,6 +239,140 @@ impl OpenAPI {
     pub fn to_json_compact(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
+
+    /// Serialize the document as YAML
+    ///
+    /// Field order matches the JSON output. Strings are written as
+    /// double-quoted scalars, which YAML reads with the same escaping rules as JSON.
+    pub fn to_yaml(&self) -> String {
+        let mut yaml = String::new();
+        if let Ok(node) = self.to_json_compact().and_then(|json| serde_json::from_str::<YamlNode>(&json)) {
+            node.write_block(&mut yaml, 0);
+        }
+        yaml
+    }
+}
+
+/// Order-preserving document tree used to emit YAML
+enum YamlNode {
+    /// Scalar already rendered in its YAML form
+    Scalar(String),
+    Seq(Vec<YamlNode>),
+    Map(Vec<(String, YamlNode)>),
+}
+
+impl<'de> Deserialize<'de> for YamlNode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NodeVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for NodeVisitor {
+            type Value = YamlNode;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("any JSON value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<YamlNode, E> {
+                Ok(YamlNode::Scalar(v.to_string()))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<YamlNode, E> {
+                Ok(YamlNode::Scalar(v.to_string()))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<YamlNode, E> {
+                Ok(YamlNode::Scalar(v.to_string()))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<YamlNode, E> {
+                Ok(YamlNode::Scalar(v.to_string()))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<YamlNode, E> {
+                Ok(YamlNode::Scalar(serde_json::to_string(v).unwrap_or_default()))
+            }
+
+            fn visit_unit<E>(self) -> Result<YamlNode, E> {
+                Ok(YamlNode::Scalar("null".to_string()))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<YamlNode, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(YamlNode::Seq(items))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<YamlNode, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(YamlNode::Map(entries))
+            }
+        }
+
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+impl YamlNode {
+    /// Inline form of a scalar or empty collection, `None` for nested blocks
+    fn inline(&self) -> Option<String> {
+        match self {
+            YamlNode::Scalar(scalar) => Some(scalar.clone()),
+            YamlNode::Seq(items) if items.is_empty() => Some("[]".to_string()),
+            YamlNode::Map(entries) if entries.is_empty() => Some("{}".to_string()),
+            _ => None,
+        }
+    }
+
+    fn write_block(&self, yaml: &mut String, indent: usize) {
+        let pad = " ".repeat(indent);
+        match self {
+            YamlNode::Map(entries) if !entries.is_empty() => {
+                for (key, item) in entries {
+                    yaml.push_str(&format!("{pad}{}:", yaml_key(key)));
+                    item.write_child(yaml, indent);
+                }
+            }
+            YamlNode::Seq(items) if !items.is_empty() => {
+                for item in items {
+                    yaml.push_str(&format!("{pad}-"));
+                    item.write_child(yaml, indent);
+                }
+            }
+            node => yaml.push_str(&format!("{pad}{}\n", node.inline().unwrap_or_default())),
+        }
+    }
+
+    /// Write a value following a `key:` or `-` marker
+    fn write_child(&self, yaml: &mut String, indent: usize) {
+        match self.inline() {
+            Some(scalar) => yaml.push_str(&format!(" {scalar}\n")),
+            None => {
+                yaml.push('\n');
+                self.write_block(yaml, indent + 2);
+            }
+        }
+    }
+}
+
+/// Mapping key, quoted unless it is a plain identifier-like word
+fn yaml_key(key: &str) -> String {
+    let plain = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !matches!(
+            key.to_ascii_lowercase().as_str(),
+            "true" | "false" | "null" | "yes" | "no" | "on" | "off"
+        );
+
+    if plain {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -216,6 +388,9 @@ pub struct Info {
     pub contact: Option<Contact>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<License>,
+    /// Vendor extensions such as `x-logo`, flattened into the info object
+    #[serde(flatten, default)]
+    pub extensions: BTreeMap<String, serde_json::Value>,
 }
 
 /// Contact information for the API
@@ -247,13 +422,31 @@ pub struct PathItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub put: Option<Operation>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub delete: Option<Operation>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub patch: Option<Operation>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub head: Option<Operation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Operation>,
+}
+
+impl PathItem {
+    /// Iterate over the operations defined on this path, in the order they serialize
+    pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        [&self.get, &self.post, &self.put, &self.patch, &self.delete, &self.head, &self.options, &self.trace]
+            .into_iter()
+            .flatten()
+    }
+
+    /// Iterate mutably over the operations defined on this path
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = &mut Operation> {
+        [&mut self.get, &mut self.post, &mut self.put, &mut self.patch, &mut self.delete, &mut self.head, &mut self.options, &mut self.trace]
+            .into_iter()
+            .flatten()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -263,17 +456,33 @@ pub struct Operation {
     pub summary: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Link to a guide or reference page for this endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocs>,
+    /// Unique name for the operation, taken from the handler function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "x-handler-function")]
     pub handler_function: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub parameters: Vec<Parameter>,
+    pub parameters: Vec<ReferenceOr<Parameter>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_body: Option<RequestBody>,
-    pub responses: HashMap<String, Response>,
+    pub responses: BTreeMap<String, ReferenceOr<Response>>,
+    /// Out-of-band requests the API makes in response to this operation, keyed by
+    /// callback name and then by the runtime expression for the callback URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callbacks: Option<BTreeMap<String, BTreeMap<String, PathItem>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub security: Option<Vec<HashMap<String, Vec<String>>>>,
+    pub security: Option<Vec<BTreeMap<String, Vec<String>>>>,
+    /// Clients should stop using the operation; only written out when true
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub deprecated: bool,
+    /// `x-` properties from the handler's "# Extensions" doc section
+    #[serde(flatten, default)]
+    pub extensions: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -284,22 +493,59 @@ pub struct Parameter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub required: bool,
+    /// Set by a `[deprecated]` flag in the parameter's doc line; only written out when true
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub deprecated: bool,
+    /// How array and object values are serialized, e.g. "form" or "pipeDelimited"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// Whether array items and object properties become separate parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
     pub schema: ReferenceOr<Schema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RequestBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    pub content: HashMap<String, MediaType>,
+    pub content: BTreeMap<String, MediaType>,
     pub required: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Response {
     pub description: String,
+    /// Headers sent with the response, keyed by header name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, Header>>,
+    /// Operations a client can follow from this response, keyed by link name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<BTreeMap<String, Link>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<BTreeMap<String, MediaType>>,
+}
+
+/// A design-time link from a response to another operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Link {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// Values passed to the linked operation's parameters, usually runtime expressions such as `$response.body#/id`
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<HashMap<String, MediaType>>,
+    pub parameters: Option<BTreeMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Header {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub schema: ReferenceOr<Schema>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -310,10 +556,16 @@ pub struct MediaType {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Components {
-    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
-    pub schemas: HashMap<String, ReferenceOr<Schema>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub schemas: BTreeMap<String, ReferenceOr<Schema>>,
+    /// Parameters shared between operations, referenced as `#/components/parameters/{name}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<BTreeMap<String, ReferenceOr<Parameter>>>,
+    /// Responses shared between operations, referenced as `#/components/responses/{name}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responses: Option<BTreeMap<String, ReferenceOr<Response>>>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "securitySchemes")]
-    pub security_schemes: Option<HashMap<String, SecurityScheme>>,
+    pub security_schemes: Option<BTreeMap<String, SecurityScheme>>,
 }
 
 /// Security scheme definition for API authentication
@@ -412,32 +664,131 @@ impl SecurityScheme {
 pub struct Schema {
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub schema_type: Option<String>,
+    /// Data type refinement such as `int64`, `double` or `uuid`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub properties: Option<HashMap<String, ReferenceOr<Schema>>>,
+    pub properties: Option<BTreeMap<String, ReferenceOr<Schema>>>,
+    /// Schema of each element when `type` is `array`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<ReferenceOr<Schema>>>,
+    /// Schema of every value in a map-like object, e.g. `HashMap<String, T>`
+    #[serde(rename = "additionalProperties", skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<Box<ReferenceOr<Schema>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<Vec<String>>,
+    /// Allowed values, used for string enums and tag constants
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<serde_json::Value>>,
+    #[serde(rename = "oneOf", skip_serializing_if = "Option::is_none")]
+    pub one_of: Option<Vec<ReferenceOr<Schema>>>,
+    /// Schemas this one is composed of, used for `#[serde(flatten)]` fields
+    #[serde(rename = "allOf", skip_serializing_if = "Option::is_none")]
+    pub all_of: Option<Vec<ReferenceOr<Schema>>>,
+    /// Property that tells the `oneOf` alternatives apart, for tagged enums
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Discriminator>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Lower bound for numeric values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    /// Upper bound for numeric values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    /// Whether `minimum` itself is excluded
+    #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
+    pub exclusive_minimum: Option<bool>,
+    /// Whether `maximum` itself is excluded
+    #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
+    pub exclusive_maximum: Option<bool>,
+    /// Minimum length for string values
+    #[serde(rename = "minLength", skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u64>,
+    /// Maximum length for string values
+    #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u64>,
+    /// Regular expression string values must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Minimum number of array items
+    #[serde(rename = "minItems", skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<u64>,
+    /// Maximum number of array items
+    #[serde(rename = "maxItems", skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<u64>,
+    /// Whether array items must be distinct
+    #[serde(rename = "uniqueItems", skip_serializing_if = "Option::is_none")]
+    pub unique_items: Option<bool>,
+    /// Whether `null` is allowed as well as values of `type`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    /// Sent in responses only, e.g. a server-assigned id
+    #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    /// Sent in requests only, e.g. a password
+    #[serde(rename = "writeOnly", skip_serializing_if = "Option::is_none")]
+    pub write_only: Option<bool>,
     /// Reference to another schema (alternative to using ReferenceOr wrapper)
     #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
     pub reference: Option<String>,
+    /// `x-` properties, e.g. from `#[extension(...)]` on a derived type
+    #[serde(flatten, default)]
+    pub extensions: BTreeMap<String, serde_json::Value>,
 }
 
 impl Default for Schema {
     fn default() -> Self {
         Self {
             schema_type: Some("object".to_string()),
+            format: None,
             title: None,
             description: None,
             properties: None,
+            items: None,
+            additional_properties: None,
             required: None,
+            enum_values: None,
+            one_of: None,
+            all_of: None,
+            discriminator: None,
+            example: None,
+            default: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            min_items: None,
+            max_items: None,
+            unique_items: None,
+            nullable: None,
+            read_only: None,
+            write_only: None,
             reference: None,
+            extensions: BTreeMap::new(),
         }
     }
 }
 
+/// Names the property whose value selects one of a schema's `oneOf` alternatives
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Discriminator {
+    pub property_name: String,
+    /// Property values mapped to the `$ref` of the schema they select
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<BTreeMap<String, String>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -514,7 +865,7 @@ mod tests {
 
     #[test]
     fn test_components_with_security_schemes() {
-        let mut security_schemes = HashMap::new();
+        let mut security_schemes = BTreeMap::new();
         security_schemes.insert(
             "sessionAuth".to_string(),
             SecurityScheme::api_key("x-session-secret", "header")
@@ -522,7 +873,9 @@ mod tests {
         );
         
         let components = Components {
-            schemas: HashMap::new(),
+            schemas: BTreeMap::new(),
+            parameters: None,
+            responses: None,
             security_schemes: Some(security_schemes),
         };
         
@@ -537,7 +890,9 @@ mod tests {
     #[test]
     fn test_components_without_security_schemes() {
         let components = Components {
-            schemas: HashMap::new(),
+            schemas: BTreeMap::new(),
+            parameters: None,
+            responses: None,
             security_schemes: None,
         };
         
@@ -563,14 +918,16 @@ mod tests {
 
     #[test]
     fn test_openapi_with_security_schemes() {
-        let mut security_schemes = HashMap::new();
+        let mut security_schemes = BTreeMap::new();
         security_schemes.insert(
             "bearerAuth".to_string(),
             SecurityScheme::bearer(Some("JWT")),
         );
         
         let components = Components {
-            schemas: HashMap::new(),
+            schemas: BTreeMap::new(),
+            parameters: None,
+            responses: None,
             security_schemes: Some(security_schemes),
         };
         
@@ -583,9 +940,12 @@ mod tests {
                 terms_of_service: None,
                 contact: None,
                 license: None,
+                extensions: BTreeMap::new(),
             },
-            paths: HashMap::new(),
+            servers: Vec::new(),
+            paths: BTreeMap::new(),
             components: Some(components),
+            security: None,
             tags: None,
         };
         
@@ -741,6 +1101,7 @@ mod tests {
                 name: "MIT".to_string(),
                 url: Some("https://opensource.org/licenses/MIT".to_string()),
             }),
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_value(&info).unwrap();
@@ -762,6 +1123,7 @@ mod tests {
             terms_of_service: None,
             contact: None,
             license: None,
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_value(&info).unwrap();
@@ -783,6 +1145,7 @@ mod tests {
             terms_of_service: Some("https://example.com/tos".to_string()),
             contact: None,
             license: None,
+            extensions: BTreeMap::new(),
         };
         
         let json_str = serde_json::to_string(&info).unwrap();
@@ -809,9 +1172,12 @@ mod tests {
                     name: "Apache 2.0".to_string(),
                     url: Some("https://www.apache.org/licenses/LICENSE-2.0.html".to_string()),
                 }),
+                extensions: BTreeMap::new(),
             },
-            paths: HashMap::new(),
+            servers: Vec::new(),
+            paths: BTreeMap::new(),
             components: None,
+            security: None,
             tags: None,
         };
         
@@ -847,12 +1213,16 @@ mod tests {
                     name: "MIT".to_string(),
                     url: Some("https://opensource.org/licenses/MIT".to_string()),
                 }),
+                extensions: BTreeMap::new(),
             },
-            paths: HashMap::new(),
+            servers: Vec::new(),
+            paths: BTreeMap::new(),
             components: Some(Components {
-                schemas: HashMap::new(),
+                schemas: BTreeMap::new(),
+                parameters: None,
+                responses: None,
                 security_schemes: Some({
-                    let mut schemes = HashMap::new();
+                    let mut schemes = BTreeMap::new();
                     schemes.insert(
                         "sessionAuth".to_string(),
                         SecurityScheme::api_key("x-session-secret", "header")
@@ -861,6 +1231,7 @@ mod tests {
                     schemes
                 }),
             }),
+            security: None,
             tags: Some(vec![
                 Tag {
                     name: "users".to_string(),
@@ -932,4 +1303,102 @@ mod tests {
         // ✅ All fields use proper camelCase conventions
         // ✅ Optional fields are omitted when None
     }
+
+    #[test]
+    fn test_server_serialization() {
+        let mut variables = BTreeMap::new();
+        variables.insert(
+            "region".to_string(),
+            ServerVariable {
+                default: "us-east".to_string(),
+                enum_values: None,
+                description: Some("Deployment region".to_string()),
+            },
+        );
+
+        let server = Server {
+            url: "https://{region}.example.com".to_string(),
+            description: Some("Regional endpoint".to_string()),
+            variables: Some(variables),
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&server).unwrap();
+        assert_eq!(json["url"], "https://{region}.example.com");
+        assert_eq!(json["description"], "Regional endpoint");
+        assert_eq!(json["variables"]["region"]["default"], "us-east");
+        assert_eq!(json["variables"]["region"]["description"], "Deployment region");
+
+        let plain = Server {
+            url: "/".to_string(),
+            description: None,
+            variables: None,
+        };
+        let json: serde_json::Value = serde_json::to_value(&plain).unwrap();
+        assert!(!json.as_object().unwrap().contains_key("description"));
+        assert!(!json.as_object().unwrap().contains_key("variables"));
+    }
+
+    #[test]
+    fn test_server_variable_enum_serialization() {
+        let server = Server {
+            url: "https://{tenant}.api.example.com/{version}".to_string(),
+            description: None,
+            variables: Some(BTreeMap::from([
+                ("tenant".to_string(), ServerVariable {
+                    default: "demo".to_string(),
+                    enum_values: None,
+                    description: Some("Customer subdomain".to_string()),
+                }),
+                ("version".to_string(), ServerVariable {
+                    default: "v2".to_string(),
+                    enum_values: Some(vec!["v1".to_string(), "v2".to_string()]),
+                    description: None,
+                }),
+            ])),
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&server).unwrap();
+        assert_eq!(json["variables"]["version"], serde_json::json!({"default": "v2", "enum": ["v1", "v2"]}));
+        assert!(json["variables"]["tenant"].get("enum").is_none());
+        assert_eq!(serde_json::from_value::<Server>(json).unwrap(), server);
+    }
+
+    #[test]
+    fn test_servers_omitted_when_empty() {
+        let mut openapi = OpenAPI::new("Test API", "1.0.0");
+        let json: serde_json::Value = serde_json::from_str(&openapi.to_json_compact().unwrap()).unwrap();
+        assert!(!json.as_object().unwrap().contains_key("servers"));
+
+        openapi.servers.push(Server {
+            url: "https://api.example.com".to_string(),
+            description: None,
+            variables: None,
+        });
+        let json: serde_json::Value = serde_json::from_str(&openapi.to_json_compact().unwrap()).unwrap();
+        assert_eq!(json["servers"][0]["url"], "https://api.example.com");
+
+        // Documents without a servers key still deserialize
+        let parsed: OpenAPI = serde_json::from_str(
+            r#"{"openapi":"3.0.0","info":{"title":"T","version":"1"},"paths":{}}"#,
+        ).unwrap();
+        assert!(parsed.servers.is_empty());
+    }
+
+    #[test]
+    fn test_to_yaml() {
+        let mut openapi = OpenAPI::new("Pet \"Store\"", "1.0.0");
+        openapi.servers.push(Server {
+            url: "https://api.example.com".to_string(),
+            description: None,
+            variables: None,
+        });
+        openapi.paths.insert("/pets/{id}".to_string(), PathItem::default());
+
+        let yaml = openapi.to_yaml();
+
+        assert!(yaml.starts_with("openapi: \"3.0.0\"\n"));
+        assert!(yaml.contains("info:\n  title: \"Pet \\\"Store\\\"\"\n  version: \"1.0.0\"\n"));
+        assert!(yaml.contains("servers:\n  -\n    url: \"https://api.example.com\"\n"));
+        assert!(yaml.contains("paths:\n  \"/pets/{id}\": {}\n"));
+    }
 }
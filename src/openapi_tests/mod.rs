@@ -4,7 +4,7 @@
 mod tests {
     use crate::openapi::*;
 
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
 
     // ============================================================================
     // Basic OpenAPI Document Tests
@@ -67,6 +67,7 @@ mod tests {
             terms_of_service: None,
             contact: None,
             license: None,
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_string(&info).unwrap();
@@ -86,6 +87,7 @@ mod tests {
             terms_of_service: None,
             contact: None,
             license: None,
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_string(&info).unwrap();
@@ -96,6 +98,29 @@ mod tests {
         assert!(parsed.get("description").is_none());
     }
 
+    #[test]
+    fn test_info_extension_roundtrip() {
+        let info = Info {
+            title: "My API".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            terms_of_service: None,
+            contact: None,
+            license: None,
+            extensions: BTreeMap::from([(
+                "x-logo".to_string(),
+                serde_json::json!({"url": "https://example.com/logo.png"}),
+            )]),
+        };
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.contains(r#""x-logo":{"url":"https://example.com/logo.png"}"#));
+        assert!(!json.contains("extensions"));
+
+        let parsed: Info = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, info);
+    }
+
     #[test]
     fn test_info_deserialization() {
         let json_str = r#"{
@@ -125,6 +150,7 @@ mod tests {
             patch: None,
             head: None,
             options: None,
+            trace: None,
         };
         
         let json = serde_json::to_string(&path_item).unwrap();
@@ -138,12 +164,17 @@ mod tests {
         let operation = Operation {
             summary: Some("Get items".to_string()),
             description: None,
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
-            responses: HashMap::new(),
+            responses: BTreeMap::new(),
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let path_item = PathItem {
@@ -154,6 +185,7 @@ mod tests {
             patch: None,
             head: None,
             options: None,
+            trace: None,
         };
         
         let json = serde_json::to_string(&path_item).unwrap();
@@ -169,12 +201,17 @@ mod tests {
         let operation = Operation {
             summary: Some("Test".to_string()),
             description: None,
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
-            responses: HashMap::new(),
+            responses: BTreeMap::new(),
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let path_item = PathItem {
@@ -185,6 +222,7 @@ mod tests {
             patch: Some(operation),
             head: None,
             options: None,
+            trace: None,
         };
         
         let json = serde_json::to_string(&path_item).unwrap();
@@ -197,6 +235,37 @@ mod tests {
         assert!(json.contains(r#""patch""#));
     }
 
+    #[test]
+    fn test_path_item_operations() {
+        let operation = |summary: &str| Operation {
+            summary: Some(summary.to_string()),
+            description: None,
+            external_docs: None,
+            operation_id: None,
+            handler_function: None,
+            tags: vec![],
+            parameters: vec![],
+            request_body: None,
+            responses: BTreeMap::new(),
+            callbacks: None,
+            security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
+        };
+
+        let path_item = PathItem {
+            get: Some(operation("List")),
+            delete: Some(operation("Remove")),
+            trace: Some(operation("Echo")),
+            ..Default::default()
+        };
+
+        let summaries: Vec<_> = path_item.operations().map(|op| op.summary.as_deref().unwrap()).collect();
+        assert_eq!(summaries, ["List", "Remove", "Echo"]);
+        assert!(serde_json::to_value(&path_item).unwrap()["trace"].is_object());
+        assert_eq!(PathItem::default().operations().count(), 0);
+    }
+
     // ============================================================================
     // Operation Tests
     // ============================================================================
@@ -206,12 +275,17 @@ mod tests {
         let operation = Operation {
             summary: None,
             description: None,
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
-            responses: HashMap::new(),
+            responses: BTreeMap::new(),
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_string(&operation).unwrap();
@@ -229,12 +303,17 @@ mod tests {
         let operation = Operation {
             summary: Some("Get user by ID".to_string()),
             description: Some("Retrieves a user's information".to_string()),
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
-            responses: HashMap::new(),
+            responses: BTreeMap::new(),
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_string(&operation).unwrap();
@@ -246,25 +325,32 @@ mod tests {
 
     #[test]
     fn test_operation_camel_case_fields() {
-        let mut responses = HashMap::new();
-        responses.insert("200".to_string(), Response {
+        let mut responses = BTreeMap::new();
+        responses.insert("200".to_string(), ReferenceOr::new_item(Response {
             description: "Success".to_string(),
+            headers: None,
+            links: None,
             content: None,
-        });
+        }));
         
         let operation = Operation {
             summary: None,
             description: None,
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: Some(RequestBody {
                 description: None,
-                content: HashMap::new(),
+                content: BTreeMap::new(),
                 required: true,
             }),
             responses,
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let json = serde_json::to_string(&operation).unwrap();
@@ -274,6 +360,159 @@ mod tests {
         assert!(!json.contains(r#""request_body""#));
     }
 
+    #[test]
+    fn test_operation_id_serialization() {
+        let operation = Operation {
+            summary: None,
+            description: None,
+            external_docs: None,
+            operation_id: Some("get_user".to_string()),
+            handler_function: None,
+            tags: vec![],
+            parameters: vec![],
+            request_body: None,
+            responses: BTreeMap::new(),
+            callbacks: None,
+            security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
+        };
+
+        let json = serde_json::to_string(&operation).unwrap();
+        assert!(json.contains(r#""operationId":"get_user""#));
+
+        let parsed: Operation = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, operation);
+    }
+
+    #[test]
+    fn test_operation_deprecated_serialization() {
+        let mut operation = Operation {
+            summary: None,
+            description: None,
+            external_docs: None,
+            operation_id: Some("get_user_v1".to_string()),
+            handler_function: None,
+            tags: vec![],
+            parameters: vec![],
+            request_body: None,
+            responses: BTreeMap::new(),
+            callbacks: None,
+            security: None,
+            deprecated: true,
+            extensions: BTreeMap::new(),
+        };
+
+        let json = serde_json::to_string(&operation).unwrap();
+        assert!(json.contains(r#""deprecated":true"#));
+        assert_eq!(serde_json::from_str::<Operation>(&json).unwrap(), operation);
+
+        operation.deprecated = false;
+        assert!(!serde_json::to_string(&operation).unwrap().contains("deprecated"));
+    }
+
+    #[test]
+    fn test_operation_external_docs_serialization() {
+        let mut operation = Operation {
+            summary: None,
+            description: None,
+            external_docs: Some(ExternalDocs {
+                url: "https://example.com/guides/pagination".to_string(),
+                description: Some("Pagination guide".to_string()),
+            }),
+            operation_id: Some("list_users".to_string()),
+            handler_function: None,
+            tags: vec![],
+            parameters: vec![],
+            request_body: None,
+            responses: BTreeMap::new(),
+            callbacks: None,
+            security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
+        };
+
+        let json = serde_json::to_value(&operation).unwrap();
+        assert_eq!(json["externalDocs"]["url"], "https://example.com/guides/pagination");
+        assert_eq!(json["externalDocs"]["description"], "Pagination guide");
+        assert_eq!(serde_json::from_value::<Operation>(json).unwrap(), operation);
+
+        operation.external_docs = None;
+        assert!(!serde_json::to_string(&operation).unwrap().contains("externalDocs"));
+    }
+
+    #[test]
+    fn test_operation_extension_roundtrip() {
+        let operation = Operation {
+            summary: None,
+            description: None,
+            external_docs: None,
+            operation_id: None,
+            handler_function: Some("list_users".to_string()),
+            tags: vec![],
+            parameters: vec![],
+            request_body: None,
+            responses: BTreeMap::new(),
+            callbacks: None,
+            security: None,
+            deprecated: false,
+            extensions: BTreeMap::from([("x-internal".to_string(), serde_json::json!(true))]),
+        };
+
+        let json = serde_json::to_string(&operation).unwrap();
+        assert!(json.contains(r#""x-internal":true"#));
+
+        // The typed x-handler-function field isn't swallowed by the extensions
+        let parsed: Operation = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, operation);
+    }
+
+    #[test]
+    fn test_operation_with_callback_serialization() {
+        let delivery = Operation {
+            summary: None,
+            description: Some("Event delivered to the subscriber".to_string()),
+            external_docs: None,
+            operation_id: None,
+            handler_function: None,
+            tags: vec![],
+            parameters: vec![],
+            request_body: None,
+            responses: BTreeMap::from([(
+                "200".to_string(),
+                ReferenceOr::new_item(Response {
+                    description: "Delivery acknowledged".to_string(),
+                    headers: None,
+                    links: None,
+                    content: None,
+                }),
+            )]),
+            callbacks: None,
+            security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
+        };
+        let mut operation = delivery.clone();
+        operation.description = Some("Create a subscription".to_string());
+        operation.callbacks = Some(BTreeMap::from([(
+            "onEvent".to_string(),
+            BTreeMap::from([(
+                "{$request.body#/callbackUrl}".to_string(),
+                PathItem { post: Some(delivery), ..Default::default() },
+            )]),
+        )]));
+
+        let parsed = serde_json::to_value(&operation).unwrap();
+        let callback = &parsed["callbacks"]["onEvent"]["{$request.body#/callbackUrl}"];
+        assert_eq!(callback["post"]["description"], "Event delivered to the subscriber");
+        assert_eq!(callback["post"]["responses"]["200"]["description"], "Delivery acknowledged");
+        assert!(callback.get("get").is_none());
+        assert!(callback["post"].get("callbacks").is_none());
+
+        let deserialized: Operation = serde_json::from_value(parsed).unwrap();
+        assert_eq!(deserialized, operation);
+    }
+
     // ============================================================================
     // Parameter Tests
     // ============================================================================
@@ -287,6 +526,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         };
         
         let parameter = Parameter {
@@ -294,7 +534,11 @@ mod tests {
             location: "path".to_string(),
             description: Some("The user ID".to_string()),
             required: true,
+            deprecated: false,
+            style: None,
+            explode: None,
             schema: ReferenceOr::new_item(schema),
+            example: None,
         };
         
         let json = serde_json::to_string(&parameter).unwrap();
@@ -329,6 +573,39 @@ mod tests {
         assert_eq!(parameter.schema.as_item().unwrap().schema_type, Some("integer".to_string()));
     }
 
+    #[test]
+    fn test_array_parameter_style_serialization() {
+        let mut parameter = Parameter {
+            name: "ids".to_string(),
+            location: "query".to_string(),
+            description: None,
+            required: false,
+            deprecated: false,
+            style: Some("form".to_string()),
+            explode: Some(true),
+            schema: ReferenceOr::new_item(Schema {
+                schema_type: Some("array".to_string()),
+                items: Some(Box::new(ReferenceOr::new_item(Schema {
+                    schema_type: Some("integer".to_string()),
+                    ..Default::default()
+                }))),
+                ..Default::default()
+            }),
+            example: None,
+        };
+
+        let json = serde_json::to_value(&parameter).unwrap();
+        assert_eq!(json["style"], "form");
+        assert_eq!(json["explode"], true);
+        assert_eq!(serde_json::from_value::<Parameter>(json).unwrap(), parameter);
+
+        parameter.style = Some("pipeDelimited".to_string());
+        parameter.explode = None;
+        let json = serde_json::to_value(&parameter).unwrap();
+        assert_eq!(json["style"], "pipeDelimited");
+        assert!(json.get("explode").is_none());
+    }
+
     // ============================================================================
     // RequestBody Tests
     // ============================================================================
@@ -342,9 +619,10 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         };
         
-        let mut content = HashMap::new();
+        let mut content = BTreeMap::new();
         content.insert("application/json".to_string(), MediaType {
             schema: Some(ReferenceOr::new_item(schema)),
         });
@@ -392,6 +670,8 @@ mod tests {
     fn test_simple_response_serialization() {
         let response = Response {
             description: "Successful operation".to_string(),
+            headers: None,
+            links: None,
             content: None,
         };
         
@@ -411,15 +691,18 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         };
         
-        let mut content = HashMap::new();
+        let mut content = BTreeMap::new();
         content.insert("application/json".to_string(), MediaType {
             schema: Some(ReferenceOr::new_item(schema)),
         });
         
         let response = Response {
             description: "User retrieved".to_string(),
+            headers: None,
+            links: None,
             content: Some(content),
         };
         
@@ -430,6 +713,100 @@ mod tests {
         assert!(parsed["content"]["application/json"]["schema"]["type"] == "object");
     }
 
+    fn location_header_response() -> Response {
+        let mut headers = BTreeMap::new();
+        headers.insert("Location".to_string(), Header {
+            description: Some("URL of the created user".to_string()),
+            schema: ReferenceOr::new_item(Schema {
+                schema_type: Some("string".to_string()),
+                ..Default::default()
+            }),
+        });
+
+        Response {
+            description: "User created".to_string(),
+            headers: Some(headers),
+            links: None,
+            content: None,
+        }
+    }
+
+    #[test]
+    fn test_response_with_header_serialization() {
+        let json = serde_json::to_string(&location_header_response()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["headers"]["Location"]["description"], "URL of the created user");
+        assert_eq!(parsed["headers"]["Location"]["schema"]["type"], "string");
+        assert!(parsed.get("content").is_none());
+
+        // No headers means no headers key
+        let json = serde_json::to_string(&Response {
+            description: "OK".to_string(),
+            headers: None,
+            links: None,
+            content: None,
+        }).unwrap();
+        assert!(!json.contains("headers"));
+    }
+
+    #[test]
+    fn test_response_with_header_deserialization() {
+        let json_str = r#"{
+            "description": "User created",
+            "headers": {
+                "Location": {"description": "URL of the created user", "schema": {"type": "string"}}
+            }
+        }"#;
+
+        let response: Response = serde_json::from_str(json_str).unwrap();
+        let header = &response.headers.as_ref().unwrap()["Location"];
+        assert_eq!(header.description.as_deref(), Some("URL of the created user"));
+        assert_eq!(header.schema.as_item().unwrap().schema_type.as_deref(), Some("string"));
+    }
+
+    #[test]
+    fn test_response_with_header_roundtrip() {
+        let original = location_header_response();
+
+        let json = serde_json::to_string(&original).unwrap();
+        let deserialized: Response = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(original, deserialized);
+    }
+
+    #[test]
+    fn test_response_with_link_serialization() {
+        let mut links = BTreeMap::new();
+        links.insert("GetUserById".to_string(), Link {
+            operation_id: Some("getUser".to_string()),
+            parameters: Some(BTreeMap::from([(
+                "userId".to_string(),
+                serde_json::Value::String("$response.body#/id".to_string()),
+            )])),
+            description: Some("Fetch the created user".to_string()),
+        });
+        let response = Response {
+            description: "User created".to_string(),
+            headers: None,
+            links: Some(links),
+            content: None,
+        };
+
+        let parsed = serde_json::to_value(&response).unwrap();
+        let link = &parsed["links"]["GetUserById"];
+        assert_eq!(link["operationId"], "getUser");
+        assert_eq!(link["parameters"]["userId"], "$response.body#/id");
+        assert_eq!(link["description"], "Fetch the created user");
+
+        let deserialized: Response = serde_json::from_value(parsed).unwrap();
+        assert_eq!(deserialized, response);
+
+        // No links means no links key
+        let json = serde_json::to_string(&location_header_response()).unwrap();
+        assert!(!json.contains("links"));
+    }
+
     // ============================================================================
     // Schema Tests
     // ============================================================================
@@ -443,6 +820,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         };
         
         let json = serde_json::to_string(&schema).unwrap();
@@ -450,11 +828,61 @@ mod tests {
         
         assert_eq!(parsed["type"], "string");
         assert!(parsed.get("title").is_none());
+        assert!(parsed.get("format").is_none());
+    }
+
+    #[test]
+    fn test_schema_format_roundtrip() {
+        let json_str = r#"{"type": "integer", "format": "int64"}"#;
+
+        let schema: Schema = serde_json::from_str(json_str).unwrap();
+        assert_eq!(schema.format, Some("int64".to_string()));
+
+        let parsed: serde_json::Value = serde_json::to_value(&schema).unwrap();
+        assert_eq!(parsed["format"], "int64");
+    }
+
+    #[test]
+    fn test_schema_all_of_roundtrip() {
+        let json_str = r##"{
+            "allOf": [
+                {"$ref": "#/components/schemas/AuditInfo"},
+                {"type": "object", "properties": {"title": {"type": "string"}}}
+            ]
+        }"##;
+
+        let schema: Schema = serde_json::from_str(json_str).unwrap();
+        let all_of = schema.all_of.as_ref().unwrap();
+        assert_eq!(all_of.len(), 2);
+        assert_eq!(all_of[0].as_ref_str(), Some("#/components/schemas/AuditInfo"));
+
+        let parsed: serde_json::Value = serde_json::to_value(&schema).unwrap();
+        assert_eq!(parsed["allOf"][0]["$ref"], "#/components/schemas/AuditInfo");
+        assert_eq!(parsed["allOf"][1]["properties"]["title"]["type"], "string");
+    }
+
+    #[test]
+    fn test_schema_additional_properties_roundtrip() {
+        let json_str = r##"{
+            "type": "object",
+            "additionalProperties": {"$ref": "#/components/schemas/Setting"}
+        }"##;
+
+        let schema: Schema = serde_json::from_str(json_str).unwrap();
+        let values = schema.additional_properties.as_ref().unwrap();
+        assert_eq!(values.as_ref_str(), Some("#/components/schemas/Setting"));
+
+        let parsed: serde_json::Value = serde_json::to_value(&schema).unwrap();
+        assert_eq!(parsed["additionalProperties"]["$ref"], "#/components/schemas/Setting");
+
+        // Omitted when not set
+        let parsed = serde_json::to_value(Schema::default()).unwrap();
+        assert!(parsed.get("additionalProperties").is_none());
     }
 
     #[test]
     fn test_object_schema_with_properties() {
-        let mut properties = HashMap::new();
+        let mut properties = BTreeMap::new();
         properties.insert("id".to_string(), ReferenceOr::new_item(Schema {
             schema_type: Some("integer".to_string()),
             title: None,
@@ -462,6 +890,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         properties.insert("name".to_string(), ReferenceOr::new_item(Schema {
             schema_type: Some("string".to_string()),
@@ -470,6 +899,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         
         let schema = Schema {
@@ -479,6 +909,7 @@ mod tests {
             properties: Some(properties),
             required: Some(vec!["id".to_string(), "name".to_string()]),
             reference: None,
+            ..Default::default()
         };
         
         let json = serde_json::to_string(&schema).unwrap();
@@ -504,13 +935,73 @@ mod tests {
         assert_eq!(schema.required, None);
     }
 
+    #[test]
+    fn test_schema_extension_roundtrip() {
+        let schema = Schema {
+            schema_type: Some("string".to_string()),
+            extensions: BTreeMap::from([("x-internal".to_string(), serde_json::json!(true))]),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(json, r#"{"type":"string","x-internal":true}"#);
+
+        let parsed: Schema = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, schema);
+    }
+
+    #[test]
+    fn test_schema_read_only_write_only_roundtrip() {
+        let schema = Schema {
+            schema_type: Some("string".to_string()),
+            read_only: Some(true),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(json, r#"{"type":"string","readOnly":true}"#);
+        assert_eq!(serde_json::from_str::<Schema>(&json).unwrap(), schema);
+
+        // Parsed as fields rather than swept into the extensions map
+        let parsed: Schema = serde_json::from_str(r#"{"type":"string","writeOnly":true}"#).unwrap();
+        assert_eq!(parsed.write_only, Some(true));
+        assert!(parsed.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_schema_nullable_roundtrip() {
+        let schema = Schema {
+            schema_type: Some("string".to_string()),
+            nullable: Some(true),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(json, r#"{"type":"string","nullable":true}"#);
+        assert_eq!(serde_json::from_str::<Schema>(&json).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_schema_discriminator_roundtrip() {
+        let json = r##"{"oneOf":[{"$ref":"#/components/schemas/CardPayment"}],"discriminator":{"propertyName":"method","mapping":{"card":"#/components/schemas/CardPayment"}}}"##;
+
+        let schema: Schema = serde_json::from_str(json).unwrap();
+        let discriminator = schema.discriminator.as_ref().unwrap();
+        assert_eq!(discriminator.property_name, "method");
+        assert_eq!(discriminator.mapping.as_ref().unwrap()["card"], "#/components/schemas/CardPayment");
+        assert_eq!(serde_json::to_string(&schema).unwrap(), json);
+
+        let without_mapping = Discriminator { property_name: "kind".to_string(), mapping: None };
+        assert_eq!(serde_json::to_string(&without_mapping).unwrap(), r#"{"propertyName":"kind"}"#);
+    }
+
     // ============================================================================
     // Components Tests
     // ============================================================================
 
     #[test]
     fn test_components_serialization() {
-        let mut schemas = HashMap::new();
+        let mut schemas = BTreeMap::new();
         schemas.insert("User".to_string(), ReferenceOr::new_item(Schema {
             schema_type: Some("object".to_string()),
             title: None,
@@ -518,10 +1009,13 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         
         let components = Components { 
             schemas,
+            parameters: None,
+            responses: None,
             security_schemes: None,
         };
         
@@ -541,21 +1035,28 @@ mod tests {
         api.info.description = Some("A complete example".to_string());
         
         // Add a path with GET operation
-        let mut responses = HashMap::new();
-        responses.insert("200".to_string(), Response {
+        let mut responses = BTreeMap::new();
+        responses.insert("200".to_string(), ReferenceOr::new_item(Response {
             description: "Success".to_string(),
+            headers: None,
+            links: None,
             content: None,
-        });
+        }));
         
         let get_operation = Operation {
             summary: Some("List users".to_string()),
             description: Some("Returns a list of users".to_string()),
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
             responses,
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let path_item = PathItem {
@@ -566,12 +1067,13 @@ mod tests {
             patch: None,
             head: None,
             options: None,
+            trace: None,
         };
         
         api.paths.insert("/users".to_string(), path_item);
         
         // Add components
-        let mut schemas = HashMap::new();
+        let mut schemas = BTreeMap::new();
         schemas.insert("User".to_string(), ReferenceOr::new_item(Schema {
             schema_type: Some("object".to_string()),
             title: None,
@@ -579,10 +1081,13 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         
         api.components = Some(Components { 
             schemas,
+            parameters: None,
+            responses: None,
             security_schemes: None,
         });
         
@@ -643,25 +1148,34 @@ mod tests {
         let mut api = OpenAPI::new("Roundtrip Test", "3.0.0");
         api.info.description = Some("Testing roundtrip serialization".to_string());
         
-        let mut responses = HashMap::new();
-        responses.insert("200".to_string(), Response {
+        let mut responses = BTreeMap::new();
+        responses.insert("200".to_string(), ReferenceOr::new_item(Response {
             description: "Successful response".to_string(),
+            headers: None,
+            links: None,
             content: None,
-        });
-        responses.insert("404".to_string(), Response {
+        }));
+        responses.insert("404".to_string(), ReferenceOr::new_item(Response {
             description: "Not found".to_string(),
+            headers: None,
+            links: None,
             content: None,
-        });
+        }));
         
         let operation = Operation {
             summary: Some("Test operation".to_string()),
             description: Some("A test operation".to_string()),
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
             responses: responses.clone(),
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let path_item = PathItem {
@@ -672,6 +1186,7 @@ mod tests {
             patch: None,
             head: None,
             options: None,
+            trace: None,
         };
         
         api.paths.insert("/test".to_string(), path_item);
@@ -723,6 +1238,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         };
         
         let schema = ReferenceOr::new_item(inline_schema);
@@ -743,7 +1259,11 @@ mod tests {
             location: "path".to_string(),
             description: Some("User identifier".to_string()),
             required: true,
+            deprecated: false,
+            style: None,
+            explode: None,
             schema: ReferenceOr::new_ref("#/components/schemas/UserId"),
+            example: None,
         };
         
         let json = serde_json::to_string(&parameter).unwrap();
@@ -771,13 +1291,15 @@ mod tests {
     fn test_response_with_referenced_schema() {
         use crate::openapi::ReferenceOr;
         
-        let mut content = HashMap::new();
+        let mut content = BTreeMap::new();
         content.insert("application/json".to_string(), MediaType {
             schema: Some(ReferenceOr::new_ref("#/components/schemas/Error")),
         });
         
         let response = Response {
             description: "Error response".to_string(),
+            headers: None,
+            links: None,
             content: Some(content),
         };
         
@@ -792,7 +1314,7 @@ mod tests {
     fn test_components_with_schema_references() {
         use crate::openapi::ReferenceOr;
         
-        let mut schemas = HashMap::new();
+        let mut schemas = BTreeMap::new();
         
         // Add an inline schema
         schemas.insert("User".to_string(), ReferenceOr::new_item(Schema {
@@ -802,6 +1324,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         
         // Add a reference (though this is unusual in components)
@@ -810,6 +1333,8 @@ mod tests {
         
         let components = Components { 
             schemas,
+            parameters: None,
+            responses: None,
             security_schemes: None,
         };
         
@@ -824,7 +1349,7 @@ mod tests {
     fn test_schema_with_referenced_properties() {
         use crate::openapi::ReferenceOr;
         
-        let mut properties = HashMap::new();
+        let mut properties = BTreeMap::new();
         properties.insert("id".to_string(), ReferenceOr::new_item(Schema {
             schema_type: Some("integer".to_string()),
             title: None,
@@ -832,6 +1357,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         properties.insert("address".to_string(), 
             ReferenceOr::new_ref("#/components/schemas/Address"));
@@ -843,6 +1369,7 @@ mod tests {
             properties: Some(properties),
             required: Some(vec!["id".to_string()]),
             reference: None,
+            ..Default::default()
         };
         
         let json = serde_json::to_string(&schema).unwrap();
@@ -877,6 +1404,7 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         });
         
         let json = serde_json::to_string(&original).unwrap();
@@ -893,26 +1421,33 @@ mod tests {
         let mut api = OpenAPI::new("Reference Test API", "1.0.0");
         
         // Add a path with referenced schema
-        let mut content = HashMap::new();
+        let mut content = BTreeMap::new();
         content.insert("application/json".to_string(), MediaType {
             schema: Some(ReferenceOr::new_ref("#/components/schemas/User")),
         });
         
-        let mut responses = HashMap::new();
-        responses.insert("200".to_string(), Response {
+        let mut responses = BTreeMap::new();
+        responses.insert("200".to_string(), ReferenceOr::new_item(Response {
             description: "Success".to_string(),
+            headers: None,
+            links: None,
             content: Some(content),
-        });
+        }));
         
         let operation = Operation {
             summary: None,
             description: None,
+            external_docs: None,
+            operation_id: None,
             handler_function: None,
             tags: vec![],
             parameters: vec![],
             request_body: None,
             responses,
+            callbacks: None,
             security: None,
+            deprecated: false,
+            extensions: BTreeMap::new(),
         };
         
         let path_item = PathItem {
@@ -923,12 +1458,13 @@ mod tests {
             patch: None,
             head: None,
             options: None,
+            trace: None,
         };
         
         api.paths.insert("/users/{id}".to_string(), path_item);
         
         // Add components with schema definition
-        let mut schemas = HashMap::new();
+        let mut schemas = BTreeMap::new();
         schemas.insert("User".to_string(), ReferenceOr::new_item(Schema {
             schema_type: Some("object".to_string()),
             title: Some("User".to_string()),
@@ -936,10 +1472,13 @@ mod tests {
             properties: None,
             required: None,
             reference: None,
+            ..Default::default()
         }));
         
         api.components = Some(Components { 
             schemas,
+            parameters: None,
+            responses: None,
             security_schemes: None,
         });
         
@@ -957,4 +1496,215 @@ mod tests {
         let deserialized: OpenAPI = serde_json::from_str(&json).unwrap();
         assert_eq!(api, deserialized);
     }
-}
\ No newline at end of file
+    // ============================================================================
+    // Spec Diff Tests
+    // ============================================================================
+
+    /// A small users API whose CreateUser body has the given properties and required list
+    fn users_spec(extra_path: bool, properties: &[&str], required: &[&str]) -> OpenAPI {
+        let mut spec: OpenAPI = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Users", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "required": true,
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/CreateUser" } } }
+                        },
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                }
+            },
+            "components": { "schemas": { "CreateUser": { "type": "object" } } }
+        })).unwrap();
+
+        if extra_path {
+            spec.paths.insert("/users/{id}".to_string(), serde_json::from_value(serde_json::json!({
+                "get": { "responses": { "200": { "description": "OK" } } }
+            })).unwrap());
+        }
+
+        let schemas = &mut spec.components.as_mut().unwrap().schemas;
+        if let Some(ReferenceOr::Item(schema)) = schemas.get_mut("CreateUser") {
+            schema.properties = Some(properties.iter()
+                .map(|name| (name.to_string(), ReferenceOr::new_item(Schema { schema_type: Some("string".to_string()), ..Default::default() })))
+                .collect());
+            schema.required = Some(required.iter().map(|name| name.to_string()).collect());
+        }
+        spec
+    }
+
+    #[test]
+    fn test_diff_removed_path_is_breaking() {
+        let old = users_spec(true, &["name"], &["name"]);
+        let new = users_spec(false, &["name"], &["name"]);
+
+        let diff = crate::spec_diff::diff(&old, &new);
+        assert_eq!(diff.changes, vec![crate::spec_diff::SpecChange::PathRemoved { path: "/users/{id}".to_string() }]);
+        assert!(diff.is_breaking());
+
+        let reverse = crate::spec_diff::diff(&new, &old);
+        assert!(!reverse.is_breaking());
+        assert_eq!(reverse.additive().count(), 1);
+    }
+
+    #[test]
+    fn test_diff_added_optional_field_is_additive() {
+        let old = users_spec(false, &["name"], &["name"]);
+        let new = users_spec(false, &["name", "nickname"], &["name"]);
+
+        let diff = crate::spec_diff::diff(&old, &new);
+        assert_eq!(diff.changes, vec![crate::spec_diff::SpecChange::OptionalFieldAdded {
+            method: "POST".to_string(),
+            path: "/users".to_string(),
+            field: "nickname".to_string(),
+        }]);
+        assert!(!diff.is_breaking());
+    }
+
+    #[test]
+    fn test_diff_optional_field_made_required_is_breaking() {
+        let old = users_spec(false, &["name", "nickname"], &["name"]);
+        let new = users_spec(false, &["name", "nickname"], &["name", "nickname"]);
+
+        let diff = crate::spec_diff::diff(&old, &new);
+        let breaking: Vec<String> = diff.breaking().map(ToString::to_string).collect();
+        assert_eq!(breaking, vec!["POST /users now requires field 'nickname'"]);
+        assert!(crate::spec_diff::diff(&new, &new).is_empty());
+    }
+
+    // ============================================================================
+    // Markdown Export Tests
+    // ============================================================================
+
+    #[test]
+    fn test_markdown_sections_follow_declared_tags() {
+        let spec: OpenAPI = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Shop", "version": "2.0.0", "description": "Orders and stock" },
+            "tags": [{ "name": "orders", "description": "Placing orders" }, { "name": "stock" }],
+            "paths": {
+                "/health": { "get": { "summary": "Health check", "responses": { "200": { "description": "Up" } } } },
+                "/orders": {
+                    "post": {
+                        "summary": "Place an order",
+                        "tags": ["stock", "orders"],
+                        "deprecated": true,
+                        "responses": { "201": { "$ref": "#/components/responses/Created" } }
+                    }
+                },
+                "/stock/{sku}": {
+                    "get": {
+                        "summary": "Stock level",
+                        "tags": ["stock"],
+                        "parameters": [{ "$ref": "#/components/parameters/Sku" }],
+                        "responses": { "200": { "description": "Units | on hand" } }
+                    }
+                }
+            },
+            "components": {
+                "parameters": { "Sku": { "name": "sku", "in": "path", "required": true, "schema": { "type": "string" } } },
+                "responses": {
+                    "Created": {
+                        "description": "Created",
+                        "content": { "application/json": { "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Order" } } } }
+                    }
+                }
+            }
+        })).unwrap();
+
+        let markdown = crate::markdown::to_markdown(&spec);
+        let headings: Vec<&str> = markdown.lines().filter(|line| line.starts_with('#')).collect();
+        assert_eq!(headings, [
+            "# Shop 2.0.0",
+            "## orders",
+            "### Place an order",
+            "## stock",
+            "### Place an order",
+            "### Stock level",
+            "## Other operations",
+            "### Health check",
+        ]);
+        assert!(markdown.contains("\nPlacing orders\n"));
+        assert!(markdown.contains("`POST /orders` (deprecated)"));
+        assert!(markdown.contains("| 201 | Created | array of `Order` |"));
+        assert!(markdown.contains("| `sku` | path | string | yes |  |"));
+        assert!(markdown.contains("| 200 | Units \\| on hand |  |"));
+    }
+
+    // ============================================================================
+    // TypeScript Export Tests
+    // ============================================================================
+
+    #[cfg(feature = "typescript")]
+    fn typescript_spec(schemas: serde_json::Value) -> OpenAPI {
+        let mut spec = OpenAPI::new("Users", "1.0.0");
+        spec.components = Some(serde_json::from_value(serde_json::json!({ "schemas": schemas })).unwrap());
+        spec
+    }
+
+    #[cfg(feature = "typescript")]
+    #[test]
+    fn test_typescript_interface_for_user_schema() {
+        let spec = typescript_spec(serde_json::json!({
+            "User": {
+                "type": "object",
+                "description": "A registered user",
+                "required": ["id", "name", "roles"],
+                "properties": {
+                    "id": { "type": "integer", "format": "int64" },
+                    "name": { "type": "string", "description": "Display name" },
+                    "email": { "type": "string", "nullable": true },
+                    "active": { "type": "boolean" },
+                    "roles": { "type": "array", "items": { "$ref": "#/components/schemas/Role" } },
+                    "settings": { "type": "object", "additionalProperties": { "type": "string" } },
+                    "x-trace": { "type": "string" }
+                }
+            }
+        }));
+
+        assert_eq!(crate::typescript::to_typescript(&spec), r#"/** A registered user */
+export interface User {
+  active?: boolean;
+  email?: string | null;
+  id: number;
+  /** Display name */
+  name: string;
+  roles: Role[];
+  settings?: Record<string, string>;
+  "x-trace"?: string;
+}
+"#);
+    }
+
+    #[cfg(feature = "typescript")]
+    #[test]
+    fn test_typescript_aliases_for_enums_unions_and_flatten() {
+        let spec = typescript_spec(serde_json::json!({
+            "Role": { "type": "string", "enum": ["admin", "member"] },
+            "Event": {
+                "oneOf": [
+                    { "$ref": "#/components/schemas/Created" },
+                    { "type": "object", "required": ["kind"], "properties": { "kind": { "type": "string", "enum": ["deleted"] } } }
+                ]
+            },
+            "Page": {
+                "allOf": [{ "$ref": "#/components/schemas/Paging" }],
+                "type": "object",
+                "properties": { "items": { "type": "array", "items": { "type": "string", "nullable": true } } }
+            }
+        }));
+
+        assert_eq!(crate::typescript::to_typescript(&spec), r#"export type Event = Created | {
+  kind: "deleted";
+};
+
+export type Page = Paging & {
+  items?: Array<string | null>;
+};
+
+export type Role = "admin" | "member";
+"#);
+    }
+}
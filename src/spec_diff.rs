@@ -0,0 +1,199 @@
+//! Compare two generated specs to catch breaking API changes
+//!
+//! [`diff`] walks the paths of an old and a new [`OpenAPI`] document and
+//! reports what was added or removed. Each [`SpecChange`] knows whether it is
+//! likely to break existing clients, so a CI job can fail on
+//! [`SpecDiff::is_breaking`] while still listing the additive changes.
+
+use crate::openapi::{OpenAPI, Operation, PathItem, ReferenceOr, Schema};
+use std::collections::BTreeSet;
+
+/// A single difference between two specs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecChange {
+    /// A path exists only in the new spec
+    PathAdded { path: String },
+    /// A path exists only in the old spec
+    PathRemoved { path: String },
+    /// A method was added to a path present in both specs
+    OperationAdded { method: String, path: String },
+    /// A method was removed from a path present in both specs
+    OperationRemoved { method: String, path: String },
+    /// An operation gained a documented response status
+    ResponseAdded { method: String, path: String, status: String },
+    /// An operation no longer documents a response status
+    ResponseRemoved { method: String, path: String, status: String },
+    /// A request body field is new and optional
+    OptionalFieldAdded { method: String, path: String, field: String },
+    /// A request body field is new and required, or was optional before
+    RequiredFieldAdded { method: String, path: String, field: String },
+}
+
+impl SpecChange {
+    /// Whether clients built against the old spec may fail against the new one
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            SpecChange::PathRemoved { .. }
+                | SpecChange::OperationRemoved { .. }
+                | SpecChange::ResponseRemoved { .. }
+                | SpecChange::RequiredFieldAdded { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for SpecChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecChange::PathAdded { path } => write!(f, "path {path} was added"),
+            SpecChange::PathRemoved { path } => write!(f, "path {path} was removed"),
+            SpecChange::OperationAdded { method, path } => write!(f, "{method} {path} was added"),
+            SpecChange::OperationRemoved { method, path } => write!(f, "{method} {path} was removed"),
+            SpecChange::ResponseAdded { method, path, status } => {
+                write!(f, "{method} {path} now documents a {status} response")
+            }
+            SpecChange::ResponseRemoved { method, path, status } => {
+                write!(f, "{method} {path} no longer documents a {status} response")
+            }
+            SpecChange::OptionalFieldAdded { method, path, field } => {
+                write!(f, "{method} {path} accepts a new optional field '{field}'")
+            }
+            SpecChange::RequiredFieldAdded { method, path, field } => {
+                write!(f, "{method} {path} now requires field '{field}'")
+            }
+        }
+    }
+}
+
+/// Every change found between two specs, in path and method order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecDiff {
+    pub changes: Vec<SpecChange>,
+}
+
+impl SpecDiff {
+    /// True when at least one change may break existing clients
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(SpecChange::is_breaking)
+    }
+
+    /// Changes that may break existing clients
+    pub fn breaking(&self) -> impl Iterator<Item = &SpecChange> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+
+    /// Changes that only add to the API
+    pub fn additive(&self) -> impl Iterator<Item = &SpecChange> {
+        self.changes.iter().filter(|change| !change.is_breaking())
+    }
+
+    /// True when the specs describe the same paths, methods, responses and body fields
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Compare an old spec with a new one
+///
+/// Request body fields are compared on the `application/json` schema, following
+/// `$ref`s into each spec's own components.
+pub fn diff(old: &OpenAPI, new: &OpenAPI) -> SpecDiff {
+    let mut changes = Vec::new();
+
+    for path in old.paths.keys().filter(|path| !new.paths.contains_key(*path)) {
+        changes.push(SpecChange::PathRemoved { path: path.clone() });
+    }
+
+    for (path, new_item) in &new.paths {
+        let Some(old_item) = old.paths.get(path) else {
+            changes.push(SpecChange::PathAdded { path: path.clone() });
+            continue;
+        };
+
+        for (method, old_op, new_op) in paired_operations(old_item, new_item) {
+            let method = method.to_string();
+            match (old_op, new_op) {
+                (Some(_), None) => changes.push(SpecChange::OperationRemoved { method, path: path.clone() }),
+                (None, Some(_)) => changes.push(SpecChange::OperationAdded { method, path: path.clone() }),
+                (Some(old_op), Some(new_op)) => {
+                    diff_operation(&method, path, (old, old_op), (new, new_op), &mut changes);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    SpecDiff { changes }
+}
+
+fn paired_operations<'a>(
+    old: &'a PathItem,
+    new: &'a PathItem,
+) -> impl Iterator<Item = (&'static str, Option<&'a Operation>, Option<&'a Operation>)> {
+    [
+        ("GET", old.get.as_ref(), new.get.as_ref()),
+        ("POST", old.post.as_ref(), new.post.as_ref()),
+        ("PUT", old.put.as_ref(), new.put.as_ref()),
+        ("PATCH", old.patch.as_ref(), new.patch.as_ref()),
+        ("DELETE", old.delete.as_ref(), new.delete.as_ref()),
+        ("HEAD", old.head.as_ref(), new.head.as_ref()),
+        ("OPTIONS", old.options.as_ref(), new.options.as_ref()),
+        ("TRACE", old.trace.as_ref(), new.trace.as_ref()),
+    ]
+    .into_iter()
+}
+
+fn diff_operation(
+    method: &str,
+    path: &str,
+    (old_spec, old_op): (&OpenAPI, &Operation),
+    (new_spec, new_op): (&OpenAPI, &Operation),
+    changes: &mut Vec<SpecChange>,
+) {
+    for status in old_op.responses.keys().filter(|status| !new_op.responses.contains_key(*status)) {
+        changes.push(SpecChange::ResponseRemoved { method: method.to_string(), path: path.to_string(), status: status.clone() });
+    }
+    for status in new_op.responses.keys().filter(|status| !old_op.responses.contains_key(*status)) {
+        changes.push(SpecChange::ResponseAdded { method: method.to_string(), path: path.to_string(), status: status.clone() });
+    }
+
+    let (old_fields, old_required) = request_body_fields(old_spec, old_op);
+    let (new_fields, new_required) = request_body_fields(new_spec, new_op);
+    for field in &new_fields {
+        if new_required.contains(field) && !old_required.contains(field) {
+            changes.push(SpecChange::RequiredFieldAdded { method: method.to_string(), path: path.to_string(), field: field.clone() });
+        } else if !old_fields.contains(field) {
+            changes.push(SpecChange::OptionalFieldAdded { method: method.to_string(), path: path.to_string(), field: field.clone() });
+        }
+    }
+}
+
+/// Property names and required property names of an operation's JSON request body
+fn request_body_fields(spec: &OpenAPI, operation: &Operation) -> (BTreeSet<String>, BTreeSet<String>) {
+    let schema = operation.request_body.as_ref()
+        .and_then(|body| body.content.get("application/json"))
+        .and_then(|media| media.schema.as_ref())
+        .and_then(|schema| resolve(spec, schema));
+
+    match schema {
+        Some(schema) => (
+            schema.properties.iter().flat_map(|properties| properties.keys().cloned()).collect(),
+            schema.required.iter().flatten().cloned().collect(),
+        ),
+        None => Default::default(),
+    }
+}
+
+/// Follow a `$ref` into the spec's component schemas
+fn resolve<'a>(spec: &'a OpenAPI, schema: &'a ReferenceOr<Schema>) -> Option<&'a Schema> {
+    let reference = match schema {
+        ReferenceOr::Reference { reference } => reference,
+        ReferenceOr::Item(Schema { reference: Some(reference), .. }) => reference,
+        ReferenceOr::Item(schema) => return Some(schema),
+    };
+    let name = reference.strip_prefix("#/components/schemas/")?;
+    match spec.components.as_ref()?.schemas.get(name)? {
+        ReferenceOr::Item(schema) if schema.reference.is_none() => Some(schema),
+        _ => None,
+    }
+}
@@ -0,0 +1,141 @@
+//! TypeScript declarations for the schemas of a generated spec
+//!
+//! [`to_typescript`] emits one declaration per entry in `components.schemas`:
+//! an `interface` for plain object schemas and a `type` alias for the rest,
+//! such as string enums, `oneOf` unions and flattened `allOf` structs. It
+//! covers the shapes `#[derive(OpenApiSchema)]` produces, not all of JSON Schema.
+
+use crate::openapi::{OpenAPI, ReferenceOr, Schema};
+
+/// Declarations for every component schema, in name order
+pub fn to_typescript(spec: &OpenAPI) -> String {
+    let Some(components) = &spec.components else {
+        return String::new();
+    };
+
+    components
+        .schemas
+        .iter()
+        .map(|(name, schema)| declaration(name, schema))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `export interface Name { ... }` for an object schema, `export type Name = ...;` otherwise
+fn declaration(name: &str, schema: &ReferenceOr<Schema>) -> String {
+    let doc = schema.as_item().and_then(|schema| schema.description.as_deref()).map(|description| doc_comment(description, ""));
+    let body = match schema.as_item() {
+        Some(item) if is_plain_object(item) => format!("export interface {name} {}\n", object_type(item, "")),
+        _ => format!("export type {name} = {};\n", ts_type(schema, "")),
+    };
+    format!("{}{body}", doc.unwrap_or_default())
+}
+
+/// An object with properties and nothing that would need an intersection or union
+fn is_plain_object(schema: &Schema) -> bool {
+    schema.properties.is_some() && schema.all_of.is_none() && schema.one_of.is_none() && schema.nullable != Some(true)
+}
+
+/// The TypeScript type for a schema, with nested object members indented past `indent`
+fn ts_type(schema: &ReferenceOr<Schema>, indent: &str) -> String {
+    let schema = match schema {
+        ReferenceOr::Reference { reference } => return reference.rsplit('/').next().unwrap_or(reference).to_string(),
+        ReferenceOr::Item(schema) => schema,
+    };
+
+    let base = if let Some(values) = &schema.enum_values {
+        values.iter().map(ToString::to_string).collect::<Vec<_>>().join(" | ")
+    } else if let Some(variants) = &schema.one_of {
+        variants.iter().map(|variant| ts_type(variant, indent)).collect::<Vec<_>>().join(" | ")
+    } else if let Some(parts) = &schema.all_of {
+        let mut parts: Vec<String> = parts.iter().map(|part| ts_type(part, indent)).collect();
+        // Fields declared next to the flattened ones
+        if schema.properties.is_some() {
+            parts.push(object_type(schema, indent));
+        }
+        parts.join(" & ")
+    } else {
+        match schema.schema_type.as_deref() {
+            Some("string") => "string".to_string(),
+            Some("integer" | "number") => "number".to_string(),
+            Some("boolean") => "boolean".to_string(),
+            Some("null") => "null".to_string(),
+            Some("array") => match &schema.items {
+                Some(items) => array_of(ts_type(items, indent)),
+                None => "unknown[]".to_string(),
+            },
+            Some("object") | None if schema.properties.is_some() => object_type(schema, indent),
+            Some("object") => match &schema.additional_properties {
+                Some(values) => format!("Record<string, {}>", ts_type(values, indent)),
+                None => "Record<string, unknown>".to_string(),
+            },
+            _ => "unknown".to_string(),
+        }
+    };
+
+    if schema.nullable == Some(true) && base != "null" {
+        format!("{} | null", parenthesize(base))
+    } else {
+        base
+    }
+}
+
+/// `{ ... }` listing an object's properties, optional unless the schema requires them
+fn object_type(schema: &Schema, indent: &str) -> String {
+    let required = schema.required.as_deref().unwrap_or_default();
+    let member_indent = format!("{indent}  ");
+    let members: String = schema
+        .properties
+        .iter()
+        .flatten()
+        .map(|(name, property)| {
+            let doc = property
+                .as_item()
+                .and_then(|property| property.description.as_deref())
+                .map(|description| doc_comment(description, &member_indent))
+                .unwrap_or_default();
+            let optional = if required.contains(name) { "" } else { "?" };
+            format!("{doc}{member_indent}{}{optional}: {};\n", property_name(name), ts_type(property, &member_indent))
+        })
+        .collect();
+    if members.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{members}{indent}}}")
+    }
+}
+
+/// `T[]`, or `Array<T>` when `T` is a union or intersection
+fn array_of(item: String) -> String {
+    if item.contains(['|', '&']) {
+        format!("Array<{item}>")
+    } else {
+        format!("{item}[]")
+    }
+}
+
+/// Wrap a union or intersection in parentheses so another operator can follow it
+fn parenthesize(ts_type: String) -> String {
+    if ts_type.contains(['|', '&']) {
+        format!("({ts_type})")
+    } else {
+        ts_type
+    }
+}
+
+/// A property name as written in an interface, quoted when it isn't an identifier
+fn property_name(name: &str) -> String {
+    let is_identifier = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap_or_else(|_| format!("\"{name}\""))
+    }
+}
+
+/// A `/** ... */` line, kept from closing early by a `*/` in the description
+fn doc_comment(description: &str, indent: &str) -> String {
+    let description = description.replace("*/", "*\\/").replace('\n', " ");
+    format!("{indent}/** {description} */\n")
+}